@@ -0,0 +1,682 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use chrono::{Duration, Utc};
+use domain::entities::{ApiKeyScope, ParticipantRole};
+use domain::error::{DomainError, Result};
+use hmac::{Hmac, Mac};
+use jsonwebtoken::{errors::ErrorKind, Algorithm, DecodingKey, Validation};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+/// Shortest secret accepted for signing access tokens
+pub const MIN_SECRET_BYTES: usize = 32;
+
+/// What an access token grants: `role` acting as `email` on resource `sub`,
+/// an RFQ, campaign or (for a manufacturer's inbox) manufacturer id, until
+/// `exp` (Unix seconds)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccessClaims {
+    pub sub: String,
+    pub role: ParticipantRole,
+    pub email: String,
+    pub exp: i64,
+}
+
+/// Issues and verifies HMAC-SHA256 signed capability tokens, so a buyer or
+/// manufacturer can open an RFQ from the link in an email without an account.
+///
+/// A token is `{claims}.{signature}`, both base64url without padding. It
+/// only grants access to the one resource it was issued for, which
+/// `Principal` enforces.
+pub struct AccessTokens {
+    secret: Vec<u8>,
+    ttl: Duration,
+}
+
+impl AccessTokens {
+    pub fn new(secret: impl Into<Vec<u8>>, ttl: Duration) -> Result<Self> {
+        let secret = secret.into();
+        if secret.len() < MIN_SECRET_BYTES {
+            return Err(DomainError::InvalidInput(format!(
+                "Access token secret must be at least {} bytes",
+                MIN_SECRET_BYTES
+            )));
+        }
+        Ok(Self { secret, ttl })
+    }
+
+    /// Signer with a secret of its own, for a single process whose tokens
+    /// need not outlive it
+    pub fn with_random_secret(ttl: Duration) -> Self {
+        let secret = [Uuid::new_v4(), Uuid::new_v4()]
+            .iter()
+            .flat_map(|id| id.into_bytes())
+            .collect();
+        Self { secret, ttl }
+    }
+
+    /// Sign a token letting `email` act as `role` on `resource_id`
+    pub fn issue(&self, resource_id: &str, role: ParticipantRole, email: &str) -> String {
+        let claims = AccessClaims {
+            sub: resource_id.to_string(),
+            role,
+            email: email.to_string(),
+            exp: (Utc::now() + self.ttl).timestamp(),
+        };
+        let payload = URL_SAFE_NO_PAD
+            .encode(serde_json::to_vec(&claims).expect("access claims always serialize"));
+        let signature = URL_SAFE_NO_PAD.encode(self.mac(&payload).finalize().into_bytes());
+        format!("{}.{}", payload, signature)
+    }
+
+    /// Check the signature and expiry of `token`
+    pub fn verify(&self, token: &str) -> Result<AccessClaims> {
+        let invalid = || DomainError::Unauthorized("Invalid access token".to_string());
+
+        let (payload, signature) = token.split_once('.').ok_or_else(invalid)?;
+        let signature = URL_SAFE_NO_PAD.decode(signature).map_err(|_| invalid())?;
+        self.mac(payload)
+            .verify_slice(&signature)
+            .map_err(|_| invalid())?;
+
+        let claims: AccessClaims = URL_SAFE_NO_PAD
+            .decode(payload)
+            .ok()
+            .and_then(|claims| serde_json::from_slice(&claims).ok())
+            .ok_or_else(invalid)?;
+        if claims.exp <= Utc::now().timestamp() {
+            return Err(DomainError::Unauthorized(
+                "Access token has expired".to_string(),
+            ));
+        }
+        Ok(claims)
+    }
+
+    fn mac(&self, payload: &str) -> Hmac<Sha256> {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.secret).expect("HMAC accepts keys of any length");
+        mac.update(payload.as_bytes());
+        mac
+    }
+}
+
+/// Issuer and keys for admin JWTs. At least one of the HS256 secret and
+/// the RS256 public key (PEM) must be given; the token header picks which.
+#[derive(Debug, Clone)]
+pub struct AdminJwtConfig {
+    pub issuer: String,
+    pub audience: String,
+    pub hs256_secret: Option<String>,
+    pub rs256_public_key: Option<String>,
+}
+
+/// How an admin request was authenticated
+#[derive(Debug, Clone, PartialEq)]
+pub enum AdminCredential {
+    /// One of the configured static bearer secrets
+    ApiKey,
+    /// A JWT from the configured issuer, valid until `exp` (Unix seconds)
+    Jwt { issuer: String, exp: i64 },
+}
+
+/// The verified identity behind an admin request. `subject` is the JWT
+/// `sub`, or `api-key:` and a fingerprint of the secret used.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdminClaims {
+    pub subject: String,
+    pub credential: AdminCredential,
+}
+
+#[derive(Deserialize)]
+struct JwtClaims {
+    sub: String,
+    iss: String,
+    exp: i64,
+}
+
+/// Verifies platform operator credentials on admin routes: static bearer
+/// secrets, several of which may be valid at once so one can be rotated
+/// out without downtime, and HS256/RS256 JWTs with issuer, audience and
+/// expiry checks.
+///
+/// Static secrets are only kept as SHA-256 digests.
+pub struct AdminCredentials {
+    api_keys: Vec<[u8; 32]>,
+    jwt: Option<AdminJwt>,
+}
+
+struct AdminJwt {
+    issuer: String,
+    audience: String,
+    hs256: Option<DecodingKey>,
+    rs256: Option<DecodingKey>,
+}
+
+impl AdminCredentials {
+    pub fn new(api_keys: &[String], jwt: Option<AdminJwtConfig>) -> Result<Self> {
+        if api_keys.iter().any(|key| key.len() < MIN_SECRET_BYTES) {
+            return Err(DomainError::InvalidInput(format!(
+                "Admin API keys must be at least {} bytes",
+                MIN_SECRET_BYTES
+            )));
+        }
+        let jwt = jwt.map(AdminJwt::new).transpose()?;
+
+        Ok(Self {
+            api_keys: api_keys.iter().map(|key| digest(key)).collect(),
+            jwt,
+        })
+    }
+
+    /// Whether any credential would be accepted at all
+    pub fn is_configured(&self) -> bool {
+        !self.api_keys.is_empty() || self.jwt.is_some()
+    }
+
+    /// Check `token` against the static secrets, then as a JWT
+    pub fn verify(&self, token: &str) -> Result<AdminClaims> {
+        let token_digest = digest(token);
+        if self.api_keys.contains(&token_digest) {
+            let fingerprint: String = token_digest[..4]
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect();
+            return Ok(AdminClaims {
+                subject: format!("api-key:{}", fingerprint),
+                credential: AdminCredential::ApiKey,
+            });
+        }
+
+        match &self.jwt {
+            Some(jwt) => jwt.verify(token),
+            None => Err(DomainError::Unauthorized(
+                "Invalid admin credentials".to_string(),
+            )),
+        }
+    }
+}
+
+impl AdminJwt {
+    fn new(config: AdminJwtConfig) -> Result<Self> {
+        let hs256 = match &config.hs256_secret {
+            Some(secret) if secret.len() < MIN_SECRET_BYTES => {
+                return Err(DomainError::InvalidInput(format!(
+                    "Admin JWT secret must be at least {} bytes",
+                    MIN_SECRET_BYTES
+                )))
+            }
+            Some(secret) => Some(DecodingKey::from_secret(secret.as_bytes())),
+            None => None,
+        };
+        let rs256 = config
+            .rs256_public_key
+            .as_deref()
+            .map(|pem| {
+                DecodingKey::from_rsa_pem(pem.as_bytes()).map_err(|e| {
+                    DomainError::InvalidInput(format!("Invalid admin JWT public key: {}", e))
+                })
+            })
+            .transpose()?;
+        if hs256.is_none() && rs256.is_none() {
+            return Err(DomainError::InvalidInput(
+                "Admin JWT needs an HS256 secret or an RS256 public key".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            issuer: config.issuer,
+            audience: config.audience,
+            hs256,
+            rs256,
+        })
+    }
+
+    fn verify(&self, token: &str) -> Result<AdminClaims> {
+        let invalid = || DomainError::Unauthorized("Invalid admin credentials".to_string());
+
+        let header = jsonwebtoken::decode_header(token).map_err(|_| invalid())?;
+        let key = match header.alg {
+            Algorithm::HS256 => self.hs256.as_ref(),
+            Algorithm::RS256 => self.rs256.as_ref(),
+            _ => None,
+        }
+        .ok_or_else(invalid)?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&self.issuer]);
+        validation.set_audience(&[&self.audience]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+
+        let claims = jsonwebtoken::decode::<JwtClaims>(token, key, &validation)
+            .map_err(|e| match e.kind() {
+                ErrorKind::ExpiredSignature => {
+                    DomainError::Unauthorized("Admin token has expired".to_string())
+                }
+                _ => invalid(),
+            })?
+            .claims;
+
+        Ok(AdminClaims {
+            subject: claims.sub,
+            credential: AdminCredential::Jwt {
+                issuer: claims.iss,
+                exp: claims.exp,
+            },
+        })
+    }
+}
+
+fn digest(secret: &str) -> [u8; 32] {
+    Sha256::digest(secret.as_bytes()).into()
+}
+
+/// Who is making a request, as established by its credentials. What each
+/// may do is decided by `policy`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Principal {
+    /// No credentials at all, on routes open to the public
+    Anonymous,
+    /// Buyer holding an access token for one RFQ or campaign
+    Buyer { email: String, resource_id: String },
+    /// Manufacturer contact holding an access token for one RFQ or for the
+    /// manufacturer's inbox
+    ManufacturerUser { email: String, resource_id: String },
+    /// A tenant's own systems, through one of its API keys
+    TenantKey {
+        tenant_id: String,
+        key_id: String,
+        scopes: Vec<ApiKeyScope>,
+    },
+    /// Platform operator
+    Admin { subject: String },
+    /// The platform itself, e.g. maintenance jobs
+    System,
+}
+
+impl From<AccessClaims> for Principal {
+    fn from(claims: AccessClaims) -> Self {
+        match claims.role {
+            ParticipantRole::Buyer => Principal::Buyer {
+                email: claims.email,
+                resource_id: claims.sub,
+            },
+            ParticipantRole::Manufacturer => Principal::ManufacturerUser {
+                email: claims.email,
+                resource_id: claims.sub,
+            },
+        }
+    }
+}
+
+impl From<AdminClaims> for Principal {
+    fn from(claims: AdminClaims) -> Self {
+        Principal::Admin {
+            subject: claims.subject,
+        }
+    }
+}
+
+impl Principal {
+    /// Email and role of a principal acting as an RFQ participant
+    pub fn participant(&self) -> Option<(&str, ParticipantRole)> {
+        match self {
+            Principal::Buyer { email, .. } => Some((email, ParticipantRole::Buyer)),
+            Principal::ManufacturerUser { email, .. } => {
+                Some((email, ParticipantRole::Manufacturer))
+            }
+            Principal::Anonymous
+            | Principal::TenantKey { .. }
+            | Principal::Admin { .. }
+            | Principal::System => None,
+        }
+    }
+
+    /// Stable string telling this principal apart from others, e.g. to keep
+    /// one caller's idempotency records from being replayed to another
+    pub fn identity(&self) -> String {
+        match self {
+            Principal::Anonymous => "anonymous".to_string(),
+            Principal::Buyer { email, resource_id } => {
+                format!("buyer:{}:{}", resource_id, email.to_lowercase())
+            }
+            Principal::ManufacturerUser { email, resource_id } => {
+                format!("manufacturer:{}:{}", resource_id, email.to_lowercase())
+            }
+            Principal::TenantKey {
+                tenant_id, key_id, ..
+            } => format!("key:{}:{}", tenant_id, key_id),
+            Principal::Admin { subject } => format!("admin:{}", subject),
+            Principal::System => "system".to_string(),
+        }
+    }
+
+    /// Whether the principal's credentials cover `resource_id`; tenant keys,
+    /// admins and the system are not limited to one resource
+    fn holds_access_to(&self, resource_id: &str) -> bool {
+        match self {
+            Principal::Buyer {
+                resource_id: id, ..
+            }
+            | Principal::ManufacturerUser {
+                resource_id: id, ..
+            } => id == resource_id,
+            Principal::TenantKey { .. } | Principal::Admin { .. } | Principal::System => true,
+            Principal::Anonymous => false,
+        }
+    }
+
+    /// Reject credentials issued for another resource before anything about
+    /// `resource_id`, even whether it exists, is revealed
+    pub fn check_access_to(&self, resource_id: &str) -> Result<()> {
+        if *self == Principal::Anonymous {
+            return Err(DomainError::Unauthorized(
+                "Missing access token".to_string(),
+            ));
+        }
+        if !self.holds_access_to(resource_id) {
+            return Err(DomainError::Forbidden(
+                "Access token was issued for a different resource".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &[u8] = b"0123456789abcdef0123456789abcdef";
+
+    fn tokens() -> AccessTokens {
+        AccessTokens::new(SECRET, Duration::days(1)).unwrap()
+    }
+
+    fn is_unauthorized(result: Result<AccessClaims>) -> bool {
+        matches!(result, Err(DomainError::Unauthorized(_)))
+    }
+
+    #[test]
+    fn issued_tokens_verify_to_their_claims() {
+        let token = tokens().issue("r_1", ParticipantRole::Buyer, "buyer@example.com");
+
+        let claims = tokens().verify(&token).unwrap();
+        assert_eq!(claims.sub, "r_1");
+        assert_eq!(claims.role, ParticipantRole::Buyer);
+        assert_eq!(claims.email, "buyer@example.com");
+        assert!(claims.exp > Utc::now().timestamp());
+    }
+
+    #[test]
+    fn tampered_tokens_are_rejected() {
+        let token = tokens().issue("r_1", ParticipantRole::Buyer, "buyer@example.com");
+        let (payload, signature) = token.split_once('.').unwrap();
+
+        // Same claims for another RFQ and role, under the original signature
+        let mut claims = tokens().verify(&token).unwrap();
+        claims.sub = "r_2".to_string();
+        claims.role = ParticipantRole::Manufacturer;
+        let forged = URL_SAFE_NO_PAD.encode(serde_json::to_vec(&claims).unwrap());
+
+        let mut flipped = URL_SAFE_NO_PAD.decode(signature).unwrap();
+        flipped[0] ^= 1;
+
+        for tampered in [
+            format!("{}.{}", forged, signature),
+            format!("{}.{}", payload, URL_SAFE_NO_PAD.encode(flipped)),
+            format!("{}.", payload),
+            payload.to_string(),
+            format!("{}.not base64!", payload),
+            String::new(),
+        ] {
+            assert!(is_unauthorized(tokens().verify(&tampered)), "{}", tampered);
+        }
+    }
+
+    #[test]
+    fn tokens_from_another_secret_are_rejected() {
+        let other = AccessTokens::new(
+            b"another secret of thirty-two bytes".to_vec(),
+            Duration::days(1),
+        )
+        .unwrap();
+        let token = other.issue("r_1", ParticipantRole::Buyer, "buyer@example.com");
+        assert!(is_unauthorized(tokens().verify(&token)));
+
+        let random = AccessTokens::with_random_secret(Duration::days(1));
+        assert!(is_unauthorized(random.verify(&tokens().issue(
+            "r_1",
+            ParticipantRole::Buyer,
+            "buyer@example.com"
+        ))));
+    }
+
+    #[test]
+    fn expired_tokens_are_rejected() {
+        for ttl in [Duration::zero(), Duration::seconds(-60)] {
+            let tokens = AccessTokens::new(SECRET, ttl).unwrap();
+            let token = tokens.issue("r_1", ParticipantRole::Buyer, "buyer@example.com");
+            assert!(matches!(
+                tokens.verify(&token),
+                Err(DomainError::Unauthorized(message)) if message.contains("expired")
+            ));
+        }
+    }
+
+    #[test]
+    fn tokens_only_grant_their_resource_and_role() {
+        let token = tokens().issue("r_1", ParticipantRole::Manufacturer, "sales@acme.example");
+        let principal = Principal::from(tokens().verify(&token).unwrap());
+
+        assert_eq!(
+            principal.participant(),
+            Some(("sales@acme.example", ParticipantRole::Manufacturer))
+        );
+        assert!(principal.check_access_to("r_1").is_ok());
+        assert!(matches!(
+            principal.check_access_to("r_2"),
+            Err(DomainError::Forbidden(_))
+        ));
+
+        let buyer = Principal::from(
+            tokens()
+                .verify(&tokens().issue("r_1", ParticipantRole::Buyer, "buyer@example.com"))
+                .unwrap(),
+        );
+        assert_ne!(buyer.identity(), principal.identity());
+        assert!(matches!(buyer, Principal::Buyer { .. }));
+    }
+
+    #[test]
+    fn short_secrets_are_refused() {
+        for length in [0, 1, MIN_SECRET_BYTES - 1] {
+            assert!(matches!(
+                AccessTokens::new(vec![b'x'; length], Duration::days(1)),
+                Err(DomainError::InvalidInput(_))
+            ));
+        }
+        assert!(AccessTokens::new(vec![b'x'; MIN_SECRET_BYTES], Duration::days(1)).is_ok());
+    }
+
+    const ISSUER: &str = "https://auth.example.com/";
+    const AUDIENCE: &str = "rfq-admin";
+    const HS256_SECRET: &str = "admin jwt secret of at least 32 bytes";
+    const RS256_PRIVATE_KEY: &str = include_str!("../tests/fixtures/admin_jwt_rs256.key.pem");
+    const RS256_PUBLIC_KEY: &str = include_str!("../tests/fixtures/admin_jwt_rs256.pub.pem");
+
+    fn admin(api_keys: &[&str], hs256: bool, rs256: bool) -> AdminCredentials {
+        let api_keys: Vec<String> = api_keys.iter().map(|key| key.to_string()).collect();
+        let jwt = (hs256 || rs256).then(|| AdminJwtConfig {
+            issuer: ISSUER.to_string(),
+            audience: AUDIENCE.to_string(),
+            hs256_secret: hs256.then(|| HS256_SECRET.to_string()),
+            rs256_public_key: rs256.then(|| RS256_PUBLIC_KEY.to_string()),
+        });
+        AdminCredentials::new(&api_keys, jwt).unwrap()
+    }
+
+    fn claims(iss: &str, aud: &str, exp: i64) -> serde_json::Value {
+        serde_json::json!({ "sub": "ops@example.com", "iss": iss, "aud": aud, "exp": exp })
+    }
+
+    fn valid_claims() -> serde_json::Value {
+        claims(
+            ISSUER,
+            AUDIENCE,
+            (Utc::now() + Duration::hours(1)).timestamp(),
+        )
+    }
+
+    fn jwt(
+        algorithm: Algorithm,
+        key: &jsonwebtoken::EncodingKey,
+        claims: &serde_json::Value,
+    ) -> String {
+        jsonwebtoken::encode(&jsonwebtoken::Header::new(algorithm), claims, key).unwrap()
+    }
+
+    fn hs256(claims: &serde_json::Value) -> String {
+        jwt(
+            Algorithm::HS256,
+            &jsonwebtoken::EncodingKey::from_secret(HS256_SECRET.as_bytes()),
+            claims,
+        )
+    }
+
+    fn rs256(claims: &serde_json::Value) -> String {
+        jwt(
+            Algorithm::RS256,
+            &jsonwebtoken::EncodingKey::from_rsa_pem(RS256_PRIVATE_KEY.as_bytes()).unwrap(),
+            claims,
+        )
+    }
+
+    fn rejected(result: Result<AdminClaims>) -> bool {
+        matches!(result, Err(DomainError::Unauthorized(_)))
+    }
+
+    #[test]
+    fn admin_jwts_from_the_issuer_are_accepted() {
+        let credentials = admin(&[], true, true);
+        for token in [hs256(&valid_claims()), rs256(&valid_claims())] {
+            let claims = credentials.verify(&token).unwrap();
+            assert_eq!(claims.subject, "ops@example.com");
+            assert!(matches!(
+                claims.credential,
+                AdminCredential::Jwt { ref issuer, .. } if issuer == ISSUER
+            ));
+        }
+    }
+
+    #[test]
+    fn admin_jwts_for_another_issuer_or_audience_are_rejected() {
+        let credentials = admin(&[], true, true);
+        let exp = (Utc::now() + Duration::hours(1)).timestamp();
+        for claims in [
+            claims("https://evil.example.com/", AUDIENCE, exp),
+            claims(ISSUER, "other-app", exp),
+            serde_json::json!({ "iss": ISSUER, "aud": AUDIENCE, "exp": exp }),
+            serde_json::json!({ "sub": "ops@example.com", "iss": ISSUER, "aud": AUDIENCE }),
+        ] {
+            assert!(rejected(credentials.verify(&hs256(&claims))), "{}", claims);
+            assert!(rejected(credentials.verify(&rs256(&claims))), "{}", claims);
+        }
+    }
+
+    #[test]
+    fn expired_admin_jwts_are_rejected() {
+        let credentials = admin(&[], true, true);
+        // Past the default 60 second leeway
+        let expired = claims(
+            ISSUER,
+            AUDIENCE,
+            (Utc::now() - Duration::minutes(5)).timestamp(),
+        );
+        for token in [hs256(&expired), rs256(&expired)] {
+            assert!(matches!(
+                credentials.verify(&token),
+                Err(DomainError::Unauthorized(message)) if message.contains("expired")
+            ));
+        }
+    }
+
+    #[test]
+    fn admin_jwts_cannot_switch_algorithms() {
+        // HS256 signed with the RSA public key, which an HS256 verifier
+        // handed the RS256 key would accept
+        let confused = jwt(
+            Algorithm::HS256,
+            &jsonwebtoken::EncodingKey::from_secret(RS256_PUBLIC_KEY.as_bytes()),
+            &valid_claims(),
+        );
+        assert!(rejected(admin(&[], false, true).verify(&confused)));
+        assert!(rejected(admin(&[], true, true).verify(&confused)));
+
+        // Each algorithm only verifies with its own configured key
+        assert!(rejected(
+            admin(&[], false, true).verify(&hs256(&valid_claims()))
+        ));
+        assert!(rejected(
+            admin(&[], true, false).verify(&rs256(&valid_claims()))
+        ));
+
+        let hs512 = jwt(
+            Algorithm::HS512,
+            &jsonwebtoken::EncodingKey::from_secret(HS256_SECRET.as_bytes()),
+            &valid_claims(),
+        );
+        assert!(rejected(admin(&[], true, true).verify(&hs512)));
+
+        let header = URL_SAFE_NO_PAD.encode(br#"{"alg":"none","typ":"JWT"}"#);
+        let payload = URL_SAFE_NO_PAD.encode(valid_claims().to_string());
+        let unsigned = format!("{}.{}.", header, payload);
+        assert!(rejected(admin(&[], true, true).verify(&unsigned)));
+    }
+
+    #[test]
+    fn both_static_keys_work_while_one_is_rotated_out() {
+        let old = "old admin key, at least thirty-two bytes";
+        let new = "new admin key, at least thirty-two bytes";
+
+        let rotating = admin(&[old, new], false, false);
+        let old_claims = rotating.verify(old).unwrap();
+        let new_claims = rotating.verify(new).unwrap();
+        assert_eq!(old_claims.credential, AdminCredential::ApiKey);
+        assert_eq!(new_claims.credential, AdminCredential::ApiKey);
+        assert_ne!(old_claims.subject, new_claims.subject);
+        assert!(!old_claims.subject.contains(old));
+
+        let rotated = admin(&[new], false, false);
+        assert!(rejected(rotated.verify(old)));
+        assert_eq!(rotated.verify(new).unwrap(), new_claims);
+        assert!(rejected(rotated.verify(&format!("{}x", new))));
+    }
+
+    #[test]
+    fn admin_credentials_refuse_weak_or_missing_keys() {
+        assert!(matches!(
+            AdminCredentials::new(&["short".to_string()], None),
+            Err(DomainError::InvalidInput(_))
+        ));
+        let jwt = |hs256_secret: Option<&str>, rs256_public_key: Option<&str>| AdminJwtConfig {
+            issuer: ISSUER.to_string(),
+            audience: AUDIENCE.to_string(),
+            hs256_secret: hs256_secret.map(str::to_string),
+            rs256_public_key: rs256_public_key.map(str::to_string),
+        };
+        for config in [
+            jwt(None, None),
+            jwt(Some("short"), None),
+            jwt(None, Some("not a pem")),
+        ] {
+            assert!(matches!(
+                AdminCredentials::new(&[], Some(config)),
+                Err(DomainError::InvalidInput(_))
+            ));
+        }
+
+        let unconfigured = admin(&[], false, false);
+        assert!(!unconfigured.is_configured());
+        assert!(rejected(unconfigured.verify(&hs256(&valid_claims()))));
+    }
+}
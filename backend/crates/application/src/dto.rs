@@ -1,4 +1,5 @@
 use domain::entities::*;
+use domain::value_objects::{Money, Quantity};
 use serde::{Deserialize, Serialize};
 
 /// DTO for creating a new RFQ
@@ -17,6 +18,71 @@ pub struct CreateRfqRequest {
 pub struct CreateRfqResponse {
     pub id: String,
     pub last_event_ts: String,
+    /// The buyer's access token for this RFQ. Left out when a request without
+    /// credentials is replayed; the buyer has the link from the confirmation
+    /// email.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub access_token: Option<String>,
+}
+
+/// DTO for sending one RFQ to several manufacturers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateCampaignRequest {
+    pub tenant_id: String,
+    pub manufacturer_ids: Vec<String>,
+    pub buyer: ContactDto,
+    pub subject: String,
+    pub body: String,
+    pub attachments: Option<Vec<AttachmentDto>>,
+}
+
+/// DTO for campaign creation response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateCampaignResponse {
+    pub id: String,
+    pub rfqs: Vec<CampaignRfq>,
+    /// The buyer's access token for the campaign view; left out like
+    /// [`CreateRfqResponse::access_token`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub access_token: Option<String>,
+}
+
+/// DTO for a campaign with its child RFQs side by side
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CampaignResponse {
+    pub id: String,
+    pub subject: String,
+    pub created_at: String,
+    pub rfqs: Vec<CampaignRfqDto>,
+}
+
+/// DTO for one manufacturer's thread in a campaign
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CampaignRfqDto {
+    pub rfq_id: String,
+    pub manufacturer_id: String,
+    pub manufacturer_name: Option<String>,
+    pub status: RfqStatus,
+    pub last_event_ts: String,
+    /// Latest quote revision, if the manufacturer has quoted
+    pub quote: Option<RfqEventDto>,
+    /// Latest message from the manufacturer
+    pub last_reply: Option<RfqEventDto>,
+    pub outcome: Option<RfqOutcome>,
+    /// The buyer's access token for this child RFQ
+    pub access_token: String,
+}
+
+/// DTO for event listing parameters
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ListEventsQuery {
+    /// ISO8601 timestamp; only events at or after it
+    pub since: Option<String>,
+    /// Opaque cursor; only events after it
+    pub cursor: Option<String>,
+    /// Opaque cursor; only events before it, newest page first
+    pub before: Option<String>,
+    pub limit: Option<u32>,
 }
 
 /// DTO for listing events
@@ -24,12 +90,53 @@ pub struct CreateRfqResponse {
 pub struct ListEventsResponse {
     pub items: Vec<RfqEventDto>,
     pub next_since: Option<String>,
+    /// Pass as `cursor` to read on after this page, including when polling for new events
+    pub next_cursor: Option<String>,
+    /// Pass as `before` to read the events preceding this page
+    pub prev_cursor: Option<String>,
+    /// Whether more events lie beyond this page in the direction read
+    pub has_more: bool,
+}
+
+/// DTO for manufacturer inbox listing parameters
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InboxQuery {
+    /// Comma-separated statuses to include, e.g. `open,awarded`
+    pub status: Option<String>,
+    /// Only unread (`true`) or only read (`false`) RFQs
+    pub unread: Option<bool>,
+    /// `desc` (newest activity first, default) or `asc`
+    pub order: Option<String>,
+    /// Opaque cursor from a previous page
+    pub cursor: Option<String>,
+    pub limit: Option<u32>,
+}
+
+/// DTO for a manufacturer's RFQ inbox
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InboxResponse {
+    pub items: Vec<InboxItemDto>,
+    pub next_cursor: Option<String>,
+    pub has_more: bool,
+    /// Unread RFQs in the whole inbox, regardless of filters
+    pub unread_count: usize,
+}
+
+/// DTO for one RFQ in a manufacturer's inbox
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InboxItemDto {
+    pub rfq_id: String,
+    pub subject: String,
+    pub buyer: ContactDto,
+    pub status: RfqStatus,
+    pub created_at: String,
+    pub last_event_ts: String,
+    pub unread: bool,
 }
 
 /// DTO for posting a message
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PostMessageRequest {
-    pub by: String, // "buyer" | "manufacturer"
     pub body: String,
     pub attachments: Option<Vec<AttachmentDto>>,
 }
@@ -40,10 +147,66 @@ pub struct PostMessageResponse {
     pub ts: String,
 }
 
+/// DTO for changing an RFQ's status
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangeStatusRequest {
+    pub status: String, // "open" | "closed" | "archived"
+    pub note: Option<String>,
+}
+
+/// DTO for status change response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangeStatusResponse {
+    pub status: RfqStatus,
+    pub ts: String,
+}
+
+/// DTO for a buyer's award or decline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RfqDecisionRequest {
+    pub reason: Option<String>,
+    /// Manufacturer message, attachment or quote the decision is about
+    pub reference: Option<OfferReference>,
+}
+
+/// DTO for a manufacturer's quote. Amounts and quantities are decimal strings
+/// so they never pass through a float.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmitQuoteRequest {
+    pub line_items: Vec<QuoteLineItemDto>,
+    #[serde(default)]
+    pub charges: Vec<QuoteChargeDto>,
+    pub lead_time_days: Option<LeadTimeDto>,
+    pub incoterms: Option<String>, // e.g. "FOB"
+    pub valid_until: String,       // YYYY-MM-DD
+    pub note: Option<String>,
+}
+
+/// DTO for quote submission response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmitQuoteResponse {
+    pub id: String,
+    pub revision: u32,
+    pub ts: String,
+}
+
+/// DTO for an attachment download link
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttachmentDownloadResponse {
+    pub url: String,
+    pub file_name: String,
+    pub expires_in: u32,
+}
+
 /// DTO for presigned upload request
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PresignUploadRequest {
-    pub tenant_id: String,
+    /// Derived from the caller's credentials; when given it must match
+    #[serde(default)]
+    pub tenant_id: Option<String>,
+    /// Manufacturer the upload is for, required without credentials
+    #[serde(default)]
+    pub manufacturer_id: Option<String>,
     #[serde(rename = "pathType")]
     pub path_type: String, // "imageRaw"
     pub content_type: String,
@@ -80,6 +243,54 @@ pub struct CreateManufacturerResponse {
     pub tenant_id: String,
 }
 
+/// DTO for a tenant editing its own manufacturer profile. Replaces the
+/// profile's fields and offerings; media is managed through uploads.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateManufacturerRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub location: Option<LocationDto>,
+    pub capabilities: Vec<String>,
+    pub contact_email: String,
+    #[serde(default)]
+    pub offerings: Vec<OfferingDto>,
+}
+
+/// DTO for creating a tenant API key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateApiKeyRequest {
+    pub name: String,
+    pub scopes: Vec<ApiKeyScope>,
+    /// RFC 3339; the key never expires when absent
+    pub expires_at: Option<String>,
+}
+
+/// DTO for a tenant API key, without the key itself
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKeyDto {
+    pub id: String,
+    pub tenant_id: String,
+    pub name: String,
+    pub scopes: Vec<ApiKeyScope>,
+    pub created_at: String,
+    pub expires_at: Option<String>,
+    pub revoked_at: Option<String>,
+}
+
+/// DTO for a created API key. `key` is only ever returned here.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateApiKeyResponse {
+    pub key: String,
+    #[serde(flatten)]
+    pub api_key: ApiKeyDto,
+}
+
+/// DTO for a tenant's API keys, newest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKeysResponse {
+    pub items: Vec<ApiKeyDto>,
+}
+
 // Supporting DTOs matching the entities
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -126,6 +337,34 @@ pub struct LeadTimeDto {
     pub max: Option<u32>,
 }
 
+/// Amount of money with the amount as a decimal string, e.g. `"12.50"`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MoneyDto {
+    pub amount: String,
+    pub currency: String, // ISO-4217, e.g. "USD"
+}
+
+/// Quantity with the value as a decimal string, e.g. `"2.5"`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuantityDto {
+    pub value: String,
+    pub unit: String, // "pcs" | "kg" | "lbs" | "m" | "ft"
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuoteLineItemDto {
+    pub part: String,
+    pub quantity: QuantityDto,
+    pub unit_price: MoneyDto,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuoteChargeDto {
+    pub kind: String, // "tooling" | "nre"
+    pub description: Option<String>,
+    pub amount: MoneyDto,
+}
+
 /// DTO for RFQ events
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -146,6 +385,8 @@ pub enum RfqEventDto {
         by: String,
         status: String,
         note: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        reference: Option<OfferReference>,
     },
     #[serde(rename = "attachment")]
     Attachment {
@@ -154,6 +395,23 @@ pub enum RfqEventDto {
         ts: String,
         by: String,
         attachments: Vec<AttachmentRefDto>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        message_id: Option<String>,
+    },
+    #[serde(rename = "quote")]
+    Quote {
+        id: String,
+        rfq_id: String,
+        ts: String,
+        by: String,
+        revision: u32,
+        supersedes: Option<String>,
+        line_items: Vec<QuoteLineItemDto>,
+        charges: Vec<QuoteChargeDto>,
+        lead_time_days: Option<LeadTimeDto>,
+        incoterms: Option<Incoterm>,
+        valid_until: String,
+        note: Option<String>,
     },
 }
 
@@ -176,6 +434,34 @@ impl From<Contact> for ContactDto {
     }
 }
 
+impl From<RfqInboxEntry> for InboxItemDto {
+    fn from(entry: RfqInboxEntry) -> Self {
+        InboxItemDto {
+            unread: entry.is_unread(),
+            rfq_id: entry.rfq_id,
+            subject: entry.subject,
+            buyer: entry.buyer.into(),
+            status: entry.status,
+            created_at: entry.created_at.to_rfc3339(),
+            last_event_ts: entry.last_event_ts.to_rfc3339(),
+        }
+    }
+}
+
+impl From<TenantApiKey> for ApiKeyDto {
+    fn from(key: TenantApiKey) -> Self {
+        ApiKeyDto {
+            id: key.id,
+            tenant_id: key.tenant_id,
+            name: key.name,
+            scopes: key.scopes,
+            created_at: key.created_at.to_rfc3339(),
+            expires_at: key.expires_at.map(|ts| ts.to_rfc3339()),
+            revoked_at: key.revoked_at.map(|ts| ts.to_rfc3339()),
+        }
+    }
+}
+
 impl From<Location> for LocationDto {
     fn from(location: Location) -> Self {
         LocationDto {
@@ -188,6 +474,56 @@ impl From<Location> for LocationDto {
     }
 }
 
+impl From<LeadTime> for LeadTimeDto {
+    fn from(lead_time: LeadTime) -> Self {
+        LeadTimeDto {
+            min: lead_time.min,
+            max: lead_time.max,
+        }
+    }
+}
+
+impl From<Money> for MoneyDto {
+    fn from(money: Money) -> Self {
+        MoneyDto {
+            amount: money.to_decimal_string(),
+            currency: money.currency().to_string(),
+        }
+    }
+}
+
+impl From<Quantity> for QuantityDto {
+    fn from(quantity: Quantity) -> Self {
+        QuantityDto {
+            value: quantity.to_decimal_string(),
+            unit: quantity.unit().as_str().to_string(),
+        }
+    }
+}
+
+impl From<QuoteLineItem> for QuoteLineItemDto {
+    fn from(item: QuoteLineItem) -> Self {
+        QuoteLineItemDto {
+            part: item.part,
+            quantity: item.quantity.into(),
+            unit_price: item.unit_price.into(),
+        }
+    }
+}
+
+impl From<QuoteCharge> for QuoteChargeDto {
+    fn from(charge: QuoteCharge) -> Self {
+        QuoteChargeDto {
+            kind: match charge.kind {
+                ChargeKind::Tooling => "tooling".to_string(),
+                ChargeKind::Nre => "nre".to_string(),
+            },
+            description: charge.description,
+            amount: charge.amount.into(),
+        }
+    }
+}
+
 impl From<AttachmentRef> for AttachmentRefDto {
     fn from(attachment: AttachmentRef) -> Self {
         AttachmentRefDto {
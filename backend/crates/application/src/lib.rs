@@ -1,3 +1,5 @@
+pub mod access;
 pub mod dto;
+pub mod policy;
 pub mod ports;
 pub mod services;
@@ -0,0 +1,808 @@
+use domain::entities::{ApiKeyScope, ManufacturerProfile, RfqCampaign, RfqMeta};
+use domain::error::{DomainError, Result};
+use domain::events::EventAuthor;
+
+use crate::access::Principal;
+
+/// An operation a principal asks to perform
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    CreateRfq,
+    ReadRfq,
+    PostMessage,
+    ChangeStatus,
+    SubmitQuote,
+    /// Award or decline
+    DecideRfq,
+    DownloadAttachment,
+    CreateCampaign,
+    ViewCampaign,
+    ReadInbox,
+    MarkInboxRead,
+    CreateManufacturer,
+    UpdateManufacturer,
+    /// Issue, list and revoke a tenant's API keys
+    ManageApiKeys,
+    PresignUpload,
+}
+
+/// What an action is performed on, with the facts the rules look at
+#[derive(Debug, Clone, Copy)]
+pub enum Resource<'a> {
+    /// An RFQ, which belongs to the tenant of the manufacturer it is
+    /// addressed to
+    Rfq(&'a RfqMeta),
+    Campaign(&'a RfqCampaign),
+    /// A manufacturer's inbox
+    Inbox(&'a ManufacturerProfile),
+    Manufacturer(&'a ManufacturerProfile),
+    /// Data of a whole tenant, e.g. its API keys or uploads
+    Tenant(&'a str),
+}
+
+impl Resource<'_> {
+    /// The tenant the resource belongs to, where known
+    fn tenant_id(&self) -> Option<&str> {
+        match self {
+            Resource::Rfq(rfq) => Some(&rfq.tenant_id),
+            Resource::Campaign(campaign) => Some(&campaign.tenant_id),
+            Resource::Inbox(manufacturer) | Resource::Manufacturer(manufacturer) => {
+                Some(&manufacturer.tenant_id)
+            }
+            Resource::Tenant(tenant_id) => Some(tenant_id),
+        }
+    }
+
+    /// The id an access token for the resource is issued for
+    fn id(&self) -> &str {
+        match self {
+            Resource::Rfq(rfq) => &rfq.id,
+            Resource::Campaign(campaign) => &campaign.id,
+            Resource::Inbox(manufacturer) | Resource::Manufacturer(manufacturer) => {
+                &manufacturer.id
+            }
+            Resource::Tenant(tenant_id) => tenant_id,
+        }
+    }
+}
+
+/// The kinds of principal the rules tell apart
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    /// No credentials
+    Anonymous,
+    Buyer,
+    ManufacturerUser,
+    /// A tenant's API key, limited by its scopes
+    TenantAdmin,
+    PlatformAdmin,
+    System,
+}
+
+impl Role {
+    pub fn of(principal: &Principal) -> Self {
+        match principal {
+            Principal::Anonymous => Role::Anonymous,
+            Principal::Buyer { .. } => Role::Buyer,
+            Principal::ManufacturerUser { .. } => Role::ManufacturerUser,
+            Principal::TenantKey { .. } => Role::TenantAdmin,
+            Principal::Admin { .. } => Role::PlatformAdmin,
+            Principal::System => Role::System,
+        }
+    }
+
+    /// Who events written in this role are attributed to; tenant keys act
+    /// for the manufacturer
+    pub fn author(self) -> Option<EventAuthor> {
+        match self {
+            Role::Buyer => Some(EventAuthor::Buyer),
+            Role::ManufacturerUser | Role::TenantAdmin => Some(EventAuthor::Manufacturer),
+            Role::System => Some(EventAuthor::System),
+            Role::Anonymous | Role::PlatformAdmin => None,
+        }
+    }
+}
+
+/// What a role needs to be allowed an action
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+    Allow,
+    Deny,
+    /// Hold a token for the RFQ and be listed among its participants in
+    /// that role
+    Participant,
+    /// Hold a token for the campaign and be its buyer
+    CampaignBuyer,
+    /// Hold a token for the manufacturer, issued to its contact
+    ManufacturerContact,
+    /// Hold a token for the resource, whatever role it grants
+    TokenHolder,
+    /// Be a manufacturer, which anyone may name
+    NamedManufacturer,
+    /// Be a key of the resource's tenant
+    OwnTenant,
+    /// Be a key of the resource's tenant carrying the scope
+    TenantScope(ApiKeyScope),
+}
+
+/// The decision table: what each role needs for each action. Whatever is
+/// not listed is denied.
+pub fn rule(action: Action, role: Role) -> Rule {
+    use Action::*;
+    use Role::*;
+
+    match (action, role) {
+        // Anyone may open an RFQ; a tenant's keys speak for suppliers
+        (CreateRfq | CreateCampaign, TenantAdmin) => Rule::Deny,
+        (CreateRfq | CreateCampaign, _) => Rule::Allow,
+
+        (ReadRfq, Buyer | ManufacturerUser) => Rule::Participant,
+        (ReadRfq, TenantAdmin) => Rule::TenantScope(ApiKeyScope::RfqRead),
+        (ReadRfq, PlatformAdmin | System) => Rule::Allow,
+
+        (PostMessage | ChangeStatus, Buyer | ManufacturerUser) => Rule::Participant,
+        (PostMessage | ChangeStatus, TenantAdmin) => Rule::TenantScope(ApiKeyScope::RfqReply),
+        (PostMessage | ChangeStatus, System) => Rule::Allow,
+
+        (SubmitQuote, ManufacturerUser) => Rule::Participant,
+        (SubmitQuote, TenantAdmin) => Rule::TenantScope(ApiKeyScope::RfqReply),
+
+        (DecideRfq, Buyer) => Rule::Participant,
+
+        // Every download is audited against a participant
+        (DownloadAttachment, Buyer | ManufacturerUser) => Rule::Participant,
+        (DownloadAttachment, TenantAdmin) => Rule::TenantScope(ApiKeyScope::RfqRead),
+
+        // The view hands out buyer tokens for each child RFQ
+        (ViewCampaign, Buyer) => Rule::CampaignBuyer,
+
+        (ReadInbox | MarkInboxRead, ManufacturerUser) => Rule::ManufacturerContact,
+        (ReadInbox | MarkInboxRead, TenantAdmin) => Rule::TenantScope(ApiKeyScope::RfqRead),
+        (ReadInbox, PlatformAdmin | System) => Rule::Allow,
+
+        (CreateManufacturer | ManageApiKeys, PlatformAdmin | System) => Rule::Allow,
+
+        (UpdateManufacturer, TenantAdmin) => Rule::TenantScope(ApiKeyScope::ProfileWrite),
+        (UpdateManufacturer, PlatformAdmin | System) => Rule::Allow,
+
+        // Uploads land in the tenant of whatever the credentials are bound
+        // to. Buyers upload before they hold any token, so they name the
+        // manufacturer they are writing to and get its tenant.
+        (PresignUpload, Anonymous) => Rule::NamedManufacturer,
+        (PresignUpload, Buyer | ManufacturerUser) => Rule::TokenHolder,
+        (PresignUpload, TenantAdmin) => Rule::OwnTenant,
+        (PresignUpload, PlatformAdmin | System) => Rule::Allow,
+
+        _ => Rule::Deny,
+    }
+}
+
+/// Decide whether `principal` may perform `action` on `resource`, returning
+/// the role it acts in
+pub fn authorize(principal: &Principal, action: Action, resource: &Resource) -> Result<Role> {
+    let role = Role::of(principal);
+    match rule(action, role) {
+        Rule::Allow => {}
+        Rule::Deny => return Err(denied(action, role)),
+        Rule::Participant => check_participant(principal, action, resource)?,
+        Rule::CampaignBuyer => {
+            let (Principal::Buyer { email, .. }, Resource::Campaign(campaign)) =
+                (principal, resource)
+            else {
+                return Err(denied(action, role));
+            };
+            principal.check_access_to(&campaign.id)?;
+            if !email.eq_ignore_ascii_case(&campaign.buyer.email) {
+                return Err(denied(action, role));
+            }
+        }
+        Rule::ManufacturerContact => {
+            let (Principal::ManufacturerUser { email, .. }, Resource::Inbox(manufacturer)) =
+                (principal, resource)
+            else {
+                return Err(denied(action, role));
+            };
+            principal.check_access_to(&manufacturer.id)?;
+            if !manufacturer
+                .contact_email
+                .as_deref()
+                .is_some_and(|contact| contact.eq_ignore_ascii_case(email))
+            {
+                return Err(denied(action, role));
+            }
+        }
+        Rule::TokenHolder => principal.check_access_to(resource.id())?,
+        Rule::NamedManufacturer => {
+            if !matches!(resource, Resource::Manufacturer(_)) {
+                return Err(denied(action, role));
+            }
+        }
+        Rule::OwnTenant => check_tenant_key(principal, resource, None)?,
+        Rule::TenantScope(scope) => check_tenant_key(principal, resource, Some(scope))?,
+    }
+    Ok(role)
+}
+
+/// Authorize a write and return the author it is attributed to
+pub fn authorize_author(
+    principal: &Principal,
+    action: Action,
+    resource: &Resource,
+) -> Result<EventAuthor> {
+    let role = authorize(principal, action, resource)?;
+    role.author().ok_or_else(|| denied(action, role))
+}
+
+fn check_participant(principal: &Principal, action: Action, resource: &Resource) -> Result<()> {
+    let (Some((email, role)), Resource::Rfq(rfq)) = (principal.participant(), resource) else {
+        return Err(denied(action, Role::of(principal)));
+    };
+    principal.check_access_to(&rfq.id)?;
+    if !rfq
+        .participants
+        .iter()
+        .any(|p| p.role == role && p.email.eq_ignore_ascii_case(email))
+    {
+        return Err(DomainError::Forbidden(format!(
+            "{} is not a {} on this RFQ",
+            email,
+            role.as_str()
+        )));
+    }
+    Ok(())
+}
+
+fn check_tenant_key(
+    principal: &Principal,
+    resource: &Resource,
+    scope: Option<ApiKeyScope>,
+) -> Result<()> {
+    let Principal::TenantKey {
+        tenant_id, scopes, ..
+    } = principal
+    else {
+        return Err(DomainError::Forbidden(
+            "Requires a tenant API key".to_string(),
+        ));
+    };
+    if resource.tenant_id() != Some(tenant_id.as_str()) {
+        return Err(DomainError::Forbidden(
+            "API key belongs to a different tenant".to_string(),
+        ));
+    }
+    if let Some(scope) = scope.filter(|scope| !scopes.contains(scope)) {
+        return Err(DomainError::Forbidden(format!(
+            "API key lacks the {} scope",
+            scope.as_str()
+        )));
+    }
+    Ok(())
+}
+
+fn denied(action: Action, role: Role) -> DomainError {
+    let message = match action {
+        Action::CreateRfq | Action::CreateCampaign => "API keys cannot open RFQs",
+        Action::ReadRfq | Action::PostMessage | Action::ChangeStatus => {
+            "Only participants can act on an RFQ"
+        }
+        Action::SubmitQuote => "Only the manufacturer can quote",
+        Action::DecideRfq => "Only the buyer can award or decline an RFQ",
+        Action::DownloadAttachment => "Only participants of this RFQ can download its attachments",
+        Action::ViewCampaign => "Only the buyer can view a campaign",
+        Action::ReadInbox | Action::MarkInboxRead => "Only the manufacturer can read its inbox",
+        Action::CreateManufacturer | Action::ManageApiKeys => "Requires a platform admin",
+        Action::UpdateManufacturer => "Requires a tenant API key",
+        Action::PresignUpload => "Not allowed to upload for this tenant",
+    };
+    match role {
+        Role::Anonymous => DomainError::Unauthorized("Missing access token".to_string()),
+        _ => DomainError::Forbidden(message.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use domain::entities::{CampaignRfq, Contact, Participant, ParticipantRole, RfqStatus};
+
+    const BUYER: &str = "buyer@example.com";
+    const CONTACT: &str = "sales@acme.example";
+
+    fn manufacturer() -> ManufacturerProfile {
+        ManufacturerProfile {
+            id: "mfg_acme".to_string(),
+            tenant_id: "t1".to_string(),
+            name: "Acme".to_string(),
+            description: None,
+            location: None,
+            categories: Vec::new(),
+            capabilities: None,
+            contact_email: Some(CONTACT.to_string()),
+            media: None,
+            offerings: None,
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn rfq() -> RfqMeta {
+        RfqMeta {
+            id: "r_1".to_string(),
+            tenant_id: "t1".to_string(),
+            manufacturer_id: "mfg_acme".to_string(),
+            buyer: Contact {
+                email: BUYER.to_string(),
+                name: None,
+            },
+            subject: "Brackets".to_string(),
+            status: RfqStatus::Open,
+            created_at: Utc::now(),
+            last_event_ts: Utc::now(),
+            participants: vec![
+                Participant {
+                    role: ParticipantRole::Buyer,
+                    email: BUYER.to_string(),
+                    name: None,
+                },
+                Participant {
+                    role: ParticipantRole::Manufacturer,
+                    email: CONTACT.to_string(),
+                    name: None,
+                },
+            ],
+            attachments: None,
+            latest_quote: None,
+            outcome: None,
+            campaign_id: None,
+            last_reply_position: None,
+        }
+    }
+
+    fn campaign() -> RfqCampaign {
+        RfqCampaign {
+            id: "c_1".to_string(),
+            tenant_id: "t1".to_string(),
+            buyer: Contact {
+                email: BUYER.to_string(),
+                name: None,
+            },
+            subject: "Brackets".to_string(),
+            created_at: Utc::now(),
+            rfqs: vec![CampaignRfq {
+                rfq_id: "r_1".to_string(),
+                manufacturer_id: "mfg_acme".to_string(),
+            }],
+        }
+    }
+
+    fn buyer(resource_id: &str, email: &str) -> Principal {
+        Principal::Buyer {
+            email: email.to_string(),
+            resource_id: resource_id.to_string(),
+        }
+    }
+
+    fn maker(resource_id: &str, email: &str) -> Principal {
+        Principal::ManufacturerUser {
+            email: email.to_string(),
+            resource_id: resource_id.to_string(),
+        }
+    }
+
+    fn key(tenant_id: &str, scopes: &[ApiKeyScope]) -> Principal {
+        Principal::TenantKey {
+            tenant_id: tenant_id.to_string(),
+            key_id: "key_ABCD1234".to_string(),
+            scopes: scopes.to_vec(),
+        }
+    }
+
+    fn admin() -> Principal {
+        Principal::Admin {
+            subject: "ops".to_string(),
+        }
+    }
+
+    /// What `authorize` is expected to decide
+    #[derive(Debug, PartialEq)]
+    enum Expect {
+        Allowed(Role),
+        Unauthorized,
+        Forbidden,
+    }
+
+    fn decide(principal: &Principal, action: Action, resource: &Resource) -> Expect {
+        match authorize(principal, action, resource) {
+            Ok(role) => Expect::Allowed(role),
+            Err(DomainError::Unauthorized(_)) => Expect::Unauthorized,
+            Err(DomainError::Forbidden(_)) => Expect::Forbidden,
+            Err(e) => panic!("unexpected error {:?}", e),
+        }
+    }
+
+    #[test]
+    fn rule_table() {
+        use Action::*;
+        use Role::*;
+
+        let cases = [
+            (CreateRfq, Anonymous, Rule::Allow),
+            (CreateRfq, TenantAdmin, Rule::Deny),
+            (CreateCampaign, TenantAdmin, Rule::Deny),
+            (ReadRfq, Anonymous, Rule::Deny),
+            (ReadRfq, Buyer, Rule::Participant),
+            (
+                ReadRfq,
+                TenantAdmin,
+                Rule::TenantScope(ApiKeyScope::RfqRead),
+            ),
+            (
+                PostMessage,
+                TenantAdmin,
+                Rule::TenantScope(ApiKeyScope::RfqReply),
+            ),
+            (PostMessage, PlatformAdmin, Rule::Deny),
+            (SubmitQuote, Buyer, Rule::Deny),
+            (SubmitQuote, ManufacturerUser, Rule::Participant),
+            (DecideRfq, Buyer, Rule::Participant),
+            (DecideRfq, ManufacturerUser, Rule::Deny),
+            (DownloadAttachment, PlatformAdmin, Rule::Deny),
+            (ViewCampaign, Buyer, Rule::CampaignBuyer),
+            (ViewCampaign, PlatformAdmin, Rule::Deny),
+            (ReadInbox, Anonymous, Rule::Deny),
+            (ReadInbox, Buyer, Rule::Deny),
+            (ReadInbox, ManufacturerUser, Rule::ManufacturerContact),
+            (MarkInboxRead, ManufacturerUser, Rule::ManufacturerContact),
+            (
+                ReadInbox,
+                TenantAdmin,
+                Rule::TenantScope(ApiKeyScope::RfqRead),
+            ),
+            (MarkInboxRead, PlatformAdmin, Rule::Deny),
+            (CreateManufacturer, TenantAdmin, Rule::Deny),
+            (
+                UpdateManufacturer,
+                TenantAdmin,
+                Rule::TenantScope(ApiKeyScope::ProfileWrite),
+            ),
+            (ManageApiKeys, TenantAdmin, Rule::Deny),
+            (ManageApiKeys, PlatformAdmin, Rule::Allow),
+            (PresignUpload, Anonymous, Rule::NamedManufacturer),
+            (PresignUpload, Buyer, Rule::TokenHolder),
+            (PresignUpload, ManufacturerUser, Rule::TokenHolder),
+            (PresignUpload, TenantAdmin, Rule::OwnTenant),
+            (PresignUpload, PlatformAdmin, Rule::Allow),
+        ];
+
+        for (action, role, expected) in cases {
+            assert_eq!(rule(action, role), expected, "{:?} as {:?}", action, role);
+        }
+    }
+
+    #[test]
+    fn authorize_table() {
+        use Action::*;
+        use Expect::*;
+
+        let manufacturer = manufacturer();
+        let rfq = rfq();
+        let campaign = campaign();
+        let on_rfq = Resource::Rfq(&rfq);
+        let inbox = Resource::Inbox(&manufacturer);
+        let read = [ApiKeyScope::RfqRead];
+
+        let cases = [
+            (
+                "buyer reads",
+                buyer("r_1", BUYER),
+                ReadRfq,
+                on_rfq,
+                Allowed(Role::Buyer),
+            ),
+            (
+                "email compared case-insensitively",
+                buyer("r_1", "Buyer@Example.com"),
+                ReadRfq,
+                on_rfq,
+                Allowed(Role::Buyer),
+            ),
+            (
+                "token for another RFQ",
+                buyer("r_2", BUYER),
+                ReadRfq,
+                on_rfq,
+                Forbidden,
+            ),
+            (
+                "campaign token on an RFQ",
+                buyer("c_1", BUYER),
+                ReadRfq,
+                on_rfq,
+                Forbidden,
+            ),
+            (
+                "non-participant email",
+                buyer("r_1", "mallory@example.com"),
+                ReadRfq,
+                on_rfq,
+                Forbidden,
+            ),
+            (
+                "buyer token with the manufacturer's email",
+                buyer("r_1", CONTACT),
+                PostMessage,
+                on_rfq,
+                Forbidden,
+            ),
+            (
+                "manufacturer quotes",
+                maker("r_1", CONTACT),
+                SubmitQuote,
+                on_rfq,
+                Allowed(Role::ManufacturerUser),
+            ),
+            (
+                "buyer quotes",
+                buyer("r_1", BUYER),
+                SubmitQuote,
+                on_rfq,
+                Forbidden,
+            ),
+            (
+                "manufacturer awards",
+                maker("r_1", CONTACT),
+                DecideRfq,
+                on_rfq,
+                Forbidden,
+            ),
+            (
+                "anonymous reads",
+                Principal::Anonymous,
+                ReadRfq,
+                on_rfq,
+                Unauthorized,
+            ),
+            (
+                "anonymous downloads",
+                Principal::Anonymous,
+                DownloadAttachment,
+                on_rfq,
+                Unauthorized,
+            ),
+            (
+                "key reads",
+                key("t1", &read),
+                ReadRfq,
+                on_rfq,
+                Allowed(Role::TenantAdmin),
+            ),
+            (
+                "key of another tenant",
+                key("t2", &read),
+                ReadRfq,
+                on_rfq,
+                Forbidden,
+            ),
+            (
+                "key without rfq:reply",
+                key("t1", &read),
+                PostMessage,
+                on_rfq,
+                Forbidden,
+            ),
+            (
+                "key opens an RFQ",
+                key("t1", &read),
+                CreateRfq,
+                Resource::Tenant("t1"),
+                Forbidden,
+            ),
+            ("admin posts", admin(), PostMessage, on_rfq, Forbidden),
+            (
+                "anonymous opens an RFQ",
+                Principal::Anonymous,
+                CreateRfq,
+                Resource::Tenant("t1"),
+                Allowed(Role::Anonymous),
+            ),
+            (
+                "buyer views campaign",
+                buyer("c_1", BUYER),
+                ViewCampaign,
+                Resource::Campaign(&campaign),
+                Allowed(Role::Buyer),
+            ),
+            (
+                "RFQ token on a campaign",
+                buyer("r_1", BUYER),
+                ViewCampaign,
+                Resource::Campaign(&campaign),
+                Forbidden,
+            ),
+            (
+                "another buyer's campaign",
+                buyer("c_1", "mallory@example.com"),
+                ViewCampaign,
+                Resource::Campaign(&campaign),
+                Forbidden,
+            ),
+            (
+                "contact reads inbox",
+                maker("mfg_acme", CONTACT),
+                ReadInbox,
+                inbox,
+                Allowed(Role::ManufacturerUser),
+            ),
+            (
+                "RFQ token on the inbox",
+                maker("r_1", CONTACT),
+                ReadInbox,
+                inbox,
+                Forbidden,
+            ),
+            (
+                "inbox token for another manufacturer",
+                maker("mfg_other", CONTACT),
+                MarkInboxRead,
+                inbox,
+                Forbidden,
+            ),
+            (
+                "inbox token for a former contact",
+                maker("mfg_acme", "old@acme.example"),
+                ReadInbox,
+                inbox,
+                Forbidden,
+            ),
+            (
+                "buyer reads inbox",
+                buyer("mfg_acme", CONTACT),
+                ReadInbox,
+                inbox,
+                Forbidden,
+            ),
+            (
+                "anonymous reads inbox",
+                Principal::Anonymous,
+                ReadInbox,
+                inbox,
+                Unauthorized,
+            ),
+            (
+                "key reads inbox",
+                key("t1", &read),
+                ReadInbox,
+                inbox,
+                Allowed(Role::TenantAdmin),
+            ),
+            (
+                "key of another tenant reads inbox",
+                key("t2", &read),
+                ReadInbox,
+                inbox,
+                Forbidden,
+            ),
+            (
+                "key without rfq:read reads inbox",
+                key("t1", &[ApiKeyScope::ProfileWrite]),
+                ReadInbox,
+                inbox,
+                Forbidden,
+            ),
+            (
+                "key edits profile",
+                key("t1", &[ApiKeyScope::ProfileWrite]),
+                UpdateManufacturer,
+                Resource::Manufacturer(&manufacturer),
+                Allowed(Role::TenantAdmin),
+            ),
+            (
+                "key without profile:write",
+                key("t1", &read),
+                UpdateManufacturer,
+                Resource::Manufacturer(&manufacturer),
+                Forbidden,
+            ),
+            (
+                "key manages keys",
+                key("t1", &read),
+                ManageApiKeys,
+                Resource::Tenant("t1"),
+                Forbidden,
+            ),
+            (
+                "admin manages keys",
+                admin(),
+                ManageApiKeys,
+                Resource::Tenant("t1"),
+                Allowed(Role::PlatformAdmin),
+            ),
+            (
+                "anonymous presigns for a manufacturer",
+                Principal::Anonymous,
+                PresignUpload,
+                Resource::Manufacturer(&manufacturer),
+                Allowed(Role::Anonymous),
+            ),
+            (
+                "anonymous presigns for a tenant",
+                Principal::Anonymous,
+                PresignUpload,
+                Resource::Tenant("t1"),
+                Unauthorized,
+            ),
+            (
+                "anonymous presigns for an RFQ",
+                Principal::Anonymous,
+                PresignUpload,
+                on_rfq,
+                Unauthorized,
+            ),
+            (
+                "buyer presigns for its RFQ",
+                buyer("r_1", BUYER),
+                PresignUpload,
+                on_rfq,
+                Allowed(Role::Buyer),
+            ),
+            (
+                "buyer presigns for another RFQ",
+                buyer("r_2", BUYER),
+                PresignUpload,
+                on_rfq,
+                Forbidden,
+            ),
+            (
+                "buyer presigns for a tenant",
+                buyer("r_1", BUYER),
+                PresignUpload,
+                Resource::Tenant("t1"),
+                Forbidden,
+            ),
+            (
+                "contact presigns for its manufacturer",
+                maker("mfg_acme", CONTACT),
+                PresignUpload,
+                Resource::Manufacturer(&manufacturer),
+                Allowed(Role::ManufacturerUser),
+            ),
+            (
+                "key presigns for its tenant",
+                key("t1", &[]),
+                PresignUpload,
+                Resource::Tenant("t1"),
+                Allowed(Role::TenantAdmin),
+            ),
+            (
+                "key presigns for another tenant",
+                key("t2", &[]),
+                PresignUpload,
+                Resource::Tenant("t1"),
+                Forbidden,
+            ),
+        ];
+
+        for (name, principal, action, resource, expected) in cases {
+            assert_eq!(decide(&principal, action, &resource), expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn authors_follow_the_role() {
+        let rfq = rfq();
+        let on_rfq = Resource::Rfq(&rfq);
+
+        let author =
+            |principal: Principal| authorize_author(&principal, Action::PostMessage, &on_rfq);
+        assert_eq!(author(buyer("r_1", BUYER)).unwrap(), EventAuthor::Buyer);
+        assert_eq!(
+            author(maker("r_1", CONTACT)).unwrap(),
+            EventAuthor::Manufacturer
+        );
+        assert_eq!(
+            author(key("t1", &[ApiKeyScope::RfqReply])).unwrap(),
+            EventAuthor::Manufacturer
+        );
+        assert_eq!(author(Principal::System).unwrap(), EventAuthor::System);
+    }
+}
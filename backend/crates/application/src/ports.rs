@@ -4,20 +4,339 @@ use domain::error::Result;
 use domain::events::*;
 use domain::value_objects::*;
 
-/// Repository for managing RFQ data
+/// A stored value together with the opaque version it was read at (the ETag on S3)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Versioned<T> {
+    pub value: T,
+    pub version: String,
+}
+
+/// Repository for managing RFQ data.
+///
+/// Meta and index saves are conditional: `expected_version` is the version the
+/// value was read at, or `None` when it must not exist yet. If another writer
+/// got there first the save fails with `DomainError::Conflict`. Saves return
+/// the new version.
 #[async_trait]
 pub trait RfqRepository {
-    async fn save_rfq_meta(&self, rfq: &RfqMeta) -> Result<()>;
-    async fn get_rfq_meta(&self, id: &RfqId) -> Result<Option<RfqMeta>>;
-    async fn save_rfq_index(&self, rfq_id: &RfqId, index: &RfqIndex) -> Result<()>;
-    async fn get_rfq_index(&self, id: &RfqId) -> Result<Option<RfqIndex>>;
+    async fn save_rfq_meta(&self, rfq: &RfqMeta, expected_version: Option<&str>) -> Result<String>;
+    async fn get_rfq_meta(&self, id: &RfqId) -> Result<Option<Versioned<RfqMeta>>>;
+    async fn save_rfq_index(
+        &self,
+        rfq_id: &RfqId,
+        index: &RfqIndex,
+        expected_version: Option<&str>,
+    ) -> Result<String>;
+    async fn get_rfq_index(&self, id: &RfqId) -> Result<Option<Versioned<RfqIndex>>>;
     async fn save_rfq_event(&self, event: &RfqEvent) -> Result<()>;
-    async fn list_rfq_events(
+    async fn list_rfq_events(&self, rfq_id: &RfqId, query: &EventQuery) -> Result<EventPage>;
+    /// Append to the RFQ's audit trail of issued attachment downloads
+    async fn save_attachment_access(&self, access: &AttachmentAccess) -> Result<()>;
+    async fn save_campaign(&self, campaign: &RfqCampaign) -> Result<()>;
+    async fn get_campaign(&self, id: &CampaignId) -> Result<Option<RfqCampaign>>;
+    async fn save_inbox_entry(
+        &self,
+        manufacturer_id: &ManufacturerId,
+        entry: &RfqInboxEntry,
+        expected_version: Option<&str>,
+    ) -> Result<String>;
+    async fn get_inbox_entry(
         &self,
+        manufacturer_id: &ManufacturerId,
         rfq_id: &RfqId,
-        since: Option<chrono::DateTime<chrono::Utc>>,
-        limit: Option<u32>,
-    ) -> Result<Vec<RfqEvent>>;
+    ) -> Result<Option<Versioned<RfqInboxEntry>>>;
+    /// One page of a manufacturer's inbox, ordered by last activity
+    async fn list_inbox(
+        &self,
+        manufacturer_id: &ManufacturerId,
+        query: &InboxPageQuery,
+    ) -> Result<InboxPage>;
+    /// Unread RFQs in a manufacturer's inbox
+    async fn count_unread_inbox(&self, manufacturer_id: &ManufacturerId) -> Result<usize>;
+
+    /// Rebuild an RFQ's meta, index and inbox entry by folding its whole
+    /// event stream onto the stored identity fields. `None` when the RFQ has
+    /// no meta.
+    async fn replay_rfq(&self, rfq_id: &RfqId) -> Result<Option<RfqReplay>> {
+        let Some(stored_meta) = self.get_rfq_meta(rfq_id).await? else {
+            return Ok(None);
+        };
+        let stored_index = self.get_rfq_index(rfq_id).await?;
+        let manufacturer_id = ManufacturerId::new(stored_meta.value.manufacturer_id.clone())?;
+        let stored_inbox = self.get_inbox_entry(&manufacturer_id, rfq_id).await?;
+
+        let mut meta = stored_meta.value.without_events();
+        let mut inbox = RfqInboxEntry {
+            // Read markers are the manufacturer's, not the stream's
+            last_read_ts: stored_inbox
+                .as_ref()
+                .and_then(|entry| entry.value.last_read_ts),
+            ..RfqInboxEntry::new(&meta)
+        };
+        let mut index = RfqIndex {
+            // Segments describe storage, not state, so they carry over
+            segments: stored_index
+                .as_ref()
+                .map(|index| index.value.segments.clone())
+                .unwrap_or_default(),
+            ..RfqIndex::new(meta.created_at)
+        };
+
+        let mut query = EventQuery {
+            limit: REPLAY_PAGE_SIZE,
+            ..EventQuery::default()
+        };
+        loop {
+            let page = self.list_rfq_events(rfq_id, &query).await?;
+            for event in &page.events {
+                meta.apply(event);
+                index.apply(event);
+                inbox.apply(event);
+            }
+            match page.last_position {
+                Some(position) if page.has_more => query.after = Some(position),
+                _ => break,
+            }
+        }
+
+        inbox.refresh(&meta);
+
+        Ok(Some(RfqReplay {
+            stored_meta,
+            stored_index,
+            stored_inbox,
+            meta,
+            index,
+            inbox,
+        }))
+    }
+}
+
+/// Events read per page while replaying an RFQ
+const REPLAY_PAGE_SIZE: u32 = 1000;
+
+/// An RFQ's stored meta, index and inbox entry next to the ones rebuilt from
+/// its events
+#[derive(Debug, Clone)]
+pub struct RfqReplay {
+    pub stored_meta: Versioned<RfqMeta>,
+    pub stored_index: Option<Versioned<RfqIndex>>,
+    pub stored_inbox: Option<Versioned<RfqInboxEntry>>,
+    pub meta: RfqMeta,
+    pub index: RfqIndex,
+    pub inbox: RfqInboxEntry,
+}
+
+impl RfqReplay {
+    /// Whether the stored meta, index or inbox entry disagrees with the event stream
+    pub fn has_drift(&self) -> bool {
+        self.meta != self.stored_meta.value
+            || self
+                .stored_index
+                .as_ref()
+                .is_none_or(|stored| stored.value != self.index)
+            || self
+                .stored_inbox
+                .as_ref()
+                .is_none_or(|stored| stored.value != self.inbox)
+    }
+
+    /// Overwrite the drifted meta, index and inbox entry, conditional on the
+    /// versions replayed from so a concurrent write is never lost
+    pub async fn repair<R>(&self, repository: &R) -> Result<()>
+    where
+        R: RfqRepository + Sync + ?Sized,
+    {
+        if self.meta != self.stored_meta.value {
+            repository
+                .save_rfq_meta(&self.meta, Some(&self.stored_meta.version))
+                .await?;
+        }
+
+        let rfq_id = RfqId::new(self.meta.id.clone())?;
+        let stored_index = self.stored_index.as_ref();
+        if stored_index.is_none_or(|stored| stored.value != self.index) {
+            repository
+                .save_rfq_index(
+                    &rfq_id,
+                    &self.index,
+                    stored_index.map(|stored| stored.version.as_str()),
+                )
+                .await?;
+        }
+
+        let stored_inbox = self.stored_inbox.as_ref();
+        if stored_inbox.is_none_or(|stored| stored.value != self.inbox) {
+            repository
+                .save_inbox_entry(
+                    &ManufacturerId::new(self.meta.manufacturer_id.clone())?,
+                    &self.inbox,
+                    stored_inbox.map(|stored| stored.version.as_str()),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+/// Which slice of an RFQ's event log to read. Positions are event sort keys
+/// (`RfqEvent::sort_key`) and both position bounds are exclusive.
+#[derive(Debug, Clone, Default)]
+pub struct EventQuery {
+    /// Only events at or after this time
+    pub since: Option<chrono::DateTime<chrono::Utc>>,
+    /// Only events after this position
+    pub after: Option<String>,
+    /// Only events before this position. The page is then the newest `limit`
+    /// matching events instead of the oldest.
+    pub before: Option<String>,
+    pub limit: u32,
+}
+
+impl EventQuery {
+    /// A one-event page ending at `position`. Sort keys are ASCII, so
+    /// nothing sorts between a position and itself followed by DEL.
+    pub fn at(position: &str) -> Self {
+        Self {
+            before: Some(format!("{}\u{7f}", position)),
+            limit: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Position to start listing after, combining `after` and `since`
+    pub fn start_after(&self) -> Option<String> {
+        let since = self.since.map(RfqEvent::sort_key_prefix);
+        match (self.after.clone(), since) {
+            (Some(after), Some(since)) => Some(after.max(since)),
+            (after, since) => after.or(since),
+        }
+    }
+
+    /// Whether a position lies within the bounds
+    pub fn contains(&self, position: &str) -> bool {
+        self.start_after()
+            .is_none_or(|start| position > start.as_str())
+            && self
+                .before
+                .as_deref()
+                .is_none_or(|before| position < before)
+    }
+
+    /// Pick this page out of ascending positions, returning whether more
+    /// matching positions lie beyond it
+    pub fn select(&self, positions: Vec<String>) -> (Vec<String>, bool) {
+        let limit = self.limit as usize;
+        let mut matching: Vec<String> = positions
+            .into_iter()
+            .filter(|position| self.contains(position))
+            .collect();
+        let has_more = matching.len() > limit;
+
+        if self.before.is_some() {
+            matching.drain(..matching.len().saturating_sub(limit));
+        } else {
+            matching.truncate(limit);
+        }
+
+        (matching, has_more)
+    }
+}
+
+/// A contiguous page of an RFQ's event log
+#[derive(Debug, Clone, Default)]
+pub struct EventPage {
+    /// Events ordered by (ts, id)
+    pub events: Vec<RfqEvent>,
+    /// First and last position covered, including events that could not be read
+    pub first_position: Option<String>,
+    pub last_position: Option<String>,
+    /// Whether more events lie beyond the page in the direction read
+    pub has_more: bool,
+}
+
+impl EventPage {
+    /// Build a page from the positions `EventQuery::select` picked and the events read for them
+    pub fn new(
+        query: &EventQuery,
+        positions: &[String],
+        mut events: Vec<RfqEvent>,
+        has_more: bool,
+    ) -> Self {
+        // Older event keys without fractional seconds only resolve `since` to the second
+        events.retain(|e| query.since.is_none_or(|since| e.timestamp() >= since));
+        events.sort_by(|a, b| (a.timestamp(), a.id()).cmp(&(b.timestamp(), b.id())));
+
+        Self {
+            events,
+            first_position: positions.first().cloned(),
+            last_position: positions.last().cloned(),
+            has_more,
+        }
+    }
+}
+
+/// Which page of a manufacturer's inbox to read. Positions are inbox
+/// positions (`RfqInboxEntry::position`, newest activity first) and `after`
+/// is exclusive in the order read.
+#[derive(Debug, Clone, Default)]
+pub struct InboxPageQuery {
+    /// Statuses to include; every status when empty
+    pub statuses: Vec<RfqStatus>,
+    /// Only unread (`true`) or only read (`false`) RFQs
+    pub unread: Option<bool>,
+    /// Oldest activity first instead of newest
+    pub ascending: bool,
+    pub after: Option<String>,
+    pub limit: u32,
+}
+
+impl InboxPageQuery {
+    /// Whether an entry passes the status and unread filters
+    pub fn matches(&self, entry: &RfqInboxEntry) -> bool {
+        (self.statuses.is_empty() || self.statuses.contains(&entry.status))
+            && self.unread.is_none_or(|unread| entry.is_unread() == unread)
+    }
+
+    /// Whether a position lies past `after` in the order read
+    pub fn follows(&self, position: &str) -> bool {
+        self.after.as_deref().is_none_or(|after| {
+            if self.ascending {
+                position < after
+            } else {
+                position > after
+            }
+        })
+    }
+
+    /// Pick this page out of a whole inbox given in any order
+    pub fn select(&self, entries: Vec<RfqInboxEntry>) -> InboxPage {
+        let mut matching: Vec<(String, RfqInboxEntry)> = entries
+            .into_iter()
+            .filter(|entry| self.matches(entry))
+            .map(|entry| (entry.position(), entry))
+            .filter(|(position, _)| self.follows(position))
+            .collect();
+        matching.sort_by(|(a, _), (b, _)| if self.ascending { b.cmp(a) } else { a.cmp(b) });
+
+        let limit = self.limit as usize;
+        let has_more = matching.len() > limit;
+        matching.truncate(limit);
+
+        InboxPage {
+            entries: matching.into_iter().map(|(_, entry)| entry).collect(),
+            has_more,
+        }
+    }
+}
+
+/// A page of a manufacturer's inbox
+#[derive(Debug, Clone, Default)]
+pub struct InboxPage {
+    /// Entries in the order read
+    pub entries: Vec<RfqInboxEntry>,
+    /// Whether more matching entries lie beyond the page
+    pub has_more: bool,
 }
 
 /// Repository for managing manufacturer data
@@ -28,6 +347,18 @@ pub trait ManufacturerRepository {
     async fn delete_manufacturer(&self, id: &ManufacturerId) -> Result<()>;
 }
 
+/// Repository for tenant API keys
+#[async_trait]
+pub trait ApiKeyRepository {
+    async fn save_api_key(&self, key: &TenantApiKey) -> Result<()>;
+    async fn get_api_key(
+        &self,
+        tenant_id: &TenantId,
+        id: &ApiKeyId,
+    ) -> Result<Option<TenantApiKey>>;
+    async fn list_api_keys(&self, tenant_id: &TenantId) -> Result<Vec<TenantApiKey>>;
+}
+
 /// Repository for managing catalog data
 #[async_trait]
 pub trait CatalogRepository {
@@ -57,18 +388,275 @@ pub trait ImageService {
     ) -> Result<crate::dto::PresignUploadResponse>;
     async fn save_image_manifest(&self, manifest: &ImageManifest) -> Result<()>;
     async fn get_image_manifest(&self, id: &str) -> Result<Option<ImageManifest>>;
+    /// Short-lived GET URL for a private object that downloads as `file_name`
+    async fn generate_presigned_download_url(
+        &self,
+        key: &S3Key,
+        file_name: &str,
+        expires_in_seconds: u32,
+    ) -> Result<String>;
+    /// Look up an uploaded object in the private bucket without reading it,
+    /// `None` if nothing was uploaded under `key`
+    async fn head_upload(&self, key: &S3Key) -> Result<Option<UploadedObject>>;
+}
+
+/// What storage reports about an uploaded object
+#[derive(Debug, Clone, PartialEq)]
+pub struct UploadedObject {
+    pub size_bytes: u64,
+    pub content_type: Option<String>,
+    /// Tenant the upload was presigned for (`x-amz-meta-tenant` on S3)
+    pub tenant_id: Option<String>,
 }
 
 /// Email notification service
 #[async_trait]
 pub trait EmailService {
     async fn send_rfq_created_notification(&self, rfq: &RfqMeta) -> Result<()>;
-    async fn send_rfq_message_notification(&self, rfq: &RfqMeta, event: &RfqEvent) -> Result<()>;
+    /// `attachments` are the files sent along with the message, if any
+    async fn send_rfq_message_notification(
+        &self,
+        rfq: &RfqMeta,
+        event: &RfqEvent,
+        attachments: &[AttachmentRef],
+    ) -> Result<()>;
+    async fn send_rfq_status_notification(&self, rfq: &RfqMeta, event: &RfqEvent) -> Result<()>;
+    /// Notify each manufacturer of its own child RFQ, without naming the
+    /// others, and send the buyer one confirmation for the campaign
+    async fn send_campaign_created_notification(
+        &self,
+        campaign: &RfqCampaign,
+        rfqs: &[RfqMeta],
+    ) -> Result<()>;
+    /// Tell both parties the buyer awarded or declined the RFQ
+    async fn send_rfq_outcome_notification(&self, rfq: &RfqMeta, event: &RfqEvent) -> Result<()>;
+}
+
+/// Outcome of claiming an idempotency key
+#[derive(Debug, Clone, PartialEq)]
+pub enum IdempotencyClaim {
+    /// The caller holds the in-progress marker, at this version, and must
+    /// store or release it
+    Acquired(String),
+    /// A previous request with the same key and body completed with this response
+    Replay(String),
 }
 
 /// Idempotency service
 #[async_trait]
 pub trait IdempotencyService {
-    async fn check_idempotency(&self, key: &str, body_hash: &str) -> Result<Option<String>>;
-    async fn store_idempotency(&self, key: &str, body_hash: &str, response: &str) -> Result<()>;
+    /// Claim a key for processing. Fails with `Conflict` if the key was used with a
+    /// different body or another request holding it is still in progress.
+    async fn claim_idempotency(&self, key: &str, body_hash: &str) -> Result<IdempotencyClaim>;
+    /// Replace the marker at version `claim` with the response. Fails with
+    /// `Conflict` if the marker went stale and another request took it over.
+    async fn store_idempotency(
+        &self,
+        key: &str,
+        claim: &str,
+        body_hash: &str,
+        response: &str,
+    ) -> Result<()>;
+    /// Drop the marker at version `claim` so the client can retry after a
+    /// failure; a marker taken over by another request is left alone
+    async fn release_idempotency(&self, key: &str, claim: &str) -> Result<()>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, Duration, Utc};
+
+    fn at(minutes: i64) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-03-01T09:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+            + Duration::minutes(minutes)
+    }
+
+    /// Position of the event `id` written at minute `minutes`
+    fn position(minutes: i64, id: &str) -> String {
+        format!("{}-{}", RfqEvent::sort_key_prefix(at(minutes)), id)
+    }
+
+    fn positions() -> Vec<String> {
+        (1..=6).map(|n| position(n, &format!("e{}", n))).collect()
+    }
+
+    fn query(after: Option<usize>, before: Option<usize>, limit: u32) -> EventQuery {
+        let positions = positions();
+        EventQuery {
+            since: None,
+            after: after.map(|n| positions[n - 1].clone()),
+            before: before.map(|n| positions[n - 1].clone()),
+            limit,
+        }
+    }
+
+    fn message(minutes: i64, seconds: i64, id: &str) -> RfqEvent {
+        let mut event =
+            RfqEvent::new_message("r_1".to_string(), EventAuthor::Buyer, id.to_string());
+        if let RfqEvent::Message(message) = &mut event {
+            message.base.id = id.to_string();
+            message.base.ts = at(minutes) + Duration::seconds(seconds);
+        }
+        event
+    }
+
+    #[test]
+    fn start_after_takes_the_later_of_after_and_since() {
+        let since = RfqEvent::sort_key_prefix(at(3));
+        let cases = [
+            (None, None, None),
+            (Some(position(1, "e1")), None, Some(position(1, "e1"))),
+            (None, Some(at(3)), Some(since.clone())),
+            // The cursor is past `since`
+            (
+                Some(position(4, "e4")),
+                Some(at(3)),
+                Some(position(4, "e4")),
+            ),
+            // `since` is past the cursor
+            (Some(position(2, "e2")), Some(at(3)), Some(since.clone())),
+            // Events at exactly `since` sort after its prefix
+            (Some(since.clone()), Some(at(3)), Some(since)),
+        ];
+
+        for (after, since, expected) in cases {
+            let query = EventQuery {
+                since,
+                after: after.clone(),
+                ..EventQuery::default()
+            };
+            assert_eq!(
+                query.start_after(),
+                expected,
+                "after {:?}, since {:?}",
+                after,
+                since
+            );
+        }
+    }
+
+    #[test]
+    fn contains_excludes_both_bounds() {
+        let p = positions();
+        let cases = [
+            (query(None, None, 10), [true, true, true, true, true, true]),
+            (
+                query(Some(2), None, 10),
+                [false, false, true, true, true, true],
+            ),
+            (
+                query(None, Some(5), 10),
+                [true, true, true, true, false, false],
+            ),
+            (
+                query(Some(2), Some(5), 10),
+                [false, false, true, true, false, false],
+            ),
+            (query(Some(3), Some(4), 10), [false; 6]),
+        ];
+
+        for (query, expected) in cases {
+            let contained: Vec<bool> = p.iter().map(|position| query.contains(position)).collect();
+            assert_eq!(contained, expected, "{:?}", query);
+        }
+
+        let since = EventQuery {
+            since: Some(at(3)),
+            ..EventQuery::default()
+        };
+        assert!(!since.contains(&p[1]));
+        assert!(since.contains(&p[2]));
+    }
+
+    #[test]
+    fn select_keeps_the_oldest_forwards_and_the_newest_backwards() {
+        let p = positions();
+        let cases: [(EventQuery, &[usize], bool); 8] = [
+            (query(None, None, 2), &[1, 2], true),
+            (query(None, None, 6), &[1, 2, 3, 4, 5, 6], false),
+            (query(Some(4), None, 2), &[5, 6], false),
+            (query(Some(6), None, 2), &[], false),
+            (query(None, Some(5), 2), &[3, 4], true),
+            (query(None, Some(3), 2), &[1, 2], false),
+            (query(None, Some(1), 2), &[], false),
+            (query(Some(1), Some(6), 3), &[3, 4, 5], true),
+        ];
+
+        for (query, expected, more) in cases {
+            let expected: Vec<String> = expected.iter().map(|n| p[n - 1].clone()).collect();
+            assert_eq!(query.select(p.clone()), (expected, more), "{:?}", query);
+        }
+    }
+
+    #[test]
+    fn select_walks_the_whole_log_in_either_direction() {
+        let p = positions();
+
+        let mut forwards = Vec::new();
+        let mut after = None;
+        loop {
+            let query = EventQuery {
+                after: after.clone(),
+                limit: 4,
+                ..EventQuery::default()
+            };
+            let (page, more) = query.select(p.clone());
+            after = page.last().cloned();
+            forwards.extend(page);
+            if !more {
+                break;
+            }
+        }
+        assert_eq!(forwards, p);
+
+        let mut backwards = Vec::new();
+        let mut before = Some("9999".to_string());
+        loop {
+            let query = EventQuery {
+                before: before.clone(),
+                limit: 4,
+                ..EventQuery::default()
+            };
+            let (page, more) = query.select(p.clone());
+            before = page.first().cloned();
+            backwards.splice(0..0, page);
+            if !more {
+                break;
+            }
+        }
+        assert_eq!(backwards, p);
+    }
+
+    #[test]
+    fn event_pages_are_sorted_and_filtered_by_since() {
+        let query = EventQuery {
+            since: Some(at(2)),
+            limit: 10,
+            ..EventQuery::default()
+        };
+        // Second-resolution keys let an event just before `since` through the listing
+        let events = vec![
+            message(3, 0, "b"),
+            message(2, 0, "c"),
+            message(1, 59, "early"),
+            message(3, 0, "a"),
+        ];
+        let positions = vec![position(1, "first"), position(4, "unreadable")];
+
+        let page = EventPage::new(&query, &positions, events, true);
+
+        let ids: Vec<&str> = page.events.iter().map(RfqEvent::id).collect();
+        assert_eq!(ids, ["c", "a", "b"]);
+        assert_eq!(page.first_position, Some(position(1, "first")));
+        assert_eq!(page.last_position, Some(position(4, "unreadable")));
+        assert!(page.has_more);
+
+        let empty = EventPage::new(&EventQuery::default(), &[], Vec::new(), false);
+        assert!(empty.events.is_empty());
+        assert_eq!(empty.first_position, None);
+        assert!(!empty.has_more);
+    }
 }
@@ -1,21 +1,199 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
 use chrono::Utc;
 use domain::entities::*;
 use domain::error::{DomainError, Result};
 use domain::events::*;
 use domain::value_objects::*;
+use serde::{de::DeserializeOwned, Serialize};
 use sha2::Digest;
+use std::future::Future;
 use std::sync::Arc;
 use uuid::Uuid;
 
+use crate::access::{AccessTokens, Principal};
 use crate::dto::*;
+use crate::policy::{self, Action, Resource};
 use crate::ports::*;
 
+/// Lifetime of presigned attachment download URLs
+const DOWNLOAD_URL_TTL_SECONDS: u32 = 300;
+
+/// Events read per page when scanning an RFQ's whole event stream
+const EVENT_SCAN_PAGE_SIZE: u32 = 1000;
+
+/// Manufacturers one campaign can fan out to
+const MIN_CAMPAIGN_MANUFACTURERS: usize = 2;
+const MAX_CAMPAIGN_MANUFACTURERS: usize = 10;
+
+/// Attempts at a read-modify-write that keeps losing to concurrent writers
+const MAX_UPDATE_ATTEMPTS: u32 = 8;
+
+/// Re-run `operation` while it fails with `DomainError::Conflict`, so each
+/// attempt re-reads the current version before writing. Waits a random,
+/// growing delay in between so racing writers spread out.
+async fn retry_on_conflict<T, F, Fut>(mut operation: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut attempt = 1;
+    loop {
+        match operation().await {
+            Err(DomainError::Conflict(reason)) if attempt < MAX_UPDATE_ATTEMPTS => {
+                tracing::debug!("Retrying after concurrent update: {}", reason);
+                let max_delay_ms = (10u64 << attempt).min(500);
+                let delay_ms = (Uuid::new_v4().as_u128() % u128::from(max_delay_ms)) as u64;
+                tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+fn money_from_dto(money: MoneyDto) -> Result<Money> {
+    Money::from_decimal(&money.amount, money.currency.parse()?)
+}
+
+fn quantity_from_dto(quantity: QuantityDto) -> Result<Quantity> {
+    Quantity::from_decimal(&quantity.value, quantity.unit.parse()?)
+}
+
+/// Map a quote request onto the domain type; `Quote::validate` checks the rest
+fn quote_from_request(request: SubmitQuoteRequest) -> Result<Quote> {
+    let valid_until =
+        chrono::NaiveDate::parse_from_str(&request.valid_until, "%Y-%m-%d").map_err(|_| {
+            DomainError::ValidationFailed("valid_until must be a YYYY-MM-DD date".to_string())
+        })?;
+    let charges = request
+        .charges
+        .into_iter()
+        .map(|charge| {
+            let kind = match charge.kind.as_str() {
+                "tooling" => ChargeKind::Tooling,
+                "nre" => ChargeKind::Nre,
+                _ => {
+                    return Err(DomainError::ValidationFailed(
+                        "Invalid charge kind".to_string(),
+                    ))
+                }
+            };
+            Ok(QuoteCharge {
+                kind,
+                description: charge.description,
+                amount: money_from_dto(charge.amount)?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let note = request
+        .note
+        .filter(|note| !note.trim().is_empty())
+        .map(|note| MessageBody::new(note).map(|note| note.as_str().to_string()))
+        .transpose()?;
+
+    Ok(Quote {
+        line_items: request
+            .line_items
+            .into_iter()
+            .map(|item| {
+                Ok(QuoteLineItem {
+                    part: item.part.trim().to_string(),
+                    quantity: quantity_from_dto(item.quantity)?,
+                    unit_price: money_from_dto(item.unit_price)?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?,
+        charges,
+        lead_time_days: request.lead_time_days.map(|lead_time| LeadTime {
+            min: lead_time.min,
+            max: lead_time.max,
+        }),
+        incoterms: request
+            .incoterms
+            .as_deref()
+            .map(Incoterm::parse)
+            .transpose()?,
+        valid_until,
+        note,
+    })
+}
+
+/// RFQs belong to the tenant of the manufacturer they are addressed to, so
+/// the tenant a request names must be that one. Attachments are checked
+/// against it.
+fn check_manufacturer_tenant(
+    manufacturer: &ManufacturerProfile,
+    tenant_id: &TenantId,
+) -> Result<()> {
+    if manufacturer.tenant_id != tenant_id.as_str() {
+        return Err(DomainError::ValidationFailed(format!(
+            "Manufacturer '{}' does not belong to tenant '{}'",
+            manufacturer.id,
+            tenant_id.as_str()
+        )));
+    }
+    Ok(())
+}
+
+/// Encode an event log position as an opaque pagination cursor
+fn encode_cursor(position: &str) -> String {
+    URL_SAFE_NO_PAD.encode(position)
+}
+
+fn decode_cursor(cursor: &str) -> Result<String> {
+    URL_SAFE_NO_PAD
+        .decode(cursor)
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .filter(|position| {
+            !position.is_empty()
+                && position
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        })
+        .ok_or_else(|| DomainError::ValidationFailed("Invalid cursor".to_string()))
+}
+
+/// What the buyer sent that every new RFQ thread opens with
+struct NewRfq<'a> {
+    tenant_id: &'a TenantId,
+    buyer: &'a Contact,
+    subject: &'a str,
+    body: &'a MessageBody,
+    attachments: &'a [AttachmentRef],
+    campaign_id: Option<&'a CampaignId>,
+}
+
+/// What an `Idempotency-Key` is scoped to: the same key on another route,
+/// for another resource or from another principal is a different request,
+/// so one caller's response is never replayed to another
+struct IdempotencyScope<'a> {
+    route: &'static str,
+    resource_id: &'a str,
+    principal: &'a Principal,
+}
+
+impl IdempotencyScope<'_> {
+    /// The key the idempotency record is stored under
+    fn key(&self, idempotency_key: &str) -> String {
+        format!(
+            "{}\n{}\n{}\n{}",
+            self.route,
+            self.resource_id,
+            self.principal.identity(),
+            idempotency_key
+        )
+    }
+}
+
 /// Main RFQ service for handling RFQ operations
 pub struct RfqService {
     rfq_repository: Arc<dyn RfqRepository + Send + Sync>,
     manufacturer_repository: Arc<dyn ManufacturerRepository + Send + Sync>,
     email_service: Arc<dyn EmailService + Send + Sync>,
     idempotency_service: Arc<dyn IdempotencyService + Send + Sync>,
+    image_service: Arc<dyn ImageService + Send + Sync>,
+    access_tokens: Arc<AccessTokens>,
 }
 
 impl RfqService {
@@ -24,34 +202,80 @@ impl RfqService {
         manufacturer_repository: Arc<dyn ManufacturerRepository + Send + Sync>,
         email_service: Arc<dyn EmailService + Send + Sync>,
         idempotency_service: Arc<dyn IdempotencyService + Send + Sync>,
+        image_service: Arc<dyn ImageService + Send + Sync>,
+        access_tokens: Arc<AccessTokens>,
     ) -> Self {
         Self {
             rfq_repository,
             manufacturer_repository,
             email_service,
             idempotency_service,
+            image_service,
+            access_tokens,
         }
     }
 
     pub async fn create_rfq(
         &self,
+        principal: &Principal,
         request: CreateRfqRequest,
         idempotency_key: Option<&str>,
     ) -> Result<CreateRfqResponse> {
-        // Check idempotency if key provided
-        if let Some(key) = idempotency_key {
-            let body_hash = self.compute_request_hash(&request)?;
-            if let Some(cached_response) = self
-                .idempotency_service
-                .check_idempotency(key, &body_hash)
-                .await?
-            {
-                return serde_json::from_str(&cached_response).map_err(|_| {
-                    DomainError::Internal("Failed to deserialize cached response".to_string())
-                });
-            }
-        }
+        policy::authorize(
+            principal,
+            Action::CreateRfq,
+            &Resource::Tenant(&request.tenant_id),
+        )?;
+
+        let body_hash = match idempotency_key {
+            Some(_) => Some(self.compute_request_hash(&request)?),
+            None => None,
+        };
+
+        let tenant_id = request.tenant_id.clone();
+        let scope = IdempotencyScope {
+            route: "POST /rfqs",
+            resource_id: &tenant_id,
+            principal,
+        };
+        let (rfq_meta, replayed) = self
+            .with_idempotency(
+                &scope,
+                idempotency_key,
+                body_hash,
+                self.create_rfq_once(request),
+            )
+            .await?;
+
+        Ok(CreateRfqResponse {
+            access_token: self.buyer_token(
+                &rfq_meta.id,
+                &rfq_meta.buyer.email,
+                principal,
+                replayed,
+            ),
+            id: rfq_meta.id,
+            last_event_ts: rfq_meta.last_event_ts.to_rfc3339(),
+        })
+    }
+
+    /// A buyer token for a resource just created. Tokens are never stored
+    /// with idempotency records; a replay gets a fresh one, except for a
+    /// caller without credentials, which cannot be told apart from any other.
+    fn buyer_token(
+        &self,
+        resource_id: &str,
+        buyer_email: &str,
+        principal: &Principal,
+        replayed: bool,
+    ) -> Option<String> {
+        (!replayed || *principal != Principal::Anonymous).then(|| {
+            self.access_tokens
+                .issue(resource_id, ParticipantRole::Buyer, buyer_email)
+        })
+    }
 
+    async fn create_rfq_once(&self, request: CreateRfqRequest) -> Result<RfqMeta> {
         // Validate input
         let tenant_id = TenantId::new(request.tenant_id.clone())?;
         let manufacturer_id = ManufacturerId::new(request.manufacturer_id.clone())?;
@@ -64,10 +288,7 @@ impl RfqService {
             .get_manufacturer(&manufacturer_id)
             .await?
             .ok_or_else(|| DomainError::NotFound("Manufacturer not found".to_string()))?;
-
-        // Generate RFQ ID
-        let rfq_id = RfqId::generate();
-        let now = Utc::now();
+        check_manufacturer_tenant(&manufacturer, &tenant_id)?;
 
         // Create buyer contact
         let buyer = Contact {
@@ -75,32 +296,366 @@ impl RfqService {
             name: request.buyer.name.clone(),
         };
 
-        // Process attachments if any
-        let attachments = if let Some(attachment_dtos) = request.attachments.clone() {
-            let mut processed_attachments = Vec::new();
-            for attachment_dto in attachment_dtos {
-                let content_type = ContentType::new(attachment_dto.content_type)?;
-                let file_size = FileSize::new(attachment_dto.size_bytes)?;
+        let attachments = self
+            .verify_attachments(&tenant_id, request.attachments.clone())
+            .await?;
 
-                processed_attachments.push(AttachmentRef {
-                    id: Uuid::new_v4().to_string(),
-                    file_name: attachment_dto.file_name,
-                    content_type: content_type.as_str().to_string(),
-                    size_bytes: file_size.as_u64(),
-                    key: attachment_dto.upload_key,
-                });
+        let rfq_meta = self
+            .open_rfq(
+                &NewRfq {
+                    tenant_id: &tenant_id,
+                    buyer: &buyer,
+                    subject: &request.subject,
+                    body: &message_body,
+                    attachments: &attachments,
+                    campaign_id: None,
+                },
+                RfqId::generate(),
+                manufacturer,
+            )
+            .await?;
+
+        // Send notifications
+        self.email_service
+            .send_rfq_created_notification(&rfq_meta)
+            .await?;
+
+        Ok(rfq_meta)
+    }
+
+    /// Send one buyer request to several manufacturers as a campaign of
+    /// linked child RFQs, each with its own private thread
+    pub async fn create_campaign(
+        &self,
+        principal: &Principal,
+        request: CreateCampaignRequest,
+        idempotency_key: Option<&str>,
+    ) -> Result<CreateCampaignResponse> {
+        policy::authorize(
+            principal,
+            Action::CreateCampaign,
+            &Resource::Tenant(&request.tenant_id),
+        )?;
+
+        let body_hash = match idempotency_key {
+            Some(_) => Some(self.compute_request_hash(&request)?),
+            None => None,
+        };
+
+        let tenant_id = request.tenant_id.clone();
+        let scope = IdempotencyScope {
+            route: "POST /campaigns",
+            resource_id: &tenant_id,
+            principal,
+        };
+        let (campaign, replayed) = self
+            .with_idempotency(
+                &scope,
+                idempotency_key,
+                body_hash,
+                self.create_campaign_once(request),
+            )
+            .await?;
+
+        Ok(CreateCampaignResponse {
+            access_token: self.buyer_token(
+                &campaign.id,
+                &campaign.buyer.email,
+                principal,
+                replayed,
+            ),
+            id: campaign.id,
+            rfqs: campaign.rfqs,
+        })
+    }
+
+    async fn create_campaign_once(&self, request: CreateCampaignRequest) -> Result<RfqCampaign> {
+        let tenant_id = TenantId::new(request.tenant_id.clone())?;
+        let buyer_email = Email::new(request.buyer.email.clone())?;
+        let message_body = MessageBody::new(request.body.clone())?;
+
+        if !(MIN_CAMPAIGN_MANUFACTURERS..=MAX_CAMPAIGN_MANUFACTURERS)
+            .contains(&request.manufacturer_ids.len())
+        {
+            return Err(DomainError::ValidationFailed(format!(
+                "A campaign needs {}-{} manufacturers",
+                MIN_CAMPAIGN_MANUFACTURERS, MAX_CAMPAIGN_MANUFACTURERS
+            )));
+        }
+
+        // Check every manufacturer before creating any RFQ
+        let mut manufacturers = Vec::with_capacity(request.manufacturer_ids.len());
+        for (i, id) in request.manufacturer_ids.iter().enumerate() {
+            if request.manufacturer_ids[..i].contains(id) {
+                return Err(DomainError::ValidationFailed(format!(
+                    "Manufacturer '{}' is listed twice",
+                    id
+                )));
+            }
+            let manufacturer_id = ManufacturerId::new(id.clone())?;
+            let manufacturer = self
+                .manufacturer_repository
+                .get_manufacturer(&manufacturer_id)
+                .await?
+                .ok_or_else(|| DomainError::NotFound(format!("Manufacturer '{}' not found", id)))?;
+            check_manufacturer_tenant(&manufacturer, &tenant_id)?;
+            manufacturers.push(manufacturer);
+        }
+
+        let buyer = Contact {
+            email: buyer_email.as_str().to_string(),
+            name: request.buyer.name.clone(),
+        };
+        let attachments = self
+            .verify_attachments(&tenant_id, request.attachments.clone())
+            .await?;
+
+        // The campaign is saved first so every child RFQ that exists is listed
+        let campaign_id = CampaignId::generate();
+        let rfq_ids: Vec<RfqId> = manufacturers.iter().map(|_| RfqId::generate()).collect();
+        let campaign = RfqCampaign {
+            id: campaign_id.as_str().to_string(),
+            tenant_id: tenant_id.as_str().to_string(),
+            buyer: buyer.clone(),
+            subject: request.subject.clone(),
+            created_at: Utc::now(),
+            rfqs: rfq_ids
+                .iter()
+                .zip(&manufacturers)
+                .map(|(rfq_id, manufacturer)| CampaignRfq {
+                    rfq_id: rfq_id.as_str().to_string(),
+                    manufacturer_id: manufacturer.id.clone(),
+                })
+                .collect(),
+        };
+        self.rfq_repository.save_campaign(&campaign).await?;
+
+        let new_rfq = NewRfq {
+            tenant_id: &tenant_id,
+            buyer: &buyer,
+            subject: &request.subject,
+            body: &message_body,
+            attachments: &attachments,
+            campaign_id: Some(&campaign_id),
+        };
+        let mut rfqs = Vec::with_capacity(manufacturers.len());
+        for (rfq_id, manufacturer) in rfq_ids.into_iter().zip(manufacturers) {
+            rfqs.push(self.open_rfq(&new_rfq, rfq_id, manufacturer).await?);
+        }
+
+        self.email_service
+            .send_campaign_created_notification(&campaign, &rfqs)
+            .await?;
+
+        Ok(campaign)
+    }
+
+    /// The campaign's child RFQs side by side, each with a fresh buyer token.
+    /// Only the buyer, holding a token for the campaign, may see it.
+    pub async fn get_campaign(
+        &self,
+        campaign_id: &str,
+        principal: &Principal,
+    ) -> Result<CampaignResponse> {
+        let campaign_id = CampaignId::new(campaign_id.to_string())?;
+        principal.check_access_to(campaign_id.as_str())?;
+
+        let campaign = self
+            .rfq_repository
+            .get_campaign(&campaign_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound("Campaign not found".to_string()))?;
+        policy::authorize(
+            principal,
+            Action::ViewCampaign,
+            &Resource::Campaign(&campaign),
+        )?;
+
+        let mut rfqs = Vec::with_capacity(campaign.rfqs.len());
+        for child in &campaign.rfqs {
+            let rfq_id = RfqId::new(child.rfq_id.clone())?;
+            // A child missing after an interrupted creation is left out
+            let Some(rfq_meta) = self.rfq_repository.get_rfq_meta(&rfq_id).await? else {
+                continue;
+            };
+            let rfq_meta = rfq_meta.value;
+
+            // The meta points at both events, so each is a one-event read
+            let quote = match rfq_meta
+                .latest_quote
+                .as_ref()
+                .and_then(|quote| quote.position.as_deref())
+            {
+                Some(position) => self.event_at(&rfq_id, position).await?,
+                None => None,
+            };
+            let last_reply = match rfq_meta.last_reply_position.as_deref() {
+                Some(position) => self.event_at(&rfq_id, position).await?,
+                None => None,
+            };
+
+            rfqs.push(CampaignRfqDto {
+                rfq_id: rfq_meta.id,
+                manufacturer_id: rfq_meta.manufacturer_id,
+                manufacturer_name: rfq_meta
+                    .participants
+                    .into_iter()
+                    .find(|p| p.role == ParticipantRole::Manufacturer)
+                    .and_then(|p| p.name),
+                status: rfq_meta.status,
+                last_event_ts: rfq_meta.last_event_ts.to_rfc3339(),
+                quote: quote.as_ref().map(|event| self.event_to_dto(event)),
+                last_reply: last_reply.as_ref().map(|event| self.event_to_dto(event)),
+                outcome: rfq_meta.outcome,
+                access_token: self.access_tokens.issue(
+                    &child.rfq_id,
+                    ParticipantRole::Buyer,
+                    &campaign.buyer.email,
+                ),
+            });
+        }
+
+        Ok(CampaignResponse {
+            id: campaign.id,
+            subject: campaign.subject,
+            created_at: campaign.created_at.to_rfc3339(),
+            rfqs,
+        })
+    }
+
+    /// RFQs addressed to a manufacturer, filtered and paged by last activity.
+    /// Open to its contact holding a token for the manufacturer; see `policy`
+    /// for everyone else.
+    pub async fn list_inbox(
+        &self,
+        manufacturer_id: &str,
+        query: InboxQuery,
+        principal: &Principal,
+    ) -> Result<InboxResponse> {
+        let manufacturer_id = ManufacturerId::new(manufacturer_id.to_string())?;
+        self.authorize_inbox(&manufacturer_id, Action::ReadInbox, principal)
+            .await?;
+
+        let statuses = query
+            .status
+            .as_deref()
+            .map(|statuses| {
+                statuses
+                    .split(',')
+                    .map(|status| match status.trim() {
+                        "open" => Ok(RfqStatus::Open),
+                        "closed" => Ok(RfqStatus::Closed),
+                        "archived" => Ok(RfqStatus::Archived),
+                        "awarded" => Ok(RfqStatus::Awarded),
+                        _ => Err(DomainError::ValidationFailed(format!(
+                            "Invalid status '{}'",
+                            status
+                        ))),
+                    })
+                    .collect::<Result<Vec<_>>>()
+            })
+            .transpose()?;
+        let ascending = match query.order.as_deref() {
+            None | Some("desc") => false,
+            Some("asc") => true,
+            Some(_) => {
+                return Err(DomainError::ValidationFailed(
+                    "order must be 'asc' or 'desc'".to_string(),
+                ))
             }
-            Some(processed_attachments)
-        } else {
-            None
         };
+        let page_query = InboxPageQuery {
+            statuses: statuses.unwrap_or_default(),
+            unread: query.unread,
+            ascending,
+            after: query.cursor.as_deref().map(decode_cursor).transpose()?,
+            limit: query.limit.unwrap_or(50).clamp(1, 200),
+        };
+
+        let page = self
+            .rfq_repository
+            .list_inbox(&manufacturer_id, &page_query)
+            .await?;
+        let unread_count = self
+            .rfq_repository
+            .count_unread_inbox(&manufacturer_id)
+            .await?;
+
+        Ok(InboxResponse {
+            next_cursor: page
+                .entries
+                .last()
+                .map(|entry| encode_cursor(&entry.position())),
+            items: page.entries.into_iter().map(Into::into).collect(),
+            has_more: page.has_more,
+            unread_count,
+        })
+    }
+
+    /// Record that the manufacturer has read everything on an RFQ so far
+    pub async fn mark_inbox_read(
+        &self,
+        manufacturer_id: &str,
+        rfq_id: &str,
+        principal: &Principal,
+    ) -> Result<InboxItemDto> {
+        let manufacturer_id = ManufacturerId::new(manufacturer_id.to_string())?;
+        let rfq_id = RfqId::new(rfq_id.to_string())?;
+        self.authorize_inbox(&manufacturer_id, Action::MarkInboxRead, principal)
+            .await?;
+
+        let entry = retry_on_conflict(|| async {
+            let current = self
+                .rfq_repository
+                .get_inbox_entry(&manufacturer_id, &rfq_id)
+                .await?
+                .ok_or_else(|| DomainError::NotFound("RFQ not found in inbox".to_string()))?;
+
+            let mut entry = current.value;
+            let now = Utc::now();
+            entry.last_read_ts = Some(entry.last_read_ts.map_or(now, |read| read.max(now)));
+            self.rfq_repository
+                .save_inbox_entry(&manufacturer_id, &entry, Some(&current.version))
+                .await?;
+            Ok(entry)
+        })
+        .await?;
+
+        Ok(entry.into())
+    }
+
+    /// Check `principal` may perform `action` on the manufacturer's inbox
+    async fn authorize_inbox(
+        &self,
+        manufacturer_id: &ManufacturerId,
+        action: Action,
+        principal: &Principal,
+    ) -> Result<()> {
+        let manufacturer = self
+            .manufacturer_repository
+            .get_manufacturer(manufacturer_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound("Manufacturer not found".to_string()))?;
+
+        policy::authorize(principal, action, &Resource::Inbox(&manufacturer))?;
+        Ok(())
+    }
+
+    /// Create an RFQ for `manufacturer` with its opening events, meta and index
+    async fn open_rfq(
+        &self,
+        new_rfq: &NewRfq<'_>,
+        rfq_id: RfqId,
+        manufacturer: ManufacturerProfile,
+    ) -> Result<RfqMeta> {
+        let now = Utc::now();
 
         // Create participants
         let participants = vec![
             Participant {
                 role: ParticipantRole::Buyer,
-                email: buyer.email.clone(),
-                name: buyer.name.clone(),
+                email: new_rfq.buyer.email.clone(),
+                name: new_rfq.buyer.name.clone(),
             },
             Participant {
                 role: ParticipantRole::Manufacturer,
@@ -109,97 +664,101 @@ impl RfqService {
             },
         ];
 
-        // Create RFQ meta
-        let rfq_meta = RfqMeta {
-            id: rfq_id.as_str().to_string(),
-            tenant_id: tenant_id.as_str().to_string(),
-            manufacturer_id: manufacturer_id.as_str().to_string(),
-            buyer: buyer.clone(),
-            subject: request.subject.clone(),
-            status: RfqStatus::Open,
-            created_at: now,
-            last_event_ts: now,
-            participants,
-            attachments: attachments.clone(),
-        };
-
-        // Save RFQ meta
-        self.rfq_repository.save_rfq_meta(&rfq_meta).await?;
-
         // Create initial events
-        let status_event = RfqEvent::new_status(
-            rfq_id.as_str().to_string(),
-            EventAuthor::System,
-            StatusType::RfqCreated,
-            None,
-        );
-
-        let message_event = RfqEvent::new_message(
-            rfq_id.as_str().to_string(),
-            EventAuthor::Buyer,
-            message_body.as_str().to_string(),
-        );
-
-        // Save events
-        self.rfq_repository.save_rfq_event(&status_event).await?;
-        self.rfq_repository.save_rfq_event(&message_event).await?;
-
-        // If there are attachments, create attachment event
-        if let Some(attachments) = attachments {
-            let attachment_event = RfqEvent::new_attachment(
+        let mut events = vec![
+            RfqEvent::new_status(
+                rfq_id.as_str().to_string(),
+                EventAuthor::System,
+                StatusType::RfqCreated,
+                None,
+                None,
+            ),
+            RfqEvent::new_message(
                 rfq_id.as_str().to_string(),
                 EventAuthor::Buyer,
-                attachments,
-            );
-            self.rfq_repository
-                .save_rfq_event(&attachment_event)
-                .await?;
+                new_rfq.body.as_str().to_string(),
+            ),
+        ];
+        if !new_rfq.attachments.is_empty() {
+            events.push(RfqEvent::new_attachment(
+                rfq_id.as_str().to_string(),
+                EventAuthor::Buyer,
+                new_rfq.attachments.to_vec(),
+                Some(events[1].id().to_string()),
+            ));
         }
 
-        // Update RFQ index
-        let index = RfqIndex {
+        // Meta and index are projections of the events
+        let mut rfq_meta = RfqMeta {
+            id: rfq_id.as_str().to_string(),
+            tenant_id: new_rfq.tenant_id.as_str().to_string(),
+            manufacturer_id: manufacturer.id,
+            buyer: new_rfq.buyer.clone(),
+            subject: new_rfq.subject.to_string(),
+            status: RfqStatus::Open,
+            created_at: now,
             last_event_ts: now,
-            count: if rfq_meta.attachments.is_some() { 3 } else { 2 },
+            participants,
+            attachments: None,
+            latest_quote: None,
+            outcome: None,
+            campaign_id: new_rfq.campaign_id.map(|id| id.as_str().to_string()),
+            last_reply_position: None,
         };
-        self.rfq_repository.save_rfq_index(&rfq_id, &index).await?;
+        let mut index = RfqIndex::new(now);
+        for event in &events {
+            rfq_meta.apply(event);
+            index.apply(event);
+        }
 
-        // Send notifications
-        self.email_service
-            .send_rfq_created_notification(&rfq_meta)
+        // Save RFQ meta, events and index
+        self.rfq_repository.save_rfq_meta(&rfq_meta, None).await?;
+        for event in &events {
+            self.rfq_repository.save_rfq_event(event).await?;
+        }
+        self.rfq_repository
+            .save_rfq_index(&rfq_id, &index, None)
             .await?;
 
-        let response = CreateRfqResponse {
-            id: rfq_id.as_str().to_string(),
-            last_event_ts: now.to_rfc3339(),
-        };
-
-        // Store idempotency result if key provided
-        if let Some(key) = idempotency_key {
-            let body_hash = self.compute_request_hash(&request)?;
-            let response_json = serde_json::to_string(&response)
-                .map_err(|_| DomainError::Internal("Failed to serialize response".to_string()))?;
-            self.idempotency_service
-                .store_idempotency(key, &body_hash, &response_json)
-                .await?;
+        // The manufacturer's inbox entry
+        let manufacturer_id = ManufacturerId::new(rfq_meta.manufacturer_id.clone())?;
+        let mut inbox_entry = RfqInboxEntry::new(&rfq_meta);
+        for event in &events {
+            inbox_entry.apply(event);
         }
+        self.rfq_repository
+            .save_inbox_entry(&manufacturer_id, &inbox_entry, None)
+            .await?;
 
-        Ok(response)
+        Ok(rfq_meta)
     }
 
-    pub async fn get_rfq(&self, rfq_id: &str) -> Result<Option<RfqMeta>> {
+    pub async fn get_rfq(&self, rfq_id: &str, principal: &Principal) -> Result<Option<RfqMeta>> {
         let rfq_id = RfqId::new(rfq_id.to_string())?;
-        self.rfq_repository.get_rfq_meta(&rfq_id).await
+        principal.check_access_to(rfq_id.as_str())?;
+        let Some(rfq_meta) = self.rfq_repository.get_rfq_meta(&rfq_id).await? else {
+            return Ok(None);
+        };
+        policy::authorize(principal, Action::ReadRfq, &Resource::Rfq(&rfq_meta.value))?;
+        Ok(Some(rfq_meta.value))
     }
 
     pub async fn list_events(
         &self,
         rfq_id: &str,
-        since: Option<String>,
-        limit: Option<u32>,
+        principal: &Principal,
+        query: ListEventsQuery,
     ) -> Result<ListEventsResponse> {
         let rfq_id = RfqId::new(rfq_id.to_string())?;
+        principal.check_access_to(rfq_id.as_str())?;
+        let rfq_meta = self
+            .rfq_repository
+            .get_rfq_meta(&rfq_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound("RFQ not found".to_string()))?;
+        policy::authorize(principal, Action::ReadRfq, &Resource::Rfq(&rfq_meta.value))?;
 
-        let since_dt = if let Some(since_str) = since {
+        let since_dt = if let Some(since_str) = query.since {
             Some(
                 chrono::DateTime::parse_from_rfc3339(&since_str)
                     .map_err(|_| {
@@ -211,119 +770,842 @@ impl RfqService {
             None
         };
 
-        let events = self
+        let event_query = EventQuery {
+            since: since_dt,
+            after: query.cursor.as_deref().map(decode_cursor).transpose()?,
+            before: query.before.as_deref().map(decode_cursor).transpose()?,
+            limit: query.limit.unwrap_or(100).clamp(1, 1000),
+        };
+
+        let page = self
             .rfq_repository
-            .list_rfq_events(&rfq_id, since_dt, limit)
+            .list_rfq_events(&rfq_id, &event_query)
             .await?;
 
-        let event_dtos: Vec<RfqEventDto> = events.iter().map(|e| self.event_to_dto(e)).collect();
+        let event_dtos: Vec<RfqEventDto> =
+            page.events.iter().map(|e| self.event_to_dto(e)).collect();
 
-        let next_since = events.last().map(|e| e.timestamp().to_rfc3339());
+        let next_since = page.events.last().map(|e| e.timestamp().to_rfc3339());
 
         Ok(ListEventsResponse {
             items: event_dtos,
             next_since,
+            // An empty page keeps the caller's position so polling can carry on from it
+            next_cursor: page
+                .last_position
+                .as_deref()
+                .map(encode_cursor)
+                .or(query.cursor),
+            prev_cursor: page.first_position.as_deref().map(encode_cursor),
+            has_more: page.has_more,
         })
     }
 
     pub async fn post_message(
         &self,
         rfq_id: &str,
+        principal: &Principal,
         request: PostMessageRequest,
         idempotency_key: Option<&str>,
     ) -> Result<PostMessageResponse> {
         let rfq_id = RfqId::new(rfq_id.to_string())?;
+        let author = self
+            .authorize(&rfq_id, principal, Action::PostMessage)
+            .await?;
 
-        // Check idempotency if key provided
-        if let Some(key) = idempotency_key {
-            let body_hash = self.compute_message_hash(&request)?;
-            if let Some(cached_response) = self
-                .idempotency_service
-                .check_idempotency(key, &body_hash)
-                .await?
-            {
-                return serde_json::from_str(&cached_response).map_err(|_| {
-                    DomainError::Internal("Failed to deserialize cached response".to_string())
-                });
-            }
-        }
+        // The same body from the other party is a different message
+        let body_hash = match idempotency_key {
+            Some(_) => Some(self.compute_request_hash(&(&author, &request))?),
+            None => None,
+        };
+
+        let scope = IdempotencyScope {
+            route: "POST /rfqs/{id}/messages",
+            resource_id: rfq_id.as_str(),
+            principal,
+        };
+        self.with_idempotency(
+            &scope,
+            idempotency_key,
+            body_hash,
+            self.post_message_once(rfq_id.clone(), author, request),
+        )
+        .await
+        .map(|(response, _)| response)
+    }
 
+    async fn post_message_once(
+        &self,
+        rfq_id: RfqId,
+        author: EventAuthor,
+        request: PostMessageRequest,
+    ) -> Result<PostMessageResponse> {
         let message_body = MessageBody::new(request.body.clone())?;
 
-        // Verify RFQ exists
+        // Verify RFQ exists and is still taking messages
         let rfq_meta = self
             .rfq_repository
             .get_rfq_meta(&rfq_id)
             .await?
-            .ok_or_else(|| DomainError::NotFound("RFQ not found".to_string()))?;
+            .ok_or_else(|| DomainError::NotFound("RFQ not found".to_string()))?
+            .value;
+        if !rfq_meta.status.accepts_messages() {
+            return Err(DomainError::Conflict(format!(
+                "RFQ is {}",
+                rfq_meta.status.as_str()
+            )));
+        }
 
-        // Parse author
-        let author = match request.by.as_str() {
-            "buyer" => EventAuthor::Buyer,
-            "manufacturer" => EventAuthor::Manufacturer,
-            _ => {
-                return Err(DomainError::ValidationFailed(
-                    "Invalid author role".to_string(),
-                ))
-            }
-        };
+        let tenant_id = TenantId::new(rfq_meta.tenant_id.clone())?;
+        let attachments = self
+            .verify_attachments(&tenant_id, request.attachments.clone())
+            .await?;
 
-        // Create message event
+        // Create message event, plus an attachment event linked to it
         let message_event = RfqEvent::new_message(
             rfq_id.as_str().to_string(),
-            author,
+            author.clone(),
             message_body.as_str().to_string(),
         );
-
         let timestamp = message_event.timestamp();
 
-        // Save event
-        self.rfq_repository.save_rfq_event(&message_event).await?;
+        let mut events = vec![message_event];
+        if !attachments.is_empty() {
+            events.push(RfqEvent::new_attachment(
+                rfq_id.as_str().to_string(),
+                author,
+                attachments.clone(),
+                Some(events[0].id().to_string()),
+            ));
+        }
 
-        // Update RFQ index
-        let mut index = self
-            .rfq_repository
-            .get_rfq_index(&rfq_id)
-            .await?
-            .unwrap_or(RfqIndex {
-                last_event_ts: timestamp,
-                count: 0,
-            });
-        index.last_event_ts = timestamp;
-        index.count += 1;
-        self.rfq_repository.save_rfq_index(&rfq_id, &index).await?;
+        // Save events
+        for event in &events {
+            self.rfq_repository.save_rfq_event(event).await?;
+        }
+
+        // Fold the events into meta and index, re-reading each whenever a
+        // concurrent message wins the write
+        let rfq_meta = retry_on_conflict(|| self.apply_to_meta(&rfq_id, &events)).await?;
+        retry_on_conflict(|| self.apply_to_index(&rfq_id, &events)).await?;
+        retry_on_conflict(|| self.apply_to_inbox(&rfq_id, &events)).await?;
 
         // Send notification
         self.email_service
-            .send_rfq_message_notification(&rfq_meta, &message_event)
+            .send_rfq_message_notification(&rfq_meta, &events[0], &attachments)
             .await?;
 
-        let response = PostMessageResponse {
+        Ok(PostMessageResponse {
             ts: timestamp.to_rfc3339(),
-        };
-
-        // Store idempotency result if key provided
-        if let Some(key) = idempotency_key {
-            let body_hash = self.compute_message_hash(&request)?;
-            let response_json = serde_json::to_string(&response)
-                .map_err(|_| DomainError::Internal("Failed to serialize response".to_string()))?;
-            self.idempotency_service
-                .store_idempotency(key, &body_hash, &response_json)
-                .await?;
-        }
-
-        Ok(response)
+        })
     }
 
-    fn compute_request_hash(&self, request: &CreateRfqRequest) -> Result<String> {
-        let json = serde_json::to_string(request)
-            .map_err(|_| DomainError::Internal("Failed to serialize request".to_string()))?;
-        Ok(format!("{:x}", sha2::Sha256::digest(json.as_bytes())))
-    }
+    /// Issue a short-lived download URL for one of the RFQ's attachments.
+    /// Only participants get one, or a key of the manufacturer's tenant with
+    /// `rfq:read`, and every issuance is audited first.
+    pub async fn attachment_download(
+        &self,
+        rfq_id: &str,
+        attachment_id: &str,
+        principal: &Principal,
+    ) -> Result<AttachmentDownloadResponse> {
+        let rfq_id = RfqId::new(rfq_id.to_string())?;
+        principal.check_access_to(rfq_id.as_str())?;
 
-    fn compute_message_hash(&self, request: &PostMessageRequest) -> Result<String> {
-        let json = serde_json::to_string(request)
-            .map_err(|_| DomainError::Internal("Failed to serialize request".to_string()))?;
+        let rfq_meta = self
+            .rfq_repository
+            .get_rfq_meta(&rfq_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound("RFQ not found".to_string()))?
+            .value;
+        policy::authorize(
+            principal,
+            Action::DownloadAttachment,
+            &Resource::Rfq(&rfq_meta),
+        )?;
+        let (email, role, api_key_id) = match principal {
+            Principal::TenantKey { key_id, .. } => {
+                let manufacturer = rfq_meta
+                    .participants
+                    .iter()
+                    .find(|p| p.role == ParticipantRole::Manufacturer)
+                    .map(|p| p.email.as_str())
+                    .unwrap_or_default();
+                (
+                    manufacturer,
+                    ParticipantRole::Manufacturer,
+                    Some(key_id.clone()),
+                )
+            }
+            _ => {
+                let (email, role) = principal.participant().ok_or_else(|| {
+                    DomainError::Forbidden(
+                        "Only participants of this RFQ can download its attachments".to_string(),
+                    )
+                })?;
+                (email, role, None)
+            }
+        };
+        let attachment = rfq_meta
+            .attachment(attachment_id)
+            .ok_or_else(|| DomainError::NotFound("Attachment not found".to_string()))?;
+
+        let issued_at = Utc::now();
+        self.rfq_repository
+            .save_attachment_access(&AttachmentAccess {
+                id: Uuid::new_v4().to_string(),
+                rfq_id: rfq_id.as_str().to_string(),
+                attachment_id: attachment.id.clone(),
+                email: email.to_string(),
+                role,
+                api_key_id,
+                issued_at,
+                expires_at: issued_at
+                    + chrono::Duration::seconds(i64::from(DOWNLOAD_URL_TTL_SECONDS)),
+            })
+            .await?;
+
+        let url = self
+            .image_service
+            .generate_presigned_download_url(
+                &S3Key::new(attachment.key.clone())?,
+                &attachment.file_name,
+                DOWNLOAD_URL_TTL_SECONDS,
+            )
+            .await?;
+
+        Ok(AttachmentDownloadResponse {
+            url,
+            file_name: attachment.file_name.clone(),
+            expires_in: DOWNLOAD_URL_TTL_SECONDS,
+        })
+    }
+
+    pub async fn change_status(
+        &self,
+        rfq_id: &str,
+        principal: &Principal,
+        request: ChangeStatusRequest,
+        idempotency_key: Option<&str>,
+    ) -> Result<ChangeStatusResponse> {
+        let rfq_id = RfqId::new(rfq_id.to_string())?;
+        let author = self
+            .authorize(&rfq_id, principal, Action::ChangeStatus)
+            .await?;
+
+        let body_hash = match idempotency_key {
+            Some(_) => Some(self.compute_request_hash(&(&author, &request))?),
+            None => None,
+        };
+
+        let scope = IdempotencyScope {
+            route: "POST /rfqs/{id}/status",
+            resource_id: rfq_id.as_str(),
+            principal,
+        };
+        self.with_idempotency(
+            &scope,
+            idempotency_key,
+            body_hash,
+            self.change_status_once(rfq_id.clone(), author, request),
+        )
+        .await
+        .map(|(response, _)| response)
+    }
+
+    async fn change_status_once(
+        &self,
+        rfq_id: RfqId,
+        author: EventAuthor,
+        request: ChangeStatusRequest,
+    ) -> Result<ChangeStatusResponse> {
+        let target = match request.status.as_str() {
+            "open" => RfqStatus::Open,
+            "closed" => RfqStatus::Closed,
+            "archived" => RfqStatus::Archived,
+            _ => return Err(DomainError::ValidationFailed("Invalid status".to_string())),
+        };
+        let note = request
+            .note
+            .filter(|note| !note.trim().is_empty())
+            .map(|note| MessageBody::new(note).map(|note| note.as_str().to_string()))
+            .transpose()?;
+
+        let (rfq_meta, status_event) = self
+            .record_status_change(
+                &rfq_id,
+                &author,
+                |meta| meta.transition(&target, &author),
+                note,
+                None,
+            )
+            .await?;
+
+        self.email_service
+            .send_rfq_status_notification(&rfq_meta, &status_event)
+            .await?;
+
+        Ok(ChangeStatusResponse {
+            status: rfq_meta.status,
+            ts: status_event.timestamp().to_rfc3339(),
+        })
+    }
+
+    /// Buyer awards the RFQ, optionally naming the winning offer
+    pub async fn award(
+        &self,
+        rfq_id: &str,
+        principal: &Principal,
+        request: RfqDecisionRequest,
+        idempotency_key: Option<&str>,
+    ) -> Result<ChangeStatusResponse> {
+        self.decide(
+            rfq_id,
+            principal,
+            Decision::Awarded,
+            request,
+            idempotency_key,
+        )
+        .await
+    }
+
+    /// Buyer declines the RFQ, closing it without a winner
+    pub async fn decline(
+        &self,
+        rfq_id: &str,
+        principal: &Principal,
+        request: RfqDecisionRequest,
+        idempotency_key: Option<&str>,
+    ) -> Result<ChangeStatusResponse> {
+        self.decide(
+            rfq_id,
+            principal,
+            Decision::Declined,
+            request,
+            idempotency_key,
+        )
+        .await
+    }
+
+    async fn decide(
+        &self,
+        rfq_id: &str,
+        principal: &Principal,
+        decision: Decision,
+        request: RfqDecisionRequest,
+        idempotency_key: Option<&str>,
+    ) -> Result<ChangeStatusResponse> {
+        let rfq_id = RfqId::new(rfq_id.to_string())?;
+        let author = self
+            .authorize(&rfq_id, principal, Action::DecideRfq)
+            .await?;
+
+        // Award and decline share a body shape, so the decision is part of the hash
+        let body_hash = match idempotency_key {
+            Some(_) => Some(self.compute_request_hash(&(decision, &request))?),
+            None => None,
+        };
+
+        let scope = IdempotencyScope {
+            route: match decision {
+                Decision::Awarded => "POST /rfqs/{id}/award",
+                Decision::Declined => "POST /rfqs/{id}/decline",
+            },
+            resource_id: rfq_id.as_str(),
+            principal,
+        };
+        self.with_idempotency(
+            &scope,
+            idempotency_key,
+            body_hash,
+            self.decide_once(rfq_id.clone(), author, decision, request),
+        )
+        .await
+        .map(|(response, _)| response)
+    }
+
+    async fn decide_once(
+        &self,
+        rfq_id: RfqId,
+        author: EventAuthor,
+        decision: Decision,
+        request: RfqDecisionRequest,
+    ) -> Result<ChangeStatusResponse> {
+        let reason = request
+            .reason
+            .filter(|reason| !reason.trim().is_empty())
+            .map(|reason| MessageBody::new(reason).map(|reason| reason.as_str().to_string()))
+            .transpose()?;
+        if let Some(reference) = &request.reference {
+            self.verify_offer(&rfq_id, reference).await?;
+        }
+
+        let (rfq_meta, status_event) = self
+            .record_status_change(
+                &rfq_id,
+                &author,
+                |meta| match decision {
+                    Decision::Awarded => meta.transition(&RfqStatus::Awarded, &author),
+                    Decision::Declined => meta.status.decline(&author),
+                },
+                reason,
+                request.reference,
+            )
+            .await?;
+
+        self.email_service
+            .send_rfq_outcome_notification(&rfq_meta, &status_event)
+            .await?;
+
+        Ok(ChangeStatusResponse {
+            status: rfq_meta.status,
+            ts: status_event.timestamp().to_rfc3339(),
+        })
+    }
+
+    /// Check a decision refers to something the manufacturer sent on this RFQ
+    async fn verify_offer(&self, rfq_id: &RfqId, reference: &OfferReference) -> Result<()> {
+        let is_offer = |event: &RfqEvent| {
+            *event.author() == EventAuthor::Manufacturer
+                && match (reference.kind, event) {
+                    (OfferKind::Message, RfqEvent::Message(e)) => e.base.id == reference.id,
+                    (OfferKind::Quote, RfqEvent::Quote(e)) => e.base.id == reference.id,
+                    (OfferKind::Attachment, RfqEvent::Attachment(e)) => {
+                        e.attachments.iter().any(|a| a.id == reference.id)
+                    }
+                    _ => false,
+                }
+        };
+
+        let mut found = false;
+        self.scan_events(rfq_id, |event| found |= is_offer(event))
+            .await?;
+
+        if !found {
+            return Err(DomainError::ValidationFailed(format!(
+                "No manufacturer {} '{}' on this RFQ",
+                reference.kind.as_str(),
+                reference.id
+            )));
+        }
+        Ok(())
+    }
+
+    /// The event at `position`, if it is still in the log
+    async fn event_at(&self, rfq_id: &RfqId, position: &str) -> Result<Option<RfqEvent>> {
+        let page = self
+            .rfq_repository
+            .list_rfq_events(rfq_id, &EventQuery::at(position))
+            .await?;
+        Ok(page
+            .events
+            .into_iter()
+            .find(|event| event.sort_key() == position))
+    }
+
+    /// Visit every event of an RFQ, oldest first
+    async fn scan_events(&self, rfq_id: &RfqId, mut visit: impl FnMut(&RfqEvent)) -> Result<()> {
+        let mut query = EventQuery {
+            limit: EVENT_SCAN_PAGE_SIZE,
+            ..EventQuery::default()
+        };
+        loop {
+            let page = self.rfq_repository.list_rfq_events(rfq_id, &query).await?;
+            page.events.iter().for_each(&mut visit);
+            match page.last_position {
+                Some(position) if page.has_more => query.after = Some(position),
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    /// Record a status change: check it against the current meta, save its
+    /// event, then fold the event into meta, index and inbox. The event goes
+    /// first, as for messages, so meta never shows a change the stream lacks.
+    /// If another change lands in between and this one no longer applies,
+    /// the fold skips the event and the caller gets a conflict.
+    async fn record_status_change(
+        &self,
+        rfq_id: &RfqId,
+        author: &EventAuthor,
+        transition: impl Fn(&RfqMeta) -> Result<StatusType>,
+        note: Option<String>,
+        reference: Option<OfferReference>,
+    ) -> Result<(RfqMeta, RfqEvent)> {
+        let current = self
+            .rfq_repository
+            .get_rfq_meta(rfq_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound("RFQ not found".to_string()))?
+            .value;
+        let event = RfqEvent::new_status(
+            rfq_id.as_str().to_string(),
+            author.clone(),
+            transition(&current)?,
+            note,
+            reference,
+        );
+        self.rfq_repository.save_rfq_event(&event).await?;
+
+        // The event is in the stream even when it lost a race, so the index
+        // and inbox count it either way
+        let folded = retry_on_conflict(|| self.apply_status_to_meta(rfq_id, &event)).await?;
+        retry_on_conflict(|| self.apply_to_index(rfq_id, std::slice::from_ref(&event))).await?;
+        retry_on_conflict(|| self.apply_to_inbox(rfq_id, std::slice::from_ref(&event))).await?;
+
+        Ok((folded?, event))
+    }
+
+    /// Fold a saved status event into meta, conditional on the version read.
+    /// The outer error is retried; the inner one reports a change that no
+    /// longer applies.
+    async fn apply_status_to_meta(
+        &self,
+        rfq_id: &RfqId,
+        event: &RfqEvent,
+    ) -> Result<Result<RfqMeta>> {
+        let current = self
+            .rfq_repository
+            .get_rfq_meta(rfq_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound("RFQ not found".to_string()))?;
+        if !current.value.accepts(event) {
+            return Ok(Err(DomainError::Conflict(format!(
+                "RFQ became {} meanwhile",
+                current.value.status.as_str()
+            ))));
+        }
+
+        let mut meta = current.value;
+        meta.apply(event);
+        self.rfq_repository
+            .save_rfq_meta(&meta, Some(&current.version))
+            .await?;
+        Ok(Ok(meta))
+    }
+
+    pub async fn submit_quote(
+        &self,
+        rfq_id: &str,
+        principal: &Principal,
+        request: SubmitQuoteRequest,
+        idempotency_key: Option<&str>,
+    ) -> Result<SubmitQuoteResponse> {
+        let rfq_id = RfqId::new(rfq_id.to_string())?;
+        self.authorize(&rfq_id, principal, Action::SubmitQuote)
+            .await?;
+
+        let body_hash = match idempotency_key {
+            Some(_) => Some(self.compute_request_hash(&request)?),
+            None => None,
+        };
+
+        let scope = IdempotencyScope {
+            route: "POST /rfqs/{id}/quotes",
+            resource_id: rfq_id.as_str(),
+            principal,
+        };
+        self.with_idempotency(
+            &scope,
+            idempotency_key,
+            body_hash,
+            self.submit_quote_once(rfq_id.clone(), request),
+        )
+        .await
+        .map(|(response, _)| response)
+    }
+
+    async fn submit_quote_once(
+        &self,
+        rfq_id: RfqId,
+        request: SubmitQuoteRequest,
+    ) -> Result<SubmitQuoteResponse> {
+        let quote = quote_from_request(request)?;
+        quote.validate(Utc::now().date_naive())?;
+
+        // The conditional meta write orders racing revisions, so each quote
+        // supersedes exactly the one it read as latest
+        let quote_event = retry_on_conflict(|| self.apply_quote(&rfq_id, quote.clone())).await??;
+
+        self.rfq_repository.save_rfq_event(&quote_event).await?;
+        retry_on_conflict(|| self.apply_to_index(&rfq_id, std::slice::from_ref(&quote_event)))
+            .await?;
+        retry_on_conflict(|| self.apply_to_inbox(&rfq_id, std::slice::from_ref(&quote_event)))
+            .await?;
+
+        let RfqEvent::Quote(quote_event) = quote_event else {
+            unreachable!("apply_quote builds quote events");
+        };
+        Ok(SubmitQuoteResponse {
+            id: quote_event.base.id,
+            revision: quote_event.revision,
+            ts: quote_event.base.ts.to_rfc3339(),
+        })
+    }
+
+    /// Record `quote` as the next revision in the stored meta. An RFQ that is
+    /// not open comes back inside `Ok` so only write conflicts are retried.
+    async fn apply_quote(&self, rfq_id: &RfqId, quote: Quote) -> Result<Result<RfqEvent>> {
+        let current = self
+            .rfq_repository
+            .get_rfq_meta(rfq_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound("RFQ not found".to_string()))?;
+
+        if !current.value.status.accepts_messages() {
+            return Ok(Err(DomainError::Conflict(format!(
+                "RFQ is {}",
+                current.value.status.as_str()
+            ))));
+        }
+
+        let latest = current.value.latest_quote.as_ref();
+        let event = RfqEvent::new_quote(
+            rfq_id.as_str().to_string(),
+            EventAuthor::Manufacturer,
+            quote,
+            latest.map_or(1, |latest| latest.revision + 1),
+            latest.map(|latest| latest.event_id.clone()),
+        );
+
+        let mut meta = current.value;
+        meta.apply(&event);
+        self.rfq_repository
+            .save_rfq_meta(&meta, Some(&current.version))
+            .await?;
+
+        Ok(Ok(event))
+    }
+
+    /// Validate the files a request references and check each was really
+    /// uploaded for `tenant_id` with the size and type claimed
+    async fn verify_attachments(
+        &self,
+        tenant_id: &TenantId,
+        attachments: Option<Vec<AttachmentDto>>,
+    ) -> Result<Vec<AttachmentRef>> {
+        let mut verified = Vec::new();
+        for attachment_dto in attachments.unwrap_or_default() {
+            let content_type = ContentType::new(attachment_dto.content_type)?;
+            let file_size = FileSize::new(attachment_dto.size_bytes)?;
+            let key = S3Key::new(attachment_dto.upload_key)?;
+            let file_name = attachment_dto.file_name;
+
+            let not_found = || {
+                DomainError::ValidationFailed(format!(
+                    "Attachment '{}' was not uploaded for this tenant",
+                    file_name
+                ))
+            };
+
+            // Only plain keys under the tenant's prefix; the object's own
+            // tenant metadata must agree
+            let tenant_prefix = format!("tenants/{}/", tenant_id.as_str());
+            let in_tenant = key
+                .as_str()
+                .strip_prefix(&tenant_prefix)
+                .is_some_and(|rest| {
+                    rest.split('/')
+                        .all(|s| !s.is_empty() && s != "." && s != "..")
+                });
+            if !in_tenant {
+                return Err(not_found());
+            }
+
+            let uploaded = self
+                .image_service
+                .head_upload(&key)
+                .await?
+                .ok_or_else(not_found)?;
+            if uploaded.tenant_id.as_deref() != Some(tenant_id.as_str()) {
+                return Err(not_found());
+            }
+            if uploaded.size_bytes != file_size.as_u64() {
+                return Err(DomainError::ValidationFailed(format!(
+                    "Attachment '{}' is {} bytes, not the {} declared",
+                    file_name,
+                    uploaded.size_bytes,
+                    file_size.as_u64()
+                )));
+            }
+            if uploaded.content_type.as_deref() != Some(content_type.as_str()) {
+                return Err(DomainError::ValidationFailed(format!(
+                    "Attachment '{}' was uploaded as {}, not {}",
+                    file_name,
+                    uploaded
+                        .content_type
+                        .as_deref()
+                        .unwrap_or("an unknown type"),
+                    content_type.as_str()
+                )));
+            }
+
+            verified.push(AttachmentRef {
+                id: Uuid::new_v4().to_string(),
+                file_name,
+                content_type: content_type.as_str().to_string(),
+                size_bytes: file_size.as_u64(),
+                key: key.as_str().to_string(),
+            });
+        }
+
+        Ok(verified)
+    }
+
+    /// Check `principal` may perform the write `action` on the RFQ and
+    /// return the author it is attributed to
+    async fn authorize(
+        &self,
+        rfq_id: &RfqId,
+        principal: &Principal,
+        action: Action,
+    ) -> Result<EventAuthor> {
+        principal.check_access_to(rfq_id.as_str())?;
+        let rfq_meta = self
+            .rfq_repository
+            .get_rfq_meta(rfq_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound("RFQ not found".to_string()))?;
+        policy::authorize_author(principal, action, &Resource::Rfq(&rfq_meta.value))
+    }
+
+    /// Fold saved events into the RFQ meta, conditional on the version read
+    async fn apply_to_meta(&self, rfq_id: &RfqId, events: &[RfqEvent]) -> Result<RfqMeta> {
+        let current = self
+            .rfq_repository
+            .get_rfq_meta(rfq_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound("RFQ not found".to_string()))?;
+
+        let mut meta = current.value;
+        for event in events {
+            meta.apply(event);
+        }
+
+        self.rfq_repository
+            .save_rfq_meta(&meta, Some(&current.version))
+            .await?;
+        Ok(meta)
+    }
+
+    /// Count saved events in the RFQ index, conditional on the version read
+    async fn apply_to_index(&self, rfq_id: &RfqId, events: &[RfqEvent]) -> Result<()> {
+        let (mut index, version) = match self.rfq_repository.get_rfq_index(rfq_id).await? {
+            Some(current) => (current.value, Some(current.version)),
+            None => (RfqIndex::new(events[0].timestamp()), None),
+        };
+
+        for event in events {
+            index.apply(event);
+        }
+
+        self.rfq_repository
+            .save_rfq_index(rfq_id, &index, version.as_deref())
+            .await?;
+        Ok(())
+    }
+
+    /// Bring the manufacturer's inbox entry up to date with saved events.
+    /// The meta is read afresh so a slower writer never restores an older
+    /// status; the save is conditional on the entry version read.
+    async fn apply_to_inbox(&self, rfq_id: &RfqId, events: &[RfqEvent]) -> Result<()> {
+        let rfq_meta = self
+            .rfq_repository
+            .get_rfq_meta(rfq_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound("RFQ not found".to_string()))?
+            .value;
+        let manufacturer_id = ManufacturerId::new(rfq_meta.manufacturer_id.clone())?;
+
+        let (mut entry, version) = match self
+            .rfq_repository
+            .get_inbox_entry(&manufacturer_id, rfq_id)
+            .await?
+        {
+            Some(current) => (current.value, Some(current.version)),
+            None => (RfqInboxEntry::new(&rfq_meta), None),
+        };
+
+        for event in events {
+            entry.apply(event);
+        }
+        entry.refresh(&rfq_meta);
+
+        self.rfq_repository
+            .save_inbox_entry(&manufacturer_id, &entry, version.as_deref())
+            .await?;
+        Ok(())
+    }
+
+    /// Run `operation` under an idempotency claim when the client sent a key,
+    /// returning its result and whether it was replayed. Completed responses
+    /// are replayed; failures release the claim so the client can retry with
+    /// the same key.
+    async fn with_idempotency<T, F>(
+        &self,
+        scope: &IdempotencyScope<'_>,
+        idempotency_key: Option<&str>,
+        body_hash: Option<String>,
+        operation: F,
+    ) -> Result<(T, bool)>
+    where
+        T: Serialize + DeserializeOwned,
+        F: Future<Output = Result<T>>,
+    {
+        let (Some(key), Some(body_hash)) = (idempotency_key, body_hash) else {
+            return Ok((operation.await?, false));
+        };
+        let key = &scope.key(key);
+
+        let claim = match self
+            .idempotency_service
+            .claim_idempotency(key, &body_hash)
+            .await?
+        {
+            IdempotencyClaim::Acquired(claim) => claim,
+            IdempotencyClaim::Replay(cached_response) => {
+                let response = serde_json::from_str(&cached_response).map_err(|_| {
+                    DomainError::Internal("Failed to deserialize cached response".to_string())
+                })?;
+                return Ok((response, true));
+            }
+        };
+
+        let response = match operation.await {
+            Ok(response) => response,
+            Err(e) => {
+                if let Err(release_error) = self
+                    .idempotency_service
+                    .release_idempotency(key, &claim)
+                    .await
+                {
+                    tracing::warn!("Failed to release idempotency key: {}", release_error);
+                }
+                return Err(e);
+            }
+        };
+
+        let response_json = serde_json::to_string(&response)
+            .map_err(|_| DomainError::Internal("Failed to serialize response".to_string()))?;
+        match self
+            .idempotency_service
+            .store_idempotency(key, &claim, &body_hash, &response_json)
+            .await
+        {
+            // The operation took effect; failing now would only invite a retry
+            // that repeats it
+            Err(DomainError::Conflict(message)) => {
+                tracing::warn!("Idempotency response not stored: {}", message);
+            }
+            result => result?,
+        }
+
+        Ok((response, false))
+    }
+
+    fn compute_request_hash<T: Serialize>(&self, request: &T) -> Result<String> {
+        let json = serde_json::to_string(request)
+            .map_err(|_| DomainError::Internal("Failed to serialize request".to_string()))?;
         Ok(format!("{:x}", sha2::Sha256::digest(json.as_bytes())))
     }
 
@@ -343,6 +1625,7 @@ impl RfqService {
                 by: format!("{:?}", e.base.by).to_lowercase(),
                 status: format!("{:?}", e.status).to_lowercase(),
                 note: e.note.clone(),
+                reference: e.reference.clone(),
             },
             RfqEvent::Attachment(e) => RfqEventDto::Attachment {
                 id: e.base.id.clone(),
@@ -350,7 +1633,454 @@ impl RfqService {
                 ts: e.base.ts.to_rfc3339(),
                 by: format!("{:?}", e.base.by).to_lowercase(),
                 attachments: e.attachments.iter().map(|a| a.clone().into()).collect(),
+                message_id: e.message_id.clone(),
             },
+            RfqEvent::Quote(e) => RfqEventDto::Quote {
+                id: e.base.id.clone(),
+                rfq_id: e.base.rfq_id.clone(),
+                ts: e.base.ts.to_rfc3339(),
+                by: format!("{:?}", e.base.by).to_lowercase(),
+                revision: e.revision,
+                supersedes: e.supersedes.clone(),
+                line_items: e
+                    .quote
+                    .line_items
+                    .iter()
+                    .map(|i| i.clone().into())
+                    .collect(),
+                charges: e.quote.charges.iter().map(|c| c.clone().into()).collect(),
+                lead_time_days: e.quote.lead_time_days.clone().map(Into::into),
+                incoterms: e.quote.incoterms,
+                valid_until: e.quote.valid_until.to_string(),
+                note: e.quote.note.clone(),
+            },
+        }
+    }
+}
+
+/// Prefix that marks a bearer credential as a tenant API key
+pub const API_KEY_PREFIX: &str = "tk_";
+
+/// Longest label a tenant API key can have
+const MAX_API_KEY_NAME_CHARS: usize = 100;
+
+/// Issues, lists, revokes and verifies tenant API keys. A key reads
+/// `tk_{tenant_id}.{key_id}.{secret}`; only its SHA-256 is stored.
+pub struct ApiKeyService {
+    api_key_repository: Arc<dyn ApiKeyRepository + Send + Sync>,
+}
+
+impl ApiKeyService {
+    pub fn new(api_key_repository: Arc<dyn ApiKeyRepository + Send + Sync>) -> Self {
+        Self { api_key_repository }
+    }
+
+    /// Create a key for `tenant_id`. The key itself is only in the response.
+    pub async fn create_key(
+        &self,
+        tenant_id: &str,
+        principal: &Principal,
+        request: CreateApiKeyRequest,
+    ) -> Result<CreateApiKeyResponse> {
+        let tenant_id = TenantId::new(tenant_id.to_string())?;
+        policy::authorize(
+            principal,
+            Action::ManageApiKeys,
+            &Resource::Tenant(tenant_id.as_str()),
+        )?;
+        let name = request.name.trim();
+        if name.is_empty() || name.chars().count() > MAX_API_KEY_NAME_CHARS {
+            return Err(DomainError::ValidationFailed(format!(
+                "API key name must be 1-{} characters",
+                MAX_API_KEY_NAME_CHARS
+            )));
+        }
+        let mut scopes = Vec::with_capacity(request.scopes.len());
+        for scope in request.scopes {
+            if !scopes.contains(&scope) {
+                scopes.push(scope);
+            }
+        }
+        if scopes.is_empty() {
+            return Err(DomainError::ValidationFailed(
+                "API key needs at least one scope".to_string(),
+            ));
+        }
+        let now = Utc::now();
+        let expires_at = request
+            .expires_at
+            .as_deref()
+            .map(|ts| {
+                chrono::DateTime::parse_from_rfc3339(ts)
+                    .map(|ts| ts.with_timezone(&Utc))
+                    .map_err(|_| {
+                        DomainError::ValidationFailed("Invalid expires_at timestamp".to_string())
+                    })
+            })
+            .transpose()?;
+        if expires_at.is_some_and(|expires_at| expires_at <= now) {
+            return Err(DomainError::ValidationFailed(
+                "expires_at must be in the future".to_string(),
+            ));
+        }
+
+        let id = ApiKeyId::generate();
+        let key = format!(
+            "{}{}.{}.{}{}",
+            API_KEY_PREFIX,
+            tenant_id.as_str(),
+            id.as_str(),
+            Uuid::new_v4().simple(),
+            Uuid::new_v4().simple()
+        );
+        let api_key = TenantApiKey {
+            id: id.as_str().to_string(),
+            tenant_id: tenant_id.as_str().to_string(),
+            name: name.to_string(),
+            scopes,
+            key_hash: hash_api_key(&key),
+            created_at: now,
+            expires_at,
+            revoked_at: None,
+        };
+        self.api_key_repository.save_api_key(&api_key).await?;
+
+        Ok(CreateApiKeyResponse {
+            key,
+            api_key: api_key.into(),
+        })
+    }
+
+    pub async fn list_keys(
+        &self,
+        tenant_id: &str,
+        principal: &Principal,
+    ) -> Result<ApiKeysResponse> {
+        let tenant_id = TenantId::new(tenant_id.to_string())?;
+        policy::authorize(
+            principal,
+            Action::ManageApiKeys,
+            &Resource::Tenant(tenant_id.as_str()),
+        )?;
+        let mut keys = self.api_key_repository.list_api_keys(&tenant_id).await?;
+        keys.sort_by_key(|key| std::cmp::Reverse(key.created_at));
+
+        Ok(ApiKeysResponse {
+            items: keys.into_iter().map(Into::into).collect(),
+        })
+    }
+
+    /// Revoke a key for good; revoking it again changes nothing
+    pub async fn revoke_key(
+        &self,
+        tenant_id: &str,
+        key_id: &str,
+        principal: &Principal,
+    ) -> Result<ApiKeyDto> {
+        let tenant_id = TenantId::new(tenant_id.to_string())?;
+        let key_id = ApiKeyId::new(key_id.to_string())?;
+        policy::authorize(
+            principal,
+            Action::ManageApiKeys,
+            &Resource::Tenant(tenant_id.as_str()),
+        )?;
+        let mut api_key = self
+            .api_key_repository
+            .get_api_key(&tenant_id, &key_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound("API key not found".to_string()))?;
+
+        if api_key.revoked_at.is_none() {
+            api_key.revoked_at = Some(Utc::now());
+            self.api_key_repository.save_api_key(&api_key).await?;
+        }
+        Ok(api_key.into())
+    }
+
+    /// Resolve a presented key into the tenant principal it stands for
+    pub async fn authenticate(&self, key: &str) -> Result<Principal> {
+        let invalid = || DomainError::Unauthorized("Invalid API key".to_string());
+
+        let mut parts = key
+            .strip_prefix(API_KEY_PREFIX)
+            .ok_or_else(invalid)?
+            .splitn(3, '.');
+        let (Some(tenant_id), Some(key_id), Some(_secret)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid());
+        };
+        let tenant_id = TenantId::new(tenant_id.to_string()).map_err(|_| invalid())?;
+        let key_id = ApiKeyId::new(key_id.to_string()).map_err(|_| invalid())?;
+
+        let api_key = self
+            .api_key_repository
+            .get_api_key(&tenant_id, &key_id)
+            .await?
+            .filter(|api_key| api_key.key_hash == hash_api_key(key))
+            .ok_or_else(invalid)?;
+        api_key.check_usable(Utc::now())?;
+
+        Ok(Principal::TenantKey {
+            tenant_id: api_key.tenant_id,
+            key_id: api_key.id,
+            scopes: api_key.scopes,
+        })
+    }
+}
+
+fn hash_api_key(key: &str) -> String {
+    format!("{:x}", sha2::Sha256::digest(key.as_bytes()))
+}
+
+/// Manufacturer profile management, by admins and by tenants themselves
+pub struct ManufacturerService {
+    manufacturer_repository: Arc<dyn ManufacturerRepository + Send + Sync>,
+}
+
+impl ManufacturerService {
+    pub fn new(manufacturer_repository: Arc<dyn ManufacturerRepository + Send + Sync>) -> Self {
+        Self {
+            manufacturer_repository,
+        }
+    }
+
+    /// Create a manufacturer profile (admin)
+    pub async fn create_manufacturer(
+        &self,
+        principal: &Principal,
+        request: CreateManufacturerRequest,
+    ) -> Result<CreateManufacturerResponse> {
+        policy::authorize(
+            principal,
+            Action::CreateManufacturer,
+            &Resource::Tenant(&request.tenant_id),
+        )?;
+
+        // Convert DTO to domain entity
+        let manufacturer = Manufacturer {
+            id: ManufacturerId::generate().as_str().to_string(),
+            tenant_id: request.tenant_id.clone(),
+            name: request.name,
+            description: request.description,
+            location: request.location.map(location_from_dto),
+            categories: request.capabilities.clone(), // Using capabilities as categories for now
+            capabilities: Some(request.capabilities),
+            contact_email: Some(request.contact_email),
+            media: None, // Will be populated later via image uploads
+            updated_at: Utc::now(),
+        };
+
+        // Save manufacturer - convert to ManufacturerProfile first
+        let profile = ManufacturerProfile {
+            id: manufacturer.id.clone(),
+            tenant_id: manufacturer.tenant_id.clone(),
+            name: manufacturer.name.clone(),
+            description: manufacturer.description.clone(),
+            location: manufacturer.location.clone(),
+            categories: manufacturer.categories.clone(),
+            capabilities: manufacturer.capabilities.clone(),
+            contact_email: manufacturer.contact_email.clone(),
+            media: manufacturer.media.clone(),
+            offerings: None, // Will be populated later
+            updated_at: manufacturer.updated_at,
+        };
+
+        self.manufacturer_repository
+            .save_manufacturer(&profile)
+            .await
+            .map_err(|e| DomainError::Internal(format!("Failed to create manufacturer: {}", e)))?;
+
+        Ok(CreateManufacturerResponse {
+            id: manufacturer.id,
+            tenant_id: manufacturer.tenant_id,
+        })
+    }
+
+    /// Replace a profile's details and offerings. Tenants edit their own
+    /// manufacturers with a `profile:write` key; admins edit any.
+    pub async fn update_manufacturer(
+        &self,
+        manufacturer_id: &str,
+        principal: &Principal,
+        request: UpdateManufacturerRequest,
+    ) -> Result<ManufacturerProfile> {
+        let manufacturer_id = ManufacturerId::new(manufacturer_id.to_string())?;
+        let current = self
+            .manufacturer_repository
+            .get_manufacturer(&manufacturer_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound("Manufacturer not found".to_string()))?;
+        policy::authorize(
+            principal,
+            Action::UpdateManufacturer,
+            &Resource::Manufacturer(&current),
+        )?;
+
+        if request.name.trim().is_empty() {
+            return Err(DomainError::ValidationFailed(
+                "Manufacturer name is required".to_string(),
+            ));
         }
+        let contact_email = Email::new(request.contact_email)?;
+        let offerings = request
+            .offerings
+            .into_iter()
+            .map(|offering| Offering {
+                id: offering.id.unwrap_or_else(|| {
+                    format!("off_{}", &Uuid::new_v4().simple().to_string()[..8])
+                }),
+                title: offering.title,
+                materials: offering.materials,
+                lead_time_days: offering.lead_time_days.map(|lead_time| LeadTime {
+                    min: lead_time.min,
+                    max: lead_time.max,
+                }),
+                media: offering.media.map(|media| {
+                    media
+                        .into_iter()
+                        .map(|media| MediaRef {
+                            image_manifest_id: media.image_manifest_id,
+                            alt: media.alt,
+                        })
+                        .collect()
+                }),
+            })
+            .collect();
+
+        let profile = ManufacturerProfile {
+            id: current.id,
+            tenant_id: current.tenant_id,
+            name: request.name,
+            description: request.description,
+            location: request.location.map(location_from_dto),
+            categories: request.capabilities.clone(),
+            capabilities: Some(request.capabilities),
+            contact_email: Some(contact_email.as_str().to_string()),
+            media: current.media,
+            offerings: Some(offerings),
+            updated_at: Utc::now(),
+        };
+        self.manufacturer_repository
+            .save_manufacturer(&profile)
+            .await?;
+
+        Ok(profile)
+    }
+}
+
+/// Presigned uploads into the tenant the caller's credentials are bound to
+pub struct UploadService {
+    image_service: Arc<dyn ImageService + Send + Sync>,
+    rfq_repository: Arc<dyn RfqRepository + Send + Sync>,
+    manufacturer_repository: Arc<dyn ManufacturerRepository + Send + Sync>,
+}
+
+impl UploadService {
+    pub fn new(
+        image_service: Arc<dyn ImageService + Send + Sync>,
+        rfq_repository: Arc<dyn RfqRepository + Send + Sync>,
+        manufacturer_repository: Arc<dyn ManufacturerRepository + Send + Sync>,
+    ) -> Self {
+        Self {
+            image_service,
+            rfq_repository,
+            manufacturer_repository,
+        }
+    }
+
+    /// Presign an upload into a tenant derived from the principal: a tenant
+    /// key's own, or that of the RFQ, campaign or manufacturer an access
+    /// token was issued for. Callers without a token name the manufacturer
+    /// they are writing to. A `tenant_id` in the request must match.
+    pub async fn presign_upload(
+        &self,
+        principal: &Principal,
+        request: PresignUploadRequest,
+    ) -> Result<PresignUploadResponse> {
+        let content_type = ContentType::new(request.content_type)?;
+        let file_size = FileSize::new(request.size_bytes)?;
+
+        let tenant_id = match principal {
+            Principal::TenantKey { tenant_id, .. } => {
+                policy::authorize(
+                    principal,
+                    Action::PresignUpload,
+                    &Resource::Tenant(tenant_id),
+                )?;
+                tenant_id.clone()
+            }
+            Principal::Buyer { resource_id, .. }
+            | Principal::ManufacturerUser { resource_id, .. } => {
+                self.bound_tenant(principal, resource_id).await?
+            }
+            Principal::Anonymous | Principal::Admin { .. } | Principal::System => {
+                let manufacturer_id = request.manufacturer_id.as_deref().ok_or_else(|| {
+                    DomainError::ValidationFailed(
+                        "manufacturer_id is required without an access token or API key"
+                            .to_string(),
+                    )
+                })?;
+                self.bound_tenant(principal, manufacturer_id).await?
+            }
+        };
+        if request
+            .tenant_id
+            .as_deref()
+            .is_some_and(|requested| requested != tenant_id)
+        {
+            return Err(DomainError::Forbidden(
+                "Not allowed to upload for this tenant".to_string(),
+            ));
+        }
+
+        self.image_service
+            .generate_presigned_upload_url(&TenantId::new(tenant_id)?, &content_type, &file_size)
+            .await
+    }
+
+    /// Tenant of the RFQ, campaign or manufacturer `id`, once `principal` is
+    /// allowed to upload for it. Which one is told by the id's prefix.
+    async fn bound_tenant(&self, principal: &Principal, id: &str) -> Result<String> {
+        let authorize = |resource: &Resource| {
+            policy::authorize(principal, Action::PresignUpload, resource).map(|_| ())
+        };
+
+        if id.starts_with("mfg_") {
+            let manufacturer = self
+                .manufacturer_repository
+                .get_manufacturer(&ManufacturerId::new(id.to_string())?)
+                .await?
+                .ok_or_else(|| DomainError::NotFound("Manufacturer not found".to_string()))?;
+            authorize(&Resource::Manufacturer(&manufacturer))?;
+            Ok(manufacturer.tenant_id)
+        } else if id.starts_with("c_") {
+            let campaign = self
+                .rfq_repository
+                .get_campaign(&CampaignId::new(id.to_string())?)
+                .await?
+                .ok_or_else(|| DomainError::NotFound("Campaign not found".to_string()))?;
+            authorize(&Resource::Campaign(&campaign))?;
+            Ok(campaign.tenant_id)
+        } else {
+            let rfq_meta = self
+                .rfq_repository
+                .get_rfq_meta(&RfqId::new(id.to_string())?)
+                .await?
+                .ok_or_else(|| DomainError::NotFound("RFQ not found".to_string()))?
+                .value;
+            authorize(&Resource::Rfq(&rfq_meta))?;
+            Ok(rfq_meta.tenant_id)
+        }
+    }
+}
+
+fn location_from_dto(location: LocationDto) -> Location {
+    Location {
+        city: location.city,
+        state: location.state,
+        country: location.country,
+        lat: location.lat,
+        lng: location.lng,
     }
 }
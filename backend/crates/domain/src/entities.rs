@@ -1,6 +1,10 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::error::{DomainError, Result};
+use crate::events::{EventAuthor, RfqEvent, StatusEvent, StatusType};
+use crate::value_objects::{Currency, Money, Quantity, Rounding};
+
 /// Location information for manufacturers
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Location {
@@ -42,6 +46,245 @@ pub struct LeadTime {
     pub max: Option<u32>,
 }
 
+/// Incoterms 2020 delivery terms
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Incoterm {
+    Exw,
+    Fca,
+    Cpt,
+    Cip,
+    Dap,
+    Dpu,
+    Ddp,
+    Fas,
+    Fob,
+    Cfr,
+    Cif,
+}
+
+impl Incoterm {
+    pub fn parse(code: &str) -> Result<Self> {
+        serde_json::from_value(serde_json::Value::String(code.to_ascii_uppercase()))
+            .map_err(|_| DomainError::ValidationFailed(format!("Unknown incoterm '{}'", code)))
+    }
+}
+
+/// One priced part in a quote
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QuoteLineItem {
+    pub part: String,
+    pub quantity: Quantity,
+    /// Price of one of `quantity`'s unit
+    pub unit_price: Money,
+}
+
+impl QuoteLineItem {
+    /// `unit_price` times `quantity`, rounded to the currency's minor unit
+    pub fn total(&self) -> Result<Money> {
+        self.unit_price
+            .mul_quantity(&self.quantity, Rounding::HalfEven)
+    }
+}
+
+/// Kind of one-off charge on a quote
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChargeKind {
+    Tooling,
+    Nre,
+}
+
+/// One-off charge on top of the line items
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QuoteCharge {
+    pub kind: ChargeKind,
+    pub description: Option<String>,
+    pub amount: Money,
+}
+
+/// Priced offer a manufacturer makes on an RFQ
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Quote {
+    pub line_items: Vec<QuoteLineItem>,
+    #[serde(default)]
+    pub charges: Vec<QuoteCharge>,
+    pub lead_time_days: Option<LeadTime>,
+    pub incoterms: Option<Incoterm>,
+    /// Last day the offer can be accepted
+    pub valid_until: NaiveDate,
+    pub note: Option<String>,
+}
+
+impl Quote {
+    const MAX_LINE_ITEMS: usize = 100;
+    const MAX_CHARGES: usize = 20;
+    const MAX_PART_LENGTH: usize = 200;
+    const MAX_DESCRIPTION_LENGTH: usize = 500;
+
+    /// Check the quote is complete and priced in a single currency
+    pub fn validate(&self, today: NaiveDate) -> Result<()> {
+        let invalid = |message: String| Err(DomainError::ValidationFailed(message));
+
+        if self.line_items.is_empty() || self.line_items.len() > Self::MAX_LINE_ITEMS {
+            return invalid(format!(
+                "A quote needs 1-{} line items",
+                Self::MAX_LINE_ITEMS
+            ));
+        }
+        if self.charges.len() > Self::MAX_CHARGES {
+            return invalid(format!(
+                "A quote can carry at most {} charges",
+                Self::MAX_CHARGES
+            ));
+        }
+
+        for item in &self.line_items {
+            if item.part.trim().is_empty() || item.part.len() > Self::MAX_PART_LENGTH {
+                return invalid(format!(
+                    "Line item part must be 1-{} characters",
+                    Self::MAX_PART_LENGTH
+                ));
+            }
+            if !item.quantity.is_positive() {
+                return invalid(format!("Quantity of '{}' must be positive", item.part));
+            }
+            if item.unit_price.is_negative() {
+                return invalid(format!("Unit price of '{}' cannot be negative", item.part));
+            }
+        }
+        for charge in &self.charges {
+            if charge.amount.is_negative() {
+                return invalid("Charges cannot be negative".to_string());
+            }
+            if charge
+                .description
+                .as_ref()
+                .is_some_and(|d| d.len() > Self::MAX_DESCRIPTION_LENGTH)
+            {
+                return invalid(format!(
+                    "Charge description must be at most {} characters",
+                    Self::MAX_DESCRIPTION_LENGTH
+                ));
+            }
+        }
+
+        let currency = self.currency();
+        if self
+            .line_items
+            .iter()
+            .map(|i| i.unit_price.currency())
+            .chain(self.charges.iter().map(|c| c.amount.currency()))
+            .any(|c| c != currency)
+        {
+            return invalid("All prices in a quote must use the same currency".to_string());
+        }
+        self.total()?;
+
+        if let Some(LeadTime {
+            min: Some(min),
+            max: Some(max),
+        }) = self.lead_time_days
+        {
+            if min > max {
+                return invalid("Lead time minimum exceeds its maximum".to_string());
+            }
+        }
+
+        if self.valid_until < today {
+            return invalid("valid_until is in the past".to_string());
+        }
+
+        Ok(())
+    }
+
+    /// Currency the quote is priced in, that of its first line item; a valid
+    /// quote always has one
+    pub fn currency(&self) -> &Currency {
+        self.line_items[0].unit_price.currency()
+    }
+
+    /// Sum of all line totals and charges
+    pub fn total(&self) -> Result<Money> {
+        let line_totals = self
+            .line_items
+            .iter()
+            .map(QuoteLineItem::total)
+            .collect::<Result<Vec<_>>>()?;
+        Money::sum(
+            line_totals
+                .iter()
+                .chain(self.charges.iter().map(|charge| &charge.amount)),
+            self.currency().clone(),
+        )
+    }
+}
+
+/// Latest quote on an RFQ, as kept in its meta
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QuoteSummary {
+    /// Id of the quote event
+    pub event_id: String,
+    /// Sort key of the quote event, so it can be read without a scan
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<String>,
+    pub revision: u32,
+    pub valid_until: NaiveDate,
+}
+
+/// Kind of manufacturer event a buyer can point to as the winning offer
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OfferKind {
+    Message,
+    Attachment,
+    Quote,
+}
+
+impl OfferKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OfferKind::Message => "message",
+            OfferKind::Attachment => "attachment",
+            OfferKind::Quote => "quote",
+        }
+    }
+}
+
+/// Manufacturer message, attachment or quote an award or decline refers to.
+/// `id` is the event id, or the attachment id for attachments.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OfferReference {
+    pub kind: OfferKind,
+    pub id: String,
+}
+
+/// How the buyer concluded an RFQ
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Decision {
+    Awarded,
+    Declined,
+}
+
+/// Buyer's decision on an RFQ, as kept in its meta
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RfqOutcome {
+    pub decision: Decision,
+    pub decided_at: DateTime<Utc>,
+    pub reference: Option<OfferReference>,
+}
+
+impl RfqOutcome {
+    fn from_event(decision: Decision, event: &StatusEvent) -> Self {
+        Self {
+            decision,
+            decided_at: event.base.ts,
+            reference: event.reference.clone(),
+        }
+    }
+}
+
 /// Tenant entity
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Tenant {
@@ -51,6 +294,63 @@ pub struct Tenant {
     pub created_at: DateTime<Utc>,
 }
 
+/// What a tenant API key may be used for
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ApiKeyScope {
+    /// Edit the tenant's manufacturer profiles and offerings
+    #[serde(rename = "profile:write")]
+    ProfileWrite,
+    /// Read the inboxes and RFQs of the tenant's manufacturers
+    #[serde(rename = "rfq:read")]
+    RfqRead,
+    /// Reply, quote and change status on those RFQs as the manufacturer
+    #[serde(rename = "rfq:reply")]
+    RfqReply,
+}
+
+impl ApiKeyScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiKeyScope::ProfileWrite => "profile:write",
+            ApiKeyScope::RfqRead => "rfq:read",
+            ApiKeyScope::RfqReply => "rfq:reply",
+        }
+    }
+}
+
+/// API key a tenant's own systems, e.g. an ERP, use to manage its data.
+/// Only a SHA-256 digest of the key is stored.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TenantApiKey {
+    pub id: String,
+    pub tenant_id: String,
+    /// Label to tell keys apart, e.g. the system using it
+    pub name: String,
+    pub scopes: Vec<ApiKeyScope>,
+    /// Hex SHA-256 of the full key
+    pub key_hash: String,
+    pub created_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl TenantApiKey {
+    /// Why the key cannot be used at `now`, if it cannot
+    pub fn check_usable(&self, now: DateTime<Utc>) -> Result<()> {
+        if self.revoked_at.is_some() {
+            return Err(DomainError::Unauthorized(
+                "API key has been revoked".to_string(),
+            ));
+        }
+        if self.expires_at.is_some_and(|expires_at| expires_at <= now) {
+            return Err(DomainError::Unauthorized("API key has expired".to_string()));
+        }
+        Ok(())
+    }
+}
+
 /// Manufacturer entity
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Manufacturer {
@@ -99,6 +399,109 @@ pub enum RfqStatus {
     Open,
     Archived,
     Closed,
+    Awarded,
+}
+
+impl RfqStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RfqStatus::Open => "open",
+            RfqStatus::Archived => "archived",
+            RfqStatus::Closed => "closed",
+            RfqStatus::Awarded => "awarded",
+        }
+    }
+
+    /// Only open RFQs take new messages
+    pub fn accepts_messages(&self) -> bool {
+        *self == RfqStatus::Open
+    }
+
+    /// Check that `author` may move an RFQ from this status to `target`,
+    /// returning the status event that records the change.
+    ///
+    /// | from             | to       | allowed authors                |
+    /// |------------------|----------|--------------------------------|
+    /// | open, awarded    | closed   | buyer, manufacturer            |
+    /// | open, closed     | archived | buyer, manufacturer, system    |
+    /// | closed, archived | open     | buyer                          |
+    /// | open             | awarded  | buyer                          |
+    ///
+    /// An awarded RFQ can still be closed and archived but never reopened;
+    /// [`RfqMeta::transition`] enforces that, as it needs the outcome.
+    pub fn transition(&self, target: &RfqStatus, author: &EventAuthor) -> Result<StatusType> {
+        use EventAuthor::*;
+        use RfqStatus::*;
+
+        let (status_type, allowed): (StatusType, &[EventAuthor]) = match (self, target) {
+            (from, to) if from == to => {
+                return Err(DomainError::Conflict(format!(
+                    "RFQ is already {}",
+                    to.as_str()
+                )))
+            }
+            (Open | Awarded, Closed) => (StatusType::Closed, &[Buyer, Manufacturer]),
+            (Open | Closed, Archived) => (StatusType::Archived, &[Buyer, Manufacturer, System]),
+            (Closed | Archived, Open) => (StatusType::Reopened, &[Buyer]),
+            (Open, Awarded) => (StatusType::Awarded, &[Buyer]),
+            (from, to) => {
+                return Err(DomainError::Conflict(format!(
+                    "Cannot move an RFQ from {} to {}",
+                    from.as_str(),
+                    to.as_str()
+                )))
+            }
+        };
+
+        if !allowed.contains(author) {
+            return Err(DomainError::Forbidden(format!(
+                "{} cannot move an RFQ from {} to {}",
+                author.as_str(),
+                self.as_str(),
+                target.as_str()
+            )));
+        }
+
+        Ok(status_type)
+    }
+
+    /// Check that `author` may decline an RFQ in this status. Declining
+    /// closes an open RFQ without a winner.
+    pub fn decline(&self, author: &EventAuthor) -> Result<StatusType> {
+        if *self != RfqStatus::Open {
+            return Err(DomainError::Conflict(format!(
+                "Cannot decline an RFQ that is {}",
+                self.as_str()
+            )));
+        }
+        if *author != EventAuthor::Buyer {
+            return Err(DomainError::Forbidden(format!(
+                "{} cannot decline an RFQ",
+                author.as_str()
+            )));
+        }
+
+        Ok(StatusType::Declined)
+    }
+
+    /// Status a recorded change moves an RFQ in this status to, or `None`
+    /// when the change does not apply to it
+    fn after(&self, change: &StatusType) -> Option<RfqStatus> {
+        use RfqStatus::*;
+
+        match (change, self) {
+            (StatusType::RfqCreated, Open) => Some(Open),
+            (StatusType::Closed, Open | Awarded) => Some(Closed),
+            (StatusType::Archived, Open | Closed) => Some(Archived),
+            (StatusType::Reopened, Closed | Archived) => Some(Open),
+            (StatusType::Awarded, Open) => Some(Awarded),
+            (StatusType::Declined, Open) => Some(Closed),
+            (StatusType::VendorViewed | StatusType::VendorReplied | StatusType::BuyerViewed, _) => {
+                Some(self.clone())
+            }
+            _ => None,
+        }
+    }
 }
 
 /// RFQ participant role
@@ -109,6 +512,23 @@ pub enum ParticipantRole {
     Manufacturer,
 }
 
+impl ParticipantRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ParticipantRole::Buyer => "buyer",
+            ParticipantRole::Manufacturer => "manufacturer",
+        }
+    }
+
+    /// How events written by this participant are attributed
+    pub fn author(&self) -> EventAuthor {
+        match self {
+            ParticipantRole::Buyer => EventAuthor::Buyer,
+            ParticipantRole::Manufacturer => EventAuthor::Manufacturer,
+        }
+    }
+}
+
 /// RFQ participant
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Participant {
@@ -130,6 +550,174 @@ pub struct RfqMeta {
     pub last_event_ts: DateTime<Utc>,
     pub participants: Vec<Participant>,
     pub attachments: Option<Vec<AttachmentRef>>,
+    /// Quote in force; each revision supersedes the previous one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latest_quote: Option<QuoteSummary>,
+    /// Buyer's award or decline. An award is final; a declined RFQ can be
+    /// reopened and keeps its decline until the buyer decides again.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outcome: Option<RfqOutcome>,
+    /// Campaign this RFQ was sent out in, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub campaign_id: Option<String>,
+    /// Sort key of the manufacturer's latest message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_reply_position: Option<String>,
+}
+
+impl RfqMeta {
+    /// The participant with this email, compared case-insensitively
+    pub fn participant(&self, email: &str) -> Option<&Participant> {
+        self.participants
+            .iter()
+            .find(|p| p.email.eq_ignore_ascii_case(email))
+    }
+
+    pub fn attachment(&self, id: &str) -> Option<&AttachmentRef> {
+        self.attachments.as_ref()?.iter().find(|a| a.id == id)
+    }
+
+    pub fn is_awarded(&self) -> bool {
+        self.outcome
+            .as_ref()
+            .is_some_and(|outcome| outcome.decision == Decision::Awarded)
+    }
+
+    /// Check that `author` may move this RFQ to `target`, as
+    /// [`RfqStatus::transition`] does, keeping an awarded RFQ from reopening
+    pub fn transition(&self, target: &RfqStatus, author: &EventAuthor) -> Result<StatusType> {
+        if *target == RfqStatus::Open && self.is_awarded() {
+            return Err(DomainError::Conflict(
+                "An awarded RFQ cannot be reopened".to_string(),
+            ));
+        }
+        self.status.transition(target, author)
+    }
+
+    /// Whether folding `event` would take effect. A status change that does
+    /// not apply to the current status, such as the loser of two concurrent
+    /// changes, stays in the stream but is skipped.
+    pub fn accepts(&self, event: &RfqEvent) -> bool {
+        match event {
+            RfqEvent::Status(e) => self.status_after(&e.status).is_some(),
+            _ => true,
+        }
+    }
+
+    fn status_after(&self, change: &StatusType) -> Option<RfqStatus> {
+        if *change == StatusType::Reopened && self.is_awarded() {
+            return None;
+        }
+        self.status.after(change)
+    }
+
+    /// Fold one event into the fields the event stream owns: status, last
+    /// activity, attachments, latest quote, latest reply and outcome.
+    /// Applying an event twice changes nothing.
+    pub fn apply(&mut self, event: &RfqEvent) {
+        self.last_event_ts = self.last_event_ts.max(event.timestamp());
+
+        match event {
+            RfqEvent::Status(e) => {
+                let Some(status) = self.status_after(&e.status) else {
+                    return;
+                };
+                self.status = status;
+                match e.status {
+                    StatusType::Awarded => {
+                        self.outcome = Some(RfqOutcome::from_event(Decision::Awarded, e));
+                    }
+                    StatusType::Declined => {
+                        self.outcome = Some(RfqOutcome::from_event(Decision::Declined, e));
+                    }
+                    _ => {}
+                }
+            }
+            RfqEvent::Attachment(e) => {
+                let attachments = self.attachments.get_or_insert_with(Vec::new);
+                for attachment in &e.attachments {
+                    if !attachments.iter().any(|a| a.id == attachment.id) {
+                        attachments.push(attachment.clone());
+                    }
+                }
+            }
+            RfqEvent::Quote(e) => {
+                if self
+                    .latest_quote
+                    .as_ref()
+                    .is_none_or(|latest| e.revision > latest.revision)
+                {
+                    self.latest_quote = Some(QuoteSummary {
+                        event_id: e.base.id.clone(),
+                        position: Some(event.sort_key()),
+                        revision: e.revision,
+                        valid_until: e.quote.valid_until,
+                    });
+                }
+            }
+            RfqEvent::Message(e) => {
+                if e.base.by == EventAuthor::Manufacturer {
+                    let position = event.sort_key();
+                    if self
+                        .last_reply_position
+                        .as_ref()
+                        .is_none_or(|latest| position > *latest)
+                    {
+                        self.last_reply_position = Some(position);
+                    }
+                }
+            }
+        }
+    }
+
+    /// The same RFQ as it was before any event: identity fields kept, event
+    /// fields reset so the stream can be replayed onto it
+    pub fn without_events(&self) -> Self {
+        Self {
+            status: RfqStatus::Open,
+            last_event_ts: self.created_at,
+            attachments: None,
+            latest_quote: None,
+            outcome: None,
+            last_reply_position: None,
+            ..self.clone()
+        }
+    }
+}
+
+/// One buyer request sent to several manufacturers at once. Each gets its
+/// own child RFQ with a private thread; only the buyer sees the campaign.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RfqCampaign {
+    pub id: String,
+    pub tenant_id: String,
+    pub buyer: Contact,
+    pub subject: String,
+    pub created_at: DateTime<Utc>,
+    pub rfqs: Vec<CampaignRfq>,
+}
+
+/// Child RFQ of a campaign
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CampaignRfq {
+    pub rfq_id: String,
+    pub manufacturer_id: String,
+}
+
+/// Audit record of a download URL issued for an RFQ attachment
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AttachmentAccess {
+    pub id: String,
+    pub rfq_id: String,
+    pub attachment_id: String,
+    /// Participant the URL was issued to
+    pub email: String,
+    pub role: ParticipantRole,
+    /// Tenant API key the manufacturer used, if not its access token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key_id: Option<String>,
+    pub issued_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
 }
 
 /// RFQ index for quick lookups
@@ -137,6 +725,102 @@ pub struct RfqMeta {
 pub struct RfqIndex {
     pub last_event_ts: DateTime<Utc>,
     pub count: u32,
+    /// Compacted event segments, oldest first. Events after the last segment
+    /// are still stored one object each.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub segments: Vec<EventSegment>,
+}
+
+impl RfqIndex {
+    /// Index of an RFQ created at `created_at` with no events yet
+    pub fn new(created_at: DateTime<Utc>) -> Self {
+        Self {
+            last_event_ts: created_at,
+            count: 0,
+            segments: Vec::new(),
+        }
+    }
+
+    /// Count one more event
+    pub fn apply(&mut self, event: &RfqEvent) {
+        self.last_event_ts = self.last_event_ts.max(event.timestamp());
+        self.count += 1;
+    }
+}
+
+/// One RFQ in a manufacturer's inbox, kept at `mfg/{manufacturer_id}/rfqs/{rfq_id}.json`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RfqInboxEntry {
+    pub rfq_id: String,
+    pub subject: String,
+    pub buyer: Contact,
+    pub status: RfqStatus,
+    pub created_at: DateTime<Utc>,
+    pub last_event_ts: DateTime<Utc>,
+    /// Latest event the manufacturer did not write itself
+    pub last_inbound_ts: DateTime<Utc>,
+    /// When the manufacturer last marked the RFQ as read
+    pub last_read_ts: Option<DateTime<Utc>>,
+}
+
+impl RfqInboxEntry {
+    /// Unread entry for an RFQ with no events yet
+    pub fn new(rfq: &RfqMeta) -> Self {
+        Self {
+            rfq_id: rfq.id.clone(),
+            subject: rfq.subject.clone(),
+            buyer: rfq.buyer.clone(),
+            status: rfq.status.clone(),
+            created_at: rfq.created_at,
+            last_event_ts: rfq.last_event_ts,
+            last_inbound_ts: rfq.created_at,
+            last_read_ts: None,
+        }
+    }
+
+    /// Note an event; anything the manufacturer did not write makes the RFQ unread
+    pub fn apply(&mut self, event: &RfqEvent) {
+        self.last_event_ts = self.last_event_ts.max(event.timestamp());
+        if *event.author() != EventAuthor::Manufacturer {
+            self.last_inbound_ts = self.last_inbound_ts.max(event.timestamp());
+        }
+    }
+
+    /// Copy the fields the RFQ meta owns
+    pub fn refresh(&mut self, rfq: &RfqMeta) {
+        self.subject = rfq.subject.clone();
+        self.buyer = rfq.buyer.clone();
+        self.status = rfq.status.clone();
+        self.last_event_ts = rfq.last_event_ts;
+    }
+
+    pub fn is_unread(&self) -> bool {
+        self.last_read_ts
+            .is_none_or(|read| self.last_inbound_ts > read)
+    }
+
+    /// Place in the manufacturer's inbox: the nanoseconds left until the end of
+    /// time at the last activity, then the RFQ id, so lexical order is newest
+    /// activity first
+    pub fn position(&self) -> String {
+        let nanos = self
+            .last_event_ts
+            .timestamp_nanos_opt()
+            .unwrap_or_default()
+            .max(0);
+        format!("{:019}-{}", i64::MAX - nanos, self.rfq_id)
+    }
+}
+
+/// Immutable JSONL object holding a contiguous run of an RFQ's events
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EventSegment {
+    /// Object name under `rfq/{rfq_id}/`, e.g. `events-00001-3f9a2c1b.jsonl`
+    pub key: String,
+    /// Sort keys of the first and last event in the segment
+    pub first_position: String,
+    pub last_position: String,
+    pub count: u32,
 }
 
 /// Catalog manufacturer summary
@@ -179,3 +863,469 @@ pub struct ImageManifest {
     pub lqip: Option<String>, // data URI for low quality placeholder
     pub created_at: DateTime<Utc>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value_objects::Unit;
+    use EventAuthor::{Buyer, Manufacturer, System};
+    use RfqStatus::{Archived, Awarded, Closed, Open};
+
+    /// What a status change is expected to do
+    #[derive(Debug, PartialEq)]
+    enum Expect {
+        Records(StatusType),
+        Forbidden,
+        Conflict,
+    }
+
+    fn expect(result: Result<StatusType>) -> Expect {
+        match result {
+            Ok(status_type) => Expect::Records(status_type),
+            Err(DomainError::Forbidden(_)) => Expect::Forbidden,
+            Err(DomainError::Conflict(_)) => Expect::Conflict,
+            Err(e) => panic!("unexpected error {:?}", e),
+        }
+    }
+
+    fn rfq(status: RfqStatus) -> RfqMeta {
+        let created_at = "2025-01-01T00:00:00Z".parse().unwrap();
+        RfqMeta {
+            id: "r_1".to_string(),
+            tenant_id: "t1".to_string(),
+            manufacturer_id: "mfg_acme".to_string(),
+            buyer: Contact {
+                email: "buyer@example.com".to_string(),
+                name: None,
+            },
+            subject: "Brackets".to_string(),
+            status,
+            created_at,
+            last_event_ts: created_at,
+            participants: Vec::new(),
+            attachments: None,
+            latest_quote: None,
+            outcome: None,
+            campaign_id: None,
+            last_reply_position: None,
+        }
+    }
+
+    fn status_event(author: EventAuthor, status: StatusType) -> RfqEvent {
+        RfqEvent::new_status("r_1".to_string(), author, status, None, None)
+    }
+
+    /// `from`, `to`, then the expectation for the buyer, manufacturer and system
+    fn transition_table() -> Vec<(RfqStatus, RfqStatus, [Expect; 3])> {
+        use Expect::{Conflict as C, Forbidden as F, Records as R};
+        use StatusType as T;
+
+        vec![
+            (Open, Open, [C, C, C]),
+            (Open, Closed, [R(T::Closed), R(T::Closed), F]),
+            (
+                Open,
+                Archived,
+                [R(T::Archived), R(T::Archived), R(T::Archived)],
+            ),
+            (Open, Awarded, [R(T::Awarded), F, F]),
+            (Closed, Open, [R(T::Reopened), F, F]),
+            (Closed, Closed, [C, C, C]),
+            (
+                Closed,
+                Archived,
+                [R(T::Archived), R(T::Archived), R(T::Archived)],
+            ),
+            (Closed, Awarded, [C, C, C]),
+            (Archived, Open, [R(T::Reopened), F, F]),
+            (Archived, Closed, [C, C, C]),
+            (Archived, Archived, [C, C, C]),
+            (Archived, Awarded, [C, C, C]),
+            (Awarded, Open, [C, C, C]),
+            (Awarded, Closed, [R(T::Closed), R(T::Closed), F]),
+            (Awarded, Archived, [C, C, C]),
+            (Awarded, Awarded, [C, C, C]),
+        ]
+    }
+
+    #[test]
+    fn transition_table_covers_every_status_and_author() {
+        for (from, to, expected) in transition_table() {
+            for (author, expected) in [Buyer, Manufacturer, System].into_iter().zip(expected) {
+                assert_eq!(
+                    expect(from.transition(&to, &author)),
+                    expected,
+                    "{:?} -> {:?} by {:?}",
+                    from,
+                    to,
+                    author
+                );
+                // Without an award the RFQ's meta agrees with its status
+                assert_eq!(
+                    expect(rfq(from.clone()).transition(&to, &author)),
+                    expected,
+                    "meta {:?} -> {:?} by {:?}",
+                    from,
+                    to,
+                    author
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn decline_table_covers_every_status_and_author() {
+        for from in [Open, Closed, Archived, Awarded] {
+            for author in [Buyer, Manufacturer, System] {
+                let expected = match (&from, &author) {
+                    (Open, Buyer) => Expect::Records(StatusType::Declined),
+                    (Open, _) => Expect::Forbidden,
+                    _ => Expect::Conflict,
+                };
+                assert_eq!(
+                    expect(from.decline(&author)),
+                    expected,
+                    "decline {:?} by {:?}",
+                    from,
+                    author
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn an_award_is_never_reopened() {
+        let mut meta = rfq(Open);
+        meta.apply(&status_event(Buyer, StatusType::Awarded));
+        let award = meta.outcome.clone().unwrap();
+        assert_eq!(meta.status, Awarded);
+
+        meta.apply(&status_event(Manufacturer, StatusType::Closed));
+        assert_eq!(meta.status, Closed);
+        assert_eq!(meta.outcome.as_ref(), Some(&award));
+
+        for author in [Buyer, Manufacturer, System] {
+            assert_eq!(
+                expect(meta.transition(&Open, &author)),
+                Expect::Conflict,
+                "{:?}",
+                author
+            );
+        }
+        assert_eq!(
+            expect(meta.transition(&Archived, &Buyer)),
+            Expect::Records(StatusType::Archived)
+        );
+
+        // A reopen that reached the stream anyway is skipped by the fold
+        let reopen = status_event(Buyer, StatusType::Reopened);
+        assert!(!meta.accepts(&reopen));
+        meta.apply(&reopen);
+        assert_eq!(meta.status, Closed);
+        meta.apply(&status_event(Buyer, StatusType::Archived));
+        assert_eq!(meta.status, Archived);
+        assert_eq!(expect(meta.transition(&Open, &Buyer)), Expect::Conflict);
+        assert_eq!(meta.outcome, Some(award));
+    }
+
+    #[test]
+    fn a_decline_can_be_reopened_and_then_awarded() {
+        let mut meta = rfq(Open);
+        meta.apply(&status_event(Buyer, StatusType::Declined));
+        assert_eq!(meta.status, Closed);
+        assert_eq!(meta.outcome.as_ref().unwrap().decision, Decision::Declined);
+
+        assert_eq!(
+            expect(meta.transition(&Open, &Buyer)),
+            Expect::Records(StatusType::Reopened)
+        );
+        meta.apply(&status_event(Buyer, StatusType::Reopened));
+        assert_eq!(meta.status, Open);
+        assert_eq!(meta.outcome.as_ref().unwrap().decision, Decision::Declined);
+
+        meta.apply(&status_event(Buyer, StatusType::Awarded));
+        assert_eq!(meta.status, Awarded);
+        assert!(meta.is_awarded());
+    }
+
+    #[test]
+    fn applying_a_status_event_twice_changes_nothing() {
+        for change in [
+            StatusType::Closed,
+            StatusType::Archived,
+            StatusType::Awarded,
+            StatusType::Declined,
+        ] {
+            let event = status_event(Buyer, change.clone());
+            let mut meta = rfq(Open);
+            meta.apply(&event);
+            let once = meta.clone();
+            meta.apply(&event);
+            assert_eq!(meta, once, "{:?}", change);
+        }
+    }
+
+    fn money(amount: &str, currency: &str) -> Money {
+        Money::from_decimal(amount, currency.parse().unwrap()).unwrap()
+    }
+
+    fn quote(line_items: Vec<QuoteLineItem>, charges: Vec<QuoteCharge>) -> Quote {
+        Quote {
+            line_items,
+            charges,
+            lead_time_days: None,
+            incoterms: None,
+            valid_until: NaiveDate::from_ymd_opt(2030, 1, 1).unwrap(),
+            note: None,
+        }
+    }
+
+    fn item(quantity: Quantity, unit_price: Money) -> QuoteLineItem {
+        QuoteLineItem {
+            part: "BRKT-104".to_string(),
+            quantity,
+            unit_price,
+        }
+    }
+
+    fn tooling(amount: Money) -> QuoteCharge {
+        QuoteCharge {
+            kind: ChargeKind::Tooling,
+            description: None,
+            amount,
+        }
+    }
+
+    #[test]
+    fn quote_totals_and_validation() {
+        let today = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let valid = quote(
+            vec![
+                item(
+                    Quantity::new(500, Unit::Pcs).unwrap(),
+                    money("12.50", "USD"),
+                ),
+                item(
+                    Quantity::from_decimal("2.345", Unit::Kg).unwrap(),
+                    money("1.50", "USD"),
+                ),
+            ],
+            vec![tooling(money("800.00", "USD"))],
+        );
+        assert!(valid.validate(today).is_ok());
+        assert_eq!(valid.total().unwrap(), money("7053.52", "USD"));
+
+        let mixed = quote(
+            vec![item(
+                Quantity::new(1, Unit::Pcs).unwrap(),
+                money("1.00", "USD"),
+            )],
+            vec![tooling(money("1.00", "EUR"))],
+        );
+        assert!(matches!(
+            mixed.validate(today),
+            Err(DomainError::ValidationFailed(_))
+        ));
+
+        let zero = quote(
+            vec![item(
+                Quantity::new(0, Unit::Pcs).unwrap(),
+                money("1.00", "USD"),
+            )],
+            Vec::new(),
+        );
+        assert!(zero.validate(today).is_err());
+
+        let negative = quote(
+            vec![item(
+                Quantity::new(1, Unit::Pcs).unwrap(),
+                money("-1.00", "USD"),
+            )],
+            Vec::new(),
+        );
+        assert!(negative.validate(today).is_err());
+
+        let overflowing = quote(
+            vec![item(
+                Quantity::new(1_000_000, Unit::Pcs).unwrap(),
+                Money::new(i64::MAX / 2, "USD".parse().unwrap()),
+            )],
+            Vec::new(),
+        );
+        assert!(overflowing.validate(today).is_err());
+    }
+
+    /// `event` as if written `minutes` after the RFQ was created
+    fn at(minutes: i64, mut event: RfqEvent) -> RfqEvent {
+        let ts = rfq(Open).created_at + chrono::Duration::minutes(minutes);
+        match &mut event {
+            RfqEvent::Message(e) => e.base.ts = ts,
+            RfqEvent::Status(e) => e.base.ts = ts,
+            RfqEvent::Attachment(e) => e.base.ts = ts,
+            RfqEvent::Quote(e) => e.base.ts = ts,
+        }
+        event
+    }
+
+    fn attachment(id: &str) -> AttachmentRef {
+        AttachmentRef {
+            id: id.to_string(),
+            file_name: format!("{}.pdf", id),
+            content_type: "application/pdf".to_string(),
+            size_bytes: 1024,
+            key: format!("rfq/r_1/attachments/{}.pdf", id),
+        }
+    }
+
+    /// A thread that is quoted twice, declined, reopened, awarded on the
+    /// second quote and then asked to reopen again
+    fn thread() -> Vec<RfqEvent> {
+        let id = || "r_1".to_string();
+        let offer = quote(
+            vec![item(
+                Quantity::new(500, Unit::Pcs).unwrap(),
+                money("12.50", "USD"),
+            )],
+            Vec::new(),
+        );
+        let first = at(
+            5,
+            RfqEvent::new_quote(id(), Manufacturer, offer.clone(), 1, None),
+        );
+        let second = at(
+            8,
+            RfqEvent::new_quote(id(), Manufacturer, offer, 2, Some(first.id().to_string())),
+        );
+        let award = OfferReference {
+            kind: OfferKind::Quote,
+            id: second.id().to_string(),
+        };
+
+        vec![
+            at(0, status_event(System, StatusType::RfqCreated)),
+            at(
+                1,
+                RfqEvent::new_message(id(), Buyer, "500 brackets?".to_string()),
+            ),
+            at(
+                1,
+                RfqEvent::new_attachment(id(), Buyer, vec![attachment("att_1")], None),
+            ),
+            first,
+            at(
+                6,
+                RfqEvent::new_message(id(), Manufacturer, "Revised".to_string()),
+            ),
+            second,
+            at(
+                9,
+                RfqEvent::new_attachment(
+                    id(),
+                    Manufacturer,
+                    vec![attachment("att_1"), attachment("att_2")],
+                    None,
+                ),
+            ),
+            at(10, status_event(Buyer, StatusType::Declined)),
+            at(12, status_event(Buyer, StatusType::Reopened)),
+            at(
+                15,
+                RfqEvent::new_status(id(), Buyer, StatusType::Awarded, None, Some(award)),
+            ),
+            at(16, status_event(Buyer, StatusType::Reopened)),
+            at(
+                20,
+                RfqEvent::new_message(id(), Manufacturer, "Shipping".to_string()),
+            ),
+        ]
+    }
+
+    #[test]
+    fn folding_a_thread_rebuilds_meta_index_and_inbox() {
+        let events = thread();
+        let created = rfq(Open);
+        let mut meta = created.without_events();
+        let mut index = RfqIndex::new(created.created_at);
+        let mut inbox = RfqInboxEntry::new(&meta);
+        for event in &events {
+            meta.apply(event);
+            index.apply(event);
+            inbox.apply(event);
+        }
+        inbox.refresh(&meta);
+
+        let minutes = |m| created.created_at + chrono::Duration::minutes(m);
+        assert_eq!(meta.status, Awarded);
+        assert_eq!(meta.last_event_ts, minutes(20));
+        let attachment_ids: Vec<&str> = meta
+            .attachments
+            .iter()
+            .flatten()
+            .map(|a| a.id.as_str())
+            .collect();
+        assert_eq!(attachment_ids, ["att_1", "att_2"]);
+        let latest = meta.latest_quote.as_ref().unwrap();
+        assert_eq!(
+            (latest.event_id.as_str(), latest.revision),
+            (events[5].id(), 2)
+        );
+        assert_eq!(latest.position, Some(events[5].sort_key()));
+        assert_eq!(
+            meta.last_reply_position,
+            Some(events.last().unwrap().sort_key())
+        );
+        let outcome = meta.outcome.as_ref().unwrap();
+        assert_eq!(outcome.decision, Decision::Awarded);
+        assert_eq!(outcome.decided_at, minutes(15));
+        assert_eq!(outcome.reference.as_ref().unwrap().id, events[5].id());
+
+        assert_eq!(index.count, events.len() as u32);
+        assert_eq!(index.last_event_ts, minutes(20));
+
+        assert_eq!(inbox.status, Awarded);
+        assert_eq!(inbox.last_event_ts, minutes(20));
+        // The ignored reopen still came from the buyer
+        assert_eq!(inbox.last_inbound_ts, minutes(16));
+        assert!(inbox.is_unread());
+
+        // Meta is a pure fold, so replaying it changes nothing
+        let once = meta.clone();
+        for event in &events {
+            meta.apply(event);
+        }
+        assert_eq!(meta, once);
+    }
+
+    #[test]
+    fn the_fold_follows_every_step_of_the_thread() {
+        let events = thread();
+        let mut meta = rfq(Open).without_events();
+        let mut seen = Vec::new();
+        for event in &events {
+            meta.apply(event);
+            seen.push((
+                meta.status.clone(),
+                meta.outcome.as_ref().map(|outcome| outcome.decision),
+            ));
+        }
+
+        use Decision::{Awarded as Won, Declined as Lost};
+        assert_eq!(
+            seen,
+            [
+                (Open, None),
+                (Open, None),
+                (Open, None),
+                (Open, None),
+                (Open, None),
+                (Open, None),
+                (Open, None),
+                (Closed, Some(Lost)),
+                (Open, Some(Lost)),
+                (Awarded, Some(Won)),
+                (Awarded, Some(Won)),
+                (Awarded, Some(Won)),
+            ]
+        );
+    }
+}
@@ -2,7 +2,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::entities::AttachmentRef;
+use crate::entities::{AttachmentRef, OfferReference, Quote};
 
 /// Who created the event
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -13,6 +13,16 @@ pub enum EventAuthor {
     System,
 }
 
+impl EventAuthor {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventAuthor::Buyer => "buyer",
+            EventAuthor::Manufacturer => "manufacturer",
+            EventAuthor::System => "system",
+        }
+    }
+}
+
 /// Base properties for all RFQ events
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct RfqEventBase {
@@ -20,7 +30,8 @@ pub struct RfqEventBase {
     pub rfq_id: String,
     pub ts: DateTime<Utc>,
     pub by: EventAuthor,
-    #[serde(rename = "type")]
+    /// Written by the `RfqEvent` tag, so it is skipped here to avoid a duplicate `type` key
+    #[serde(rename = "type", skip_serializing, default)]
     pub event_type: String,
 }
 
@@ -42,6 +53,9 @@ pub enum StatusType {
     BuyerViewed,
     Closed,
     Archived,
+    Reopened,
+    Awarded,
+    Declined,
 }
 
 /// Status event - system or participant changes RFQ status
@@ -51,6 +65,9 @@ pub struct StatusEvent {
     pub base: RfqEventBase,
     pub status: StatusType,
     pub note: Option<String>,
+    /// Winning (or rejected) offer an award or decline refers to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reference: Option<OfferReference>,
 }
 
 /// Attachment event - files are uploaded
@@ -59,11 +76,28 @@ pub struct AttachmentEvent {
     #[serde(flatten)]
     pub base: RfqEventBase,
     pub attachments: Vec<AttachmentRef>,
+    /// Message the files were sent with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_id: Option<String>,
+}
+
+/// Quote event - manufacturer prices the RFQ. A later revision supersedes
+/// all earlier ones.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QuoteEvent {
+    #[serde(flatten)]
+    pub base: RfqEventBase,
+    /// 1 for the first quote on the RFQ, incremented by each revision
+    pub revision: u32,
+    /// Event id of the quote this one replaces
+    pub supersedes: Option<String>,
+    #[serde(flatten)]
+    pub quote: Quote,
 }
 
 /// Union type for all RFQ events
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-#[serde(tag = "type")]
+#[serde(tag = "type", from = "TaggedRfqEvent")]
 pub enum RfqEvent {
     #[serde(rename = "message")]
     Message(MessageEvent),
@@ -71,6 +105,46 @@ pub enum RfqEvent {
     Status(StatusEvent),
     #[serde(rename = "attachment")]
     Attachment(AttachmentEvent),
+    #[serde(rename = "quote")]
+    Quote(QuoteEvent),
+}
+
+/// Wire shape of [`RfqEvent`]. The enum consumes the `type` tag, so the base
+/// `event_type` is restored from the variant after deserializing.
+#[derive(Deserialize)]
+#[serde(tag = "type")]
+enum TaggedRfqEvent {
+    #[serde(rename = "message")]
+    Message(MessageEvent),
+    #[serde(rename = "status")]
+    Status(StatusEvent),
+    #[serde(rename = "attachment")]
+    Attachment(AttachmentEvent),
+    #[serde(rename = "quote")]
+    Quote(QuoteEvent),
+}
+
+impl From<TaggedRfqEvent> for RfqEvent {
+    fn from(event: TaggedRfqEvent) -> Self {
+        match event {
+            TaggedRfqEvent::Message(mut e) => {
+                e.base.event_type = "message".to_string();
+                RfqEvent::Message(e)
+            }
+            TaggedRfqEvent::Status(mut e) => {
+                e.base.event_type = "status".to_string();
+                RfqEvent::Status(e)
+            }
+            TaggedRfqEvent::Attachment(mut e) => {
+                e.base.event_type = "attachment".to_string();
+                RfqEvent::Attachment(e)
+            }
+            TaggedRfqEvent::Quote(mut e) => {
+                e.base.event_type = "quote".to_string();
+                RfqEvent::Quote(e)
+            }
+        }
+    }
 }
 
 impl RfqEvent {
@@ -79,6 +153,7 @@ impl RfqEvent {
             RfqEvent::Message(e) => &e.base.id,
             RfqEvent::Status(e) => &e.base.id,
             RfqEvent::Attachment(e) => &e.base.id,
+            RfqEvent::Quote(e) => &e.base.id,
         }
     }
 
@@ -87,6 +162,7 @@ impl RfqEvent {
             RfqEvent::Message(e) => &e.base.rfq_id,
             RfqEvent::Status(e) => &e.base.rfq_id,
             RfqEvent::Attachment(e) => &e.base.rfq_id,
+            RfqEvent::Quote(e) => &e.base.rfq_id,
         }
     }
 
@@ -95,14 +171,28 @@ impl RfqEvent {
             RfqEvent::Message(e) => e.base.ts,
             RfqEvent::Status(e) => e.base.ts,
             RfqEvent::Attachment(e) => e.base.ts,
+            RfqEvent::Quote(e) => e.base.ts,
         }
     }
 
+    /// Position of the event in its RFQ's log: the timestamp with fixed nanosecond
+    /// precision (`:` replaced by `-`) followed by the id, so lexical order is
+    /// (ts, id) order. Stored events are named after it.
+    pub fn sort_key(&self) -> String {
+        format!("{}-{}", Self::sort_key_prefix(self.timestamp()), self.id())
+    }
+
+    /// Sort key prefix of events at `ts`; it sorts before all of them
+    pub fn sort_key_prefix(ts: DateTime<Utc>) -> String {
+        ts.format("%Y-%m-%dT%H-%M-%S%.9fZ").to_string()
+    }
+
     pub fn author(&self) -> &EventAuthor {
         match self {
             RfqEvent::Message(e) => &e.base.by,
             RfqEvent::Status(e) => &e.base.by,
             RfqEvent::Attachment(e) => &e.base.by,
+            RfqEvent::Quote(e) => &e.base.by,
         }
     }
 
@@ -129,6 +219,7 @@ impl RfqEvent {
         author: EventAuthor,
         status: StatusType,
         note: Option<String>,
+        reference: Option<OfferReference>,
     ) -> Self {
         let id = Uuid::new_v4().to_string();
         let ts = Utc::now();
@@ -143,14 +234,16 @@ impl RfqEvent {
             },
             status,
             note,
+            reference,
         })
     }
 
-    /// Create a new attachment event
+    /// Create a new attachment event, linked to the message it came with
     pub fn new_attachment(
         rfq_id: String,
         author: EventAuthor,
         attachments: Vec<AttachmentRef>,
+        message_id: Option<String>,
     ) -> Self {
         let id = Uuid::new_v4().to_string();
         let ts = Utc::now();
@@ -164,6 +257,32 @@ impl RfqEvent {
                 event_type: "attachment".to_string(),
             },
             attachments,
+            message_id,
+        })
+    }
+
+    /// Create a new quote event, superseding `supersedes` if given
+    pub fn new_quote(
+        rfq_id: String,
+        author: EventAuthor,
+        quote: Quote,
+        revision: u32,
+        supersedes: Option<String>,
+    ) -> Self {
+        let id = Uuid::new_v4().to_string();
+        let ts = Utc::now();
+
+        RfqEvent::Quote(QuoteEvent {
+            base: RfqEventBase {
+                id,
+                rfq_id,
+                ts,
+                by: author,
+                event_type: "quote".to_string(),
+            },
+            revision,
+            supersedes,
+            quote,
         })
     }
 }
@@ -93,6 +93,65 @@ impl RfqId {
     }
 }
 
+/// RFQ campaign ID value object
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CampaignId(String);
+
+impl CampaignId {
+    pub fn new(id: String) -> Result<Self> {
+        if id.is_empty() || id.len() > 50 {
+            return Err(DomainError::ValidationFailed(
+                "Campaign ID must be 1-50 characters".to_string(),
+            ));
+        }
+
+        Ok(CampaignId(id))
+    }
+
+    pub fn generate() -> Self {
+        let id = format!(
+            "c_{}",
+            &uuid::Uuid::new_v4().simple().to_string()[0..8].to_uppercase()
+        );
+        CampaignId(id)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Tenant API key ID value object
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ApiKeyId(String);
+
+impl ApiKeyId {
+    pub fn new(id: String) -> Result<Self> {
+        let id_regex = Regex::new(r"^key_[A-Z0-9]{8}$")
+            .map_err(|_| DomainError::Internal("Failed to compile API key ID regex".to_string()))?;
+
+        if !id_regex.is_match(&id) {
+            return Err(DomainError::ValidationFailed(
+                "API key ID must be 'key_' followed by 8 characters".to_string(),
+            ));
+        }
+
+        Ok(ApiKeyId(id))
+    }
+
+    pub fn generate() -> Self {
+        let id = format!(
+            "key_{}",
+            &uuid::Uuid::new_v4().simple().to_string()[0..8].to_uppercase()
+        );
+        ApiKeyId(id)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
 /// Manufacturer ID value object
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ManufacturerId(String);
@@ -245,3 +304,738 @@ impl MessageBody {
         &self.0
     }
 }
+
+/// ISO-4217 currency code
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(try_from = "String", into = "String")]
+pub struct Currency(String);
+
+impl Currency {
+    pub fn new(code: String) -> Result<Self> {
+        if code.len() != 3 || !code.bytes().all(|b| b.is_ascii_uppercase()) {
+            return Err(DomainError::ValidationFailed(format!(
+                "'{}' is not an ISO-4217 currency code",
+                code
+            )));
+        }
+
+        Ok(Currency(code))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Digits after the decimal point in the currency's minor unit, e.g. 2
+    /// for USD (cents) and 0 for JPY
+    pub fn minor_units(&self) -> u32 {
+        match self.0.as_str() {
+            "BIF" | "CLP" | "DJF" | "GNF" | "ISK" | "JPY" | "KMF" | "KRW" | "PYG" | "RWF"
+            | "UGX" | "UYI" | "VND" | "VUV" | "XAF" | "XOF" | "XPF" => 0,
+            "BHD" | "IQD" | "JOD" | "KWD" | "LYD" | "OMR" | "TND" => 3,
+            "CLF" | "UYW" => 4,
+            _ => 2,
+        }
+    }
+}
+
+impl TryFrom<String> for Currency {
+    type Error = DomainError;
+
+    fn try_from(code: String) -> Result<Self> {
+        Currency::new(code)
+    }
+}
+
+impl From<Currency> for String {
+    fn from(currency: Currency) -> Self {
+        currency.0
+    }
+}
+
+impl FromStr for Currency {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Currency::new(s.to_string())
+    }
+}
+
+impl std::fmt::Display for Currency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// How to round a result that falls between two representable values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Rounding {
+    /// Ties go to the even neighbour (banker's rounding)
+    #[default]
+    HalfEven,
+    /// Ties go away from zero
+    HalfUp,
+}
+
+impl Rounding {
+    /// `numerator / denominator` rounded to an integer
+    fn divide(self, numerator: i128, denominator: i128) -> i128 {
+        let (numerator, denominator) = if denominator < 0 {
+            (-numerator, -denominator)
+        } else {
+            (numerator, denominator)
+        };
+        let quotient = numerator.div_euclid(denominator);
+        let twice_remainder = 2 * numerator.rem_euclid(denominator);
+
+        match twice_remainder.cmp(&denominator) {
+            std::cmp::Ordering::Less => quotient,
+            std::cmp::Ordering::Greater => quotient + 1,
+            std::cmp::Ordering::Equal => match self {
+                Rounding::HalfEven if quotient.rem_euclid(2) == 0 => quotient,
+                Rounding::HalfEven => quotient + 1,
+                // `quotient` is the floor, which is away from zero for negatives
+                Rounding::HalfUp if numerator < 0 => quotient,
+                Rounding::HalfUp => quotient + 1,
+            },
+        }
+    }
+}
+
+/// Parse a plain decimal string (`-12.345`) into an integer scaled by
+/// `10^scale`. Digits past `scale` are rounded, or rejected without `rounding`.
+fn parse_scaled(value: &str, scale: u32, rounding: Option<Rounding>) -> Result<i128> {
+    const MAX_DIGITS: usize = 30;
+    let invalid = || DomainError::ValidationFailed(format!("'{}' is not a decimal number", value));
+
+    let (negative, digits) = match value.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, value),
+    };
+    let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+    if whole.is_empty()
+        || (digits.contains('.') && fraction.is_empty())
+        || whole.len() + fraction.len() > MAX_DIGITS
+        || !whole
+            .bytes()
+            .chain(fraction.bytes())
+            .all(|b| b.is_ascii_digit())
+    {
+        return Err(invalid());
+    }
+
+    let unscaled: i128 = format!("{}{}", whole, fraction)
+        .parse()
+        .map_err(|_| invalid())?;
+    let unscaled = if negative { -unscaled } else { unscaled };
+
+    let fraction_digits = fraction.len() as u32;
+    if fraction_digits <= scale {
+        return Ok(unscaled * 10i128.pow(scale - fraction_digits));
+    }
+    match rounding {
+        Some(rounding) => Ok(rounding.divide(unscaled, 10i128.pow(fraction_digits - scale))),
+        None => Err(DomainError::ValidationFailed(format!(
+            "'{}' has more than {} decimal places",
+            value, scale
+        ))),
+    }
+}
+
+/// Format an integer scaled by `10^scale` as a plain decimal string, keeping
+/// all `scale` digits or trimming trailing zeros
+fn format_scaled(value: i128, scale: u32, trim: bool) -> String {
+    let sign = if value < 0 { "-" } else { "" };
+    let magnitude = value.unsigned_abs();
+    let divisor = 10u128.pow(scale);
+    let whole = magnitude / divisor;
+    if scale == 0 {
+        return format!("{}{}", sign, whole);
+    }
+
+    let fraction = format!("{:0width$}", magnitude % divisor, width = scale as usize);
+    let fraction = if trim {
+        fraction.trim_end_matches('0')
+    } else {
+        fraction.as_str()
+    };
+    if fraction.is_empty() {
+        format!("{}{}", sign, whole)
+    } else {
+        format!("{}{}.{}", sign, whole, fraction)
+    }
+}
+
+/// Amount of money in a single currency, held exactly as an integer count of
+/// the currency's minor unit. Serializes as `{"amount": "12.50", "currency": "USD"}`
+/// with the amount as a string so it never passes through a float.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(try_from = "MoneyRepr", into = "MoneyRepr")]
+pub struct Money {
+    amount_minor: i64,
+    currency: Currency,
+}
+
+#[derive(Serialize, Deserialize)]
+struct MoneyRepr {
+    amount: String,
+    currency: Currency,
+}
+
+impl Money {
+    pub fn new(amount_minor: i64, currency: Currency) -> Self {
+        Money {
+            amount_minor,
+            currency,
+        }
+    }
+
+    pub fn zero(currency: Currency) -> Self {
+        Money::new(0, currency)
+    }
+
+    /// Parse a decimal amount such as `"12.50"`, rejecting more decimal places
+    /// than the currency has
+    pub fn from_decimal(amount: &str, currency: Currency) -> Result<Self> {
+        Self::parse(amount, currency, None)
+    }
+
+    /// Parse a decimal amount, rounding it to the currency's minor unit
+    pub fn from_decimal_rounded(
+        amount: &str,
+        currency: Currency,
+        rounding: Rounding,
+    ) -> Result<Self> {
+        Self::parse(amount, currency, Some(rounding))
+    }
+
+    fn parse(amount: &str, currency: Currency, rounding: Option<Rounding>) -> Result<Self> {
+        let minor = parse_scaled(amount, currency.minor_units(), rounding)?;
+        Self::from_minor(minor, currency)
+    }
+
+    fn from_minor(amount_minor: i128, currency: Currency) -> Result<Self> {
+        let amount_minor = i64::try_from(amount_minor).map_err(|_| {
+            DomainError::InvalidInput(format!("Amount out of range for {}", currency))
+        })?;
+        Ok(Money::new(amount_minor, currency))
+    }
+
+    pub fn amount_minor(&self) -> i64 {
+        self.amount_minor
+    }
+
+    pub fn currency(&self) -> &Currency {
+        &self.currency
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.amount_minor < 0
+    }
+
+    /// The amount as a decimal string with all of the currency's minor digits
+    pub fn to_decimal_string(&self) -> String {
+        format_scaled(self.amount_minor.into(), self.currency.minor_units(), false)
+    }
+
+    pub fn checked_add(&self, other: &Money) -> Result<Money> {
+        self.same_currency(other, "add")?;
+        self.amount_minor
+            .checked_add(other.amount_minor)
+            .map(|amount| Money::new(amount, self.currency.clone()))
+            .ok_or_else(|| self.overflow())
+    }
+
+    pub fn checked_sub(&self, other: &Money) -> Result<Money> {
+        self.same_currency(other, "subtract")?;
+        self.amount_minor
+            .checked_sub(other.amount_minor)
+            .map(|amount| Money::new(amount, self.currency.clone()))
+            .ok_or_else(|| self.overflow())
+    }
+
+    /// Multiply by a whole number, e.g. a unit price by a piece count
+    pub fn checked_mul(&self, factor: i64) -> Result<Money> {
+        self.amount_minor
+            .checked_mul(factor)
+            .map(|amount| Money::new(amount, self.currency.clone()))
+            .ok_or_else(|| self.overflow())
+    }
+
+    /// Multiply by `numerator / denominator` (a discount, tax rate or share),
+    /// rounding to the minor unit
+    pub fn mul_ratio(&self, numerator: i64, denominator: i64, rounding: Rounding) -> Result<Money> {
+        if denominator == 0 {
+            return Err(DomainError::InvalidInput(
+                "Cannot divide money by zero".to_string(),
+            ));
+        }
+        let amount = rounding.divide(
+            i128::from(self.amount_minor) * i128::from(numerator),
+            denominator.into(),
+        );
+        Self::from_minor(amount, self.currency.clone())
+    }
+
+    /// Price of `quantity` at this unit price, rounding to the minor unit
+    pub fn mul_quantity(&self, quantity: &Quantity, rounding: Rounding) -> Result<Money> {
+        let amount = rounding.divide(
+            i128::from(self.amount_minor) * i128::from(quantity.value_scaled),
+            Quantity::SCALE_FACTOR,
+        );
+        Self::from_minor(amount, self.currency.clone())
+    }
+
+    /// Total of `amounts`, which must all be in `currency`
+    pub fn sum<'a>(
+        amounts: impl IntoIterator<Item = &'a Money>,
+        currency: Currency,
+    ) -> Result<Money> {
+        amounts
+            .into_iter()
+            .try_fold(Money::zero(currency), |total, amount| {
+                total.checked_add(amount)
+            })
+    }
+
+    fn same_currency(&self, other: &Money, operation: &str) -> Result<()> {
+        if self.currency != other.currency {
+            return Err(DomainError::InvalidInput(format!(
+                "Cannot {} {} and {} amounts",
+                operation, self.currency, other.currency
+            )));
+        }
+        Ok(())
+    }
+
+    fn overflow(&self) -> DomainError {
+        DomainError::InvalidInput(format!("Amount out of range for {}", self.currency))
+    }
+}
+
+impl TryFrom<MoneyRepr> for Money {
+    type Error = DomainError;
+
+    fn try_from(repr: MoneyRepr) -> Result<Self> {
+        Money::from_decimal(&repr.amount, repr.currency)
+    }
+}
+
+impl From<Money> for MoneyRepr {
+    fn from(money: Money) -> Self {
+        MoneyRepr {
+            amount: money.to_decimal_string(),
+            currency: money.currency,
+        }
+    }
+}
+
+impl std::fmt::Display for Money {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.to_decimal_string(), self.currency)
+    }
+}
+
+/// Unit a quantity is measured in
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Unit {
+    /// Pieces; always a whole number
+    Pcs,
+    Kg,
+    Lbs,
+    M,
+    Ft,
+}
+
+impl Unit {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Unit::Pcs => "pcs",
+            Unit::Kg => "kg",
+            Unit::Lbs => "lbs",
+            Unit::M => "m",
+            Unit::Ft => "ft",
+        }
+    }
+
+    /// Size of one of this unit in its dimension's base unit (pcs, kg or m),
+    /// as an exact fraction
+    fn in_base_unit(&self) -> (Unit, i128, i128) {
+        match self {
+            Unit::Pcs => (Unit::Pcs, 1, 1),
+            Unit::Kg => (Unit::Kg, 1, 1),
+            // International pound: exactly 0.45359237 kg
+            Unit::Lbs => (Unit::Kg, 45_359_237, 100_000_000),
+            Unit::M => (Unit::M, 1, 1),
+            // International foot: exactly 0.3048 m
+            Unit::Ft => (Unit::M, 3_048, 10_000),
+        }
+    }
+}
+
+impl FromStr for Unit {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "pcs" => Ok(Unit::Pcs),
+            "kg" => Ok(Unit::Kg),
+            "lbs" => Ok(Unit::Lbs),
+            "m" => Ok(Unit::M),
+            "ft" => Ok(Unit::Ft),
+            _ => Err(DomainError::ValidationFailed(format!(
+                "Unknown unit '{}'",
+                s
+            ))),
+        }
+    }
+}
+
+/// Quantity with a unit, held exactly to six decimal places. Serializes as
+/// `{"value": "2.5", "unit": "kg"}` with the value as a string so it never
+/// passes through a float.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(try_from = "QuantityRepr", into = "QuantityRepr")]
+pub struct Quantity {
+    /// Value times `10^SCALE`
+    value_scaled: i64,
+    unit: Unit,
+}
+
+#[derive(Serialize, Deserialize)]
+struct QuantityRepr {
+    value: String,
+    unit: Unit,
+}
+
+impl Quantity {
+    /// Decimal places kept
+    pub const SCALE: u32 = 6;
+    const SCALE_FACTOR: i128 = 1_000_000;
+
+    /// Whole number of `unit`
+    pub fn new(value: i64, unit: Unit) -> Result<Self> {
+        let scaled = i128::from(value) * Self::SCALE_FACTOR;
+        Self::from_scaled(scaled, unit)
+    }
+
+    /// Parse a decimal value such as `"2.5"`, rejecting more than six
+    /// decimal places and fractional pieces
+    pub fn from_decimal(value: &str, unit: Unit) -> Result<Self> {
+        Self::from_scaled(parse_scaled(value, Self::SCALE, None)?, unit)
+    }
+
+    fn from_scaled(value_scaled: i128, unit: Unit) -> Result<Self> {
+        if unit == Unit::Pcs && value_scaled % Self::SCALE_FACTOR != 0 {
+            return Err(DomainError::ValidationFailed(
+                "A piece count must be a whole number".to_string(),
+            ));
+        }
+        let value_scaled = i64::try_from(value_scaled).map_err(|_| {
+            DomainError::InvalidInput(format!("Quantity out of range for {}", unit.as_str()))
+        })?;
+
+        Ok(Quantity { value_scaled, unit })
+    }
+
+    pub fn unit(&self) -> Unit {
+        self.unit
+    }
+
+    pub fn is_positive(&self) -> bool {
+        self.value_scaled > 0
+    }
+
+    /// The value as a decimal string without trailing zeros
+    pub fn to_decimal_string(&self) -> String {
+        format_scaled(self.value_scaled.into(), Self::SCALE, true)
+    }
+
+    /// The same amount in `target`, which must measure the same thing (mass
+    /// or length), rounded to six decimal places
+    pub fn convert_to(&self, target: Unit, rounding: Rounding) -> Result<Quantity> {
+        let (from_base, from_num, from_den) = self.unit.in_base_unit();
+        let (to_base, to_num, to_den) = target.in_base_unit();
+        if from_base != to_base {
+            return Err(DomainError::InvalidInput(format!(
+                "Cannot convert {} to {}",
+                self.unit.as_str(),
+                target.as_str()
+            )));
+        }
+
+        let value = rounding.divide(
+            i128::from(self.value_scaled) * from_num * to_den,
+            from_den * to_num,
+        );
+        Self::from_scaled(value, target)
+    }
+
+    /// Sum of two quantities in this quantity's unit, converting `other` if needed
+    pub fn checked_add(&self, other: &Quantity, rounding: Rounding) -> Result<Quantity> {
+        let other = other.convert_to(self.unit, rounding)?;
+        self.value_scaled
+            .checked_add(other.value_scaled)
+            .map(|value_scaled| Quantity {
+                value_scaled,
+                unit: self.unit,
+            })
+            .ok_or_else(|| {
+                DomainError::InvalidInput(format!(
+                    "Quantity out of range for {}",
+                    self.unit.as_str()
+                ))
+            })
+    }
+}
+
+impl TryFrom<QuantityRepr> for Quantity {
+    type Error = DomainError;
+
+    fn try_from(repr: QuantityRepr) -> Result<Self> {
+        Quantity::from_decimal(&repr.value, repr.unit)
+    }
+}
+
+impl From<Quantity> for QuantityRepr {
+    fn from(quantity: Quantity) -> Self {
+        QuantityRepr {
+            value: quantity.to_decimal_string(),
+            unit: quantity.unit,
+        }
+    }
+}
+
+impl std::fmt::Display for Quantity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.to_decimal_string(), self.unit.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usd(amount: &str) -> Money {
+        Money::from_decimal(amount, "USD".parse().unwrap()).unwrap()
+    }
+
+    fn rounded(amount: &str, rounding: Rounding) -> String {
+        Money::from_decimal_rounded(amount, "USD".parse().unwrap(), rounding)
+            .unwrap()
+            .to_decimal_string()
+    }
+
+    #[test]
+    fn rounding_ties() {
+        let cases = [
+            ("0.125", "0.12", "0.13"),
+            ("0.135", "0.14", "0.14"),
+            ("-0.125", "-0.12", "-0.13"),
+            ("-0.135", "-0.14", "-0.14"),
+            ("2.5", "2.50", "2.50"),
+            ("0.1251", "0.13", "0.13"),
+            ("-0.1249", "-0.12", "-0.12"),
+            ("-0.005", "0.00", "-0.01"),
+        ];
+        for (amount, half_even, half_up) in cases {
+            assert_eq!(rounded(amount, Rounding::HalfEven), half_even, "{}", amount);
+            assert_eq!(rounded(amount, Rounding::HalfUp), half_up, "{}", amount);
+        }
+    }
+
+    #[test]
+    fn ratio_rounding_with_negative_values() {
+        let refund = usd("-0.25");
+        assert_eq!(
+            refund.mul_ratio(1, 2, Rounding::HalfEven).unwrap(),
+            usd("-0.12")
+        );
+        assert_eq!(
+            refund.mul_ratio(1, 2, Rounding::HalfUp).unwrap(),
+            usd("-0.13")
+        );
+        assert_eq!(
+            usd("0.25").mul_ratio(-1, 2, Rounding::HalfUp).unwrap(),
+            usd("-0.13")
+        );
+        assert!(refund.mul_ratio(1, 0, Rounding::HalfEven).is_err());
+    }
+
+    #[test]
+    fn decimal_parsing() {
+        assert_eq!(usd("12.5").amount_minor(), 1250);
+        assert_eq!(usd("-0.01").amount_minor(), -1);
+        assert_eq!(
+            Money::from_decimal("1500", "JPY".parse().unwrap())
+                .unwrap()
+                .amount_minor(),
+            1500
+        );
+        for invalid in ["12.505", "", "-", "1.", ".5", "1e3", "1,50", "+1", " 1"] {
+            assert!(
+                Money::from_decimal(invalid, "USD".parse().unwrap()).is_err(),
+                "{:?}",
+                invalid
+            );
+        }
+        assert!(Money::from_decimal("1.5", "JPY".parse().unwrap()).is_err());
+        assert!(Money::from_decimal("92233720368547758.08", "USD".parse().unwrap()).is_err());
+        for invalid in ["usd", "US", "USDT", "U$D"] {
+            assert!(Currency::new(invalid.to_string()).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn mixed_currencies_are_refused() {
+        let eur = Money::from_decimal("1.00", "EUR".parse().unwrap()).unwrap();
+        assert!(matches!(
+            usd("1.00").checked_add(&eur),
+            Err(DomainError::InvalidInput(_))
+        ));
+        assert!(usd("1.00").checked_sub(&eur).is_err());
+        assert!(Money::sum([&usd("1.00"), &eur], "USD".parse().unwrap()).is_err());
+        assert_eq!(
+            Money::sum([&usd("1.00"), &usd("2.50")], "USD".parse().unwrap()).unwrap(),
+            usd("3.50")
+        );
+    }
+
+    #[test]
+    fn overflow_is_an_error() {
+        let max = Money::new(i64::MAX, "USD".parse().unwrap());
+        assert!(max.checked_add(&usd("0.01")).is_err());
+        assert!(max.checked_mul(2).is_err());
+        assert!(max.mul_ratio(3, 2, Rounding::HalfEven).is_err());
+    }
+
+    #[test]
+    fn unit_price_times_quantity() {
+        let kg = Quantity::from_decimal("2.345", Unit::Kg).unwrap();
+        assert_eq!(
+            usd("1.50").mul_quantity(&kg, Rounding::HalfEven).unwrap(),
+            usd("3.52")
+        );
+        assert_eq!(
+            usd("1.50").mul_quantity(&kg, Rounding::HalfUp).unwrap(),
+            usd("3.52")
+        );
+        let pcs = Quantity::new(500, Unit::Pcs).unwrap();
+        assert_eq!(
+            usd("12.50").mul_quantity(&pcs, Rounding::HalfEven).unwrap(),
+            usd("6250.00")
+        );
+    }
+
+    #[test]
+    fn unit_conversions() {
+        let cases = [
+            ("1", Unit::Lbs, Unit::Kg, "0.453592"),
+            ("100", Unit::Lbs, Unit::Kg, "45.359237"),
+            ("45.359237", Unit::Kg, Unit::Lbs, "100"),
+            ("1", Unit::Kg, Unit::Lbs, "2.204623"),
+            ("1", Unit::Ft, Unit::M, "0.3048"),
+            ("0.3048", Unit::M, Unit::Ft, "1"),
+            ("1", Unit::M, Unit::Ft, "3.28084"),
+            ("-2.5", Unit::Ft, Unit::M, "-0.762"),
+            ("7", Unit::Kg, Unit::Kg, "7"),
+        ];
+        for (value, from, to, expected) in cases {
+            let converted = Quantity::from_decimal(value, from)
+                .unwrap()
+                .convert_to(to, Rounding::HalfEven)
+                .unwrap();
+            assert_eq!(converted.unit(), to);
+            assert_eq!(
+                converted.to_decimal_string(),
+                expected,
+                "{} {:?}",
+                value,
+                from
+            );
+        }
+
+        let one_kg = Quantity::new(1, Unit::Kg).unwrap();
+        assert!(one_kg.convert_to(Unit::M, Rounding::HalfEven).is_err());
+        assert!(one_kg.convert_to(Unit::Pcs, Rounding::HalfEven).is_err());
+        assert_eq!(
+            one_kg
+                .checked_add(&Quantity::new(1, Unit::Lbs).unwrap(), Rounding::HalfEven)
+                .unwrap(),
+            Quantity::from_decimal("1.453592", Unit::Kg).unwrap()
+        );
+    }
+
+    #[test]
+    fn pieces_are_whole() {
+        assert!(Quantity::from_decimal("2.5", Unit::Pcs).is_err());
+        assert!(Quantity::from_decimal("2.000001", Unit::Pcs).is_err());
+        assert_eq!(
+            Quantity::from_decimal("3.000", Unit::Pcs).unwrap(),
+            Quantity::new(3, Unit::Pcs).unwrap()
+        );
+        assert!(Quantity::from_decimal("0.0000001", Unit::Kg).is_err());
+        assert!(serde_json::from_str::<Quantity>(r#"{"value":"1.5","unit":"pcs"}"#).is_err());
+        assert!("box".parse::<Unit>().is_err());
+    }
+
+    #[test]
+    fn money_serde_round_trip() {
+        for (json, amount) in [
+            (r#"{"amount":"12.50","currency":"USD"}"#, 1250),
+            (r#"{"amount":"-0.01","currency":"USD"}"#, -1),
+            (r#"{"amount":"1500","currency":"JPY"}"#, 1500),
+            (r#"{"amount":"0.123","currency":"KWD"}"#, 123),
+            (
+                r#"{"amount":"92233720368547758.07","currency":"USD"}"#,
+                i64::MAX,
+            ),
+        ] {
+            let money: Money = serde_json::from_str(json).unwrap();
+            assert_eq!(money.amount_minor(), amount);
+            assert_eq!(serde_json::to_string(&money).unwrap(), json);
+        }
+
+        // Every cent value survives the trip, unlike via f64
+        let money: Money = serde_json::from_str(r#"{"amount":"0.1","currency":"USD"}"#).unwrap();
+        let sum = money.checked_add(&usd("0.2")).unwrap();
+        assert_eq!(
+            serde_json::to_value(&sum).unwrap(),
+            serde_json::json!({"amount": "0.30", "currency": "USD"})
+        );
+
+        for invalid in [
+            r#"{"amount":12.5,"currency":"USD"}"#,
+            r#"{"amount":"12.505","currency":"USD"}"#,
+            r#"{"amount":"1","currency":"usd"}"#,
+        ] {
+            assert!(
+                serde_json::from_str::<Money>(invalid).is_err(),
+                "{}",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn quantity_serde_round_trip() {
+        for (json, unit) in [
+            (r#"{"value":"2.5","unit":"kg"}"#, Unit::Kg),
+            (r#"{"value":"500","unit":"pcs"}"#, Unit::Pcs),
+            (r#"{"value":"0.000001","unit":"lbs"}"#, Unit::Lbs),
+            (r#"{"value":"-3.25","unit":"ft"}"#, Unit::Ft),
+            (r#"{"value":"1000000","unit":"m"}"#, Unit::M),
+        ] {
+            let quantity: Quantity = serde_json::from_str(json).unwrap();
+            assert_eq!(quantity.unit(), unit);
+            assert_eq!(serde_json::to_string(&quantity).unwrap(), json);
+        }
+
+        let trailing: Quantity = serde_json::from_str(r#"{"value":"2.500","unit":"kg"}"#).unwrap();
+        assert_eq!(
+            serde_json::to_string(&trailing).unwrap(),
+            r#"{"value":"2.5","unit":"kg"}"#
+        );
+    }
+}
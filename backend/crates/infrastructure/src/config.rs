@@ -1,4 +1,16 @@
+use application::access::{AccessTokens, AdminCredentials, AdminJwtConfig};
+use domain::error::{DomainError, Result};
 use std::env;
+use std::path::PathBuf;
+
+/// Where repositories keep their objects
+#[derive(Debug, Clone, PartialEq)]
+pub enum StorageBackend {
+    /// AWS S3 (or LocalStack via `AWS_ENDPOINT_URL`)
+    S3,
+    /// Local directory tree mirroring the S3 key layout, one directory per bucket
+    Fs,
+}
 
 pub struct Config {
     pub public_bucket: String,
@@ -6,6 +18,29 @@ pub struct Config {
     pub environment: String,
     pub region: String,
     pub aws_endpoint_url: Option<String>,
+    pub idempotency_ttl_seconds: i64,
+    pub idempotency_lock_timeout_seconds: i64,
+    pub storage_backend: StorageBackend,
+    pub storage_root: PathBuf,
+    pub s3_max_attempts: u32,
+    pub event_fetch_concurrency: usize,
+    /// Key for signing participant access tokens; required outside dev
+    pub access_token_secret: Option<String>,
+    /// Secrets Manager secret holding `access_token_secret`, read by `load`
+    pub access_token_secret_arn: Option<String>,
+    pub access_token_ttl_days: i64,
+    /// Frontend origin that links in notification emails point to
+    pub app_base_url: String,
+    /// Static bearer secrets for admin routes; several may be valid at once
+    /// while one is rotated out
+    pub admin_api_keys: Vec<String>,
+    /// Secrets Manager secret holding `admin_api_keys`, read by `load`
+    pub admin_api_keys_secret_arn: Option<String>,
+    pub admin_jwt_issuer: Option<String>,
+    pub admin_jwt_audience: Option<String>,
+    pub admin_jwt_hs256_secret: Option<String>,
+    /// PEM encoded RSA public key for RS256 admin JWTs
+    pub admin_jwt_rs256_public_key: Option<String>,
 }
 
 impl Config {
@@ -20,7 +55,121 @@ impl Config {
             environment: env,
             region: env::var("AWS_REGION").unwrap_or_else(|_| "us-east-1".to_string()),
             aws_endpoint_url: env::var("AWS_ENDPOINT_URL").ok(),
+            idempotency_ttl_seconds: env::var("IDEMPOTENCY_TTL_SECONDS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(24 * 60 * 60),
+            idempotency_lock_timeout_seconds: env::var("IDEMPOTENCY_LOCK_TIMEOUT_SECONDS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(60),
+            storage_backend: match env::var("STORAGE_BACKEND").as_deref() {
+                Ok("fs") => StorageBackend::Fs,
+                _ => StorageBackend::S3,
+            },
+            storage_root: env::var("STORAGE_ROOT")
+                .map(PathBuf::from)
+                .unwrap_or_else(|_| PathBuf::from("./data")),
+            s3_max_attempts: env::var("S3_MAX_ATTEMPTS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(3),
+            event_fetch_concurrency: env::var("EVENT_FETCH_CONCURRENCY")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(16),
+            access_token_secret: env::var("ACCESS_TOKEN_SECRET").ok(),
+            access_token_secret_arn: env::var("ACCESS_TOKEN_SECRET_ARN").ok(),
+            access_token_ttl_days: env::var("ACCESS_TOKEN_TTL_DAYS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(30),
+            app_base_url: env::var("APP_BASE_URL")
+                .unwrap_or_else(|_| "http://localhost:4321".to_string()),
+            admin_api_keys: env::var("ADMIN_API_KEYS")
+                .map(|keys| split_keys(&keys))
+                .unwrap_or_default(),
+            admin_api_keys_secret_arn: env::var("ADMIN_API_KEYS_SECRET_ARN").ok(),
+            admin_jwt_issuer: env::var("ADMIN_JWT_ISSUER").ok(),
+            admin_jwt_audience: env::var("ADMIN_JWT_AUDIENCE").ok(),
+            admin_jwt_hs256_secret: env::var("ADMIN_JWT_HS256_SECRET").ok(),
+            admin_jwt_rs256_public_key: env::var("ADMIN_JWT_RS256_PUBLIC_KEY").ok(),
+        }
+    }
+
+    /// `from_env`, with secrets given by ARN read from Secrets Manager, so
+    /// their values never sit in the Lambda environment
+    pub async fn load() -> Result<Self> {
+        let mut config = Self::from_env();
+        if config.access_token_secret_arn.is_none() && config.admin_api_keys_secret_arn.is_none() {
+            return Ok(config);
+        }
+
+        let client = aws_sdk_secretsmanager::Client::new(&config.create_aws_config().await);
+        if let Some(arn) = &config.access_token_secret_arn {
+            config.access_token_secret = Some(read_secret(&client, arn).await?);
+        }
+        if let Some(arn) = &config.admin_api_keys_secret_arn {
+            config.admin_api_keys = split_keys(&read_secret(&client, arn).await?);
         }
+        Ok(config)
+    }
+
+    /// Signer for participant access tokens. Every Lambda issuing or
+    /// verifying tokens must share the secret, so it is required everywhere.
+    pub fn access_tokens(&self) -> Result<AccessTokens> {
+        let ttl = chrono::Duration::days(self.access_token_ttl_days);
+        match &self.access_token_secret {
+            Some(secret) => AccessTokens::new(secret.as_bytes(), ttl),
+            None => Err(DomainError::Internal(
+                "ACCESS_TOKEN_SECRET must be set".to_string(),
+            )),
+        }
+    }
+
+    /// Signer for a single process keeping all data in memory. Nothing else
+    /// verifies its tokens, so a missing secret is replaced by a random one
+    /// and tokens only last until restart.
+    pub fn in_memory_access_tokens(&self) -> Result<AccessTokens> {
+        if self.access_token_secret.is_some() {
+            return self.access_tokens();
+        }
+        tracing::warn!("ACCESS_TOKEN_SECRET is not set; using a random secret");
+        Ok(AccessTokens::with_random_secret(chrono::Duration::days(
+            self.access_token_ttl_days,
+        )))
+    }
+
+    /// Verifier for admin credentials. JWTs are accepted once a key is set,
+    /// which then needs an issuer and audience too. With nothing configured
+    /// every admin request is rejected.
+    pub fn admin_credentials(&self) -> Result<AdminCredentials> {
+        let jwt = if self.admin_jwt_hs256_secret.is_some()
+            || self.admin_jwt_rs256_public_key.is_some()
+        {
+            let (Some(issuer), Some(audience)) = (&self.admin_jwt_issuer, &self.admin_jwt_audience)
+            else {
+                return Err(DomainError::Internal(
+                    "ADMIN_JWT_ISSUER and ADMIN_JWT_AUDIENCE must be set".to_string(),
+                ));
+            };
+            Some(AdminJwtConfig {
+                issuer: issuer.clone(),
+                audience: audience.clone(),
+                hs256_secret: self.admin_jwt_hs256_secret.clone(),
+                rs256_public_key: self.admin_jwt_rs256_public_key.clone(),
+            })
+        } else {
+            None
+        };
+
+        let credentials = AdminCredentials::new(&self.admin_api_keys, jwt)?;
+        if !credentials.is_configured() {
+            tracing::warn!(
+                "No admin API keys or JWT keys configured; admin routes reject all requests"
+            );
+        }
+        Ok(credentials)
     }
 
     /// Create AWS config with optional endpoint override for LocalStack
@@ -37,3 +186,61 @@ impl Config {
         config_loader.load().await
     }
 }
+
+/// Comma separated keys, ignoring blanks
+fn split_keys(keys: &str) -> Vec<String> {
+    keys.split(',')
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+async fn read_secret(client: &aws_sdk_secretsmanager::Client, arn: &str) -> Result<String> {
+    client
+        .get_secret_value()
+        .secret_id(arn)
+        .send()
+        .await
+        .map_err(|e| DomainError::Internal(format!("Failed to read secret {}: {}", arn, e)))?
+        .secret_string
+        .ok_or_else(|| DomainError::Internal(format!("Secret {} has no string value", arn)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use domain::entities::ParticipantRole;
+
+    fn config(secret: Option<&str>) -> Config {
+        let mut config = Config::from_env();
+        config.environment = "dev".to_string();
+        config.access_token_secret = secret.map(str::to_string);
+        config
+    }
+
+    #[test]
+    fn access_tokens_need_a_shared_secret_even_in_dev() {
+        assert!(config(None).access_tokens().is_err());
+        assert!(config(Some("too short")).access_tokens().is_err());
+
+        // Two Lambdas configured alike accept each other's tokens
+        let secret = Some("0123456789abcdef0123456789abcdef");
+        let token = config(secret).access_tokens().unwrap().issue(
+            "r_1",
+            ParticipantRole::Buyer,
+            "buyer@example.com",
+        );
+        assert!(config(secret)
+            .access_tokens()
+            .unwrap()
+            .verify(&token)
+            .is_ok());
+    }
+
+    #[test]
+    fn only_in_memory_servers_fall_back_to_a_random_secret() {
+        assert!(config(None).in_memory_access_tokens().is_ok());
+        assert!(config(Some("too short")).in_memory_access_tokens().is_err());
+    }
+}
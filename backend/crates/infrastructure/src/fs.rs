@@ -0,0 +1,845 @@
+use application::ports::*;
+use async_trait::async_trait;
+use chrono::Utc;
+use domain::entities::*;
+use domain::error::{DomainError, Result};
+use domain::events::*;
+use domain::value_objects::*;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::Digest;
+use std::io::ErrorKind;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+use crate::config::Config;
+use crate::s3::{IdempotencyRecord, IdempotencyState};
+
+/// Object store on the local filesystem. Each bucket is a directory under
+/// `root` and every S3 key maps to the file at the same relative path.
+#[derive(Clone)]
+pub struct FsStore {
+    root: PathBuf,
+    /// Serializes conditional writes between clones of this store
+    write_lock: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl FsStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            write_lock: Arc::default(),
+        }
+    }
+
+    fn path(&self, bucket: &str, key: &str) -> Result<PathBuf> {
+        let relative = Path::new(key);
+        if key.is_empty()
+            || !relative
+                .components()
+                .all(|c| matches!(c, Component::Normal(_)))
+        {
+            return Err(DomainError::Internal(format!(
+                "Refusing to map key '{}' to a file path",
+                key
+            )));
+        }
+
+        Ok(self.root.join(bucket).join(relative))
+    }
+
+    pub async fn read_json<T: DeserializeOwned>(
+        &self,
+        bucket: &str,
+        key: &str,
+    ) -> Result<Option<T>> {
+        Ok(self
+            .read_json_versioned(bucket, key)
+            .await?
+            .map(|versioned| versioned.value))
+    }
+
+    /// Read an object along with its version, a hash of the stored bytes
+    pub async fn read_json_versioned<T: DeserializeOwned>(
+        &self,
+        bucket: &str,
+        key: &str,
+    ) -> Result<Option<Versioned<T>>> {
+        let path = self.path(bucket, key)?;
+
+        let Some(bytes) = self.read_bytes(&path).await? else {
+            return Ok(None);
+        };
+
+        let value = serde_json::from_slice(&bytes).map_err(|e| {
+            DomainError::Internal(format!("Failed to deserialize {}: {}", path.display(), e))
+        })?;
+
+        Ok(Some(Versioned {
+            value,
+            version: Self::version_of(&bytes),
+        }))
+    }
+
+    async fn read_bytes(&self, path: &Path) -> Result<Option<Vec<u8>>> {
+        match tokio::fs::read(path).await {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(DomainError::Internal(format!(
+                "Failed to read {}: {}",
+                path.display(),
+                e
+            ))),
+        }
+    }
+
+    /// Size in bytes of a stored object, `None` if it does not exist
+    pub async fn size(&self, bucket: &str, key: &str) -> Result<Option<u64>> {
+        let path = self.path(bucket, key)?;
+        match tokio::fs::metadata(&path).await {
+            Ok(metadata) if metadata.is_file() => Ok(Some(metadata.len())),
+            Ok(_) => Ok(None),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(DomainError::Internal(format!(
+                "Failed to stat {}: {}",
+                path.display(),
+                e
+            ))),
+        }
+    }
+
+    fn version_of(bytes: &[u8]) -> String {
+        format!("{:x}", sha2::Sha256::digest(bytes))
+    }
+
+    /// Write an object only if it is still at `expected_version`, or absent when
+    /// `None`. Fails with `DomainError::Conflict` otherwise; returns the new version.
+    pub async fn write_json_if<T: Serialize>(
+        &self,
+        bucket: &str,
+        key: &str,
+        value: &T,
+        expected_version: Option<&str>,
+    ) -> Result<String> {
+        let path = self.path(bucket, key)?;
+        let body = serde_json::to_vec_pretty(value)
+            .map_err(|e| DomainError::Internal(format!("Failed to serialize {}: {}", key, e)))?;
+        let _guard = self.write_lock.lock().await;
+
+        let current_version = self
+            .read_bytes(&path)
+            .await?
+            .map(|bytes| Self::version_of(&bytes));
+        if current_version.as_deref() != expected_version {
+            return Err(DomainError::Conflict(format!(
+                "Concurrent modification of {}",
+                key
+            )));
+        }
+
+        self.write_bytes(&path, &body).await?;
+        Ok(Self::version_of(&body))
+    }
+
+    /// Write through a temporary file and rename, so readers never see a partial object
+    pub async fn write_json<T: Serialize>(&self, bucket: &str, key: &str, value: &T) -> Result<()> {
+        let path = self.path(bucket, key)?;
+        let body = serde_json::to_vec_pretty(value)
+            .map_err(|e| DomainError::Internal(format!("Failed to serialize {}: {}", key, e)))?;
+
+        self.write_bytes(&path, &body).await
+    }
+
+    async fn write_bytes(&self, path: &Path, body: &[u8]) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await.map_err(|e| {
+                DomainError::Internal(format!("Failed to create {}: {}", parent.display(), e))
+            })?;
+        }
+
+        let tmp_path = path.with_extension(format!("tmp-{}", uuid::Uuid::new_v4().simple()));
+        tokio::fs::write(&tmp_path, body).await.map_err(|e| {
+            DomainError::Internal(format!("Failed to write {}: {}", tmp_path.display(), e))
+        })?;
+        tokio::fs::rename(&tmp_path, path).await.map_err(|e| {
+            DomainError::Internal(format!("Failed to write {}: {}", path.display(), e))
+        })?;
+
+        Ok(())
+    }
+
+    /// Create the object only if it does not exist yet. Returns `false` if it did.
+    pub async fn create_json<T: Serialize>(
+        &self,
+        bucket: &str,
+        key: &str,
+        value: &T,
+    ) -> Result<bool> {
+        use tokio::io::AsyncWriteExt;
+
+        let path = self.path(bucket, key)?;
+        let body = serde_json::to_vec_pretty(value)
+            .map_err(|e| DomainError::Internal(format!("Failed to serialize {}: {}", key, e)))?;
+
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await.map_err(|e| {
+                DomainError::Internal(format!("Failed to create {}: {}", parent.display(), e))
+            })?;
+        }
+
+        let mut file = match tokio::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .await
+        {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => return Ok(false),
+            Err(e) => {
+                return Err(DomainError::Internal(format!(
+                    "Failed to create {}: {}",
+                    path.display(),
+                    e
+                )))
+            }
+        };
+
+        file.write_all(&body).await.map_err(|e| {
+            DomainError::Internal(format!("Failed to write {}: {}", path.display(), e))
+        })?;
+
+        Ok(true)
+    }
+
+    pub async fn delete(&self, bucket: &str, key: &str) -> Result<()> {
+        let path = self.path(bucket, key)?;
+
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(DomainError::Internal(format!(
+                "Failed to delete {}: {}",
+                path.display(),
+                e
+            ))),
+        }
+    }
+
+    /// Delete an object only while it is still at `expected_version`; a
+    /// missing or replaced object is left as it is
+    pub async fn delete_if(&self, bucket: &str, key: &str, expected_version: &str) -> Result<()> {
+        let path = self.path(bucket, key)?;
+        let _guard = self.write_lock.lock().await;
+
+        let current_version = self
+            .read_bytes(&path)
+            .await?
+            .map(|bytes| Self::version_of(&bytes));
+        if current_version.as_deref() != Some(expected_version) {
+            return Ok(());
+        }
+
+        self.delete(bucket, key).await
+    }
+
+    /// Keys of the `.json` objects directly under a `/`-terminated prefix, in lexical order
+    pub async fn list_keys(&self, bucket: &str, prefix: &str) -> Result<Vec<String>> {
+        let mut keys: Vec<String> = self
+            .list_entries(bucket, prefix)
+            .await?
+            .into_iter()
+            .filter(|(name, is_dir)| !is_dir && name.ends_with(".json"))
+            .map(|(name, _)| format!("{}{}", prefix, name))
+            .collect();
+        keys.sort();
+
+        Ok(keys)
+    }
+
+    /// Names of the "directories" directly under a `/`-terminated prefix, in lexical order
+    pub async fn list_dirs(&self, bucket: &str, prefix: &str) -> Result<Vec<String>> {
+        let mut dirs: Vec<String> = self
+            .list_entries(bucket, prefix)
+            .await?
+            .into_iter()
+            .filter(|(_, is_dir)| *is_dir)
+            .map(|(name, _)| name)
+            .collect();
+        dirs.sort();
+
+        Ok(dirs)
+    }
+
+    /// Entry names under a prefix and whether each is a directory
+    async fn list_entries(&self, bucket: &str, prefix: &str) -> Result<Vec<(String, bool)>> {
+        let dir = self.path(bucket, prefix.trim_end_matches('/'))?;
+        let list_error = |e: std::io::Error| {
+            DomainError::Internal(format!("Failed to list {}: {}", dir.display(), e))
+        };
+
+        let mut entries = match tokio::fs::read_dir(&dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(list_error(e)),
+        };
+
+        let mut names = Vec::new();
+        while let Some(entry) = entries.next_entry().await.map_err(list_error)? {
+            let is_dir = entry.file_type().await.map_err(list_error)?.is_dir();
+            names.push((entry.file_name().to_string_lossy().into_owned(), is_dir));
+        }
+
+        Ok(names)
+    }
+}
+
+/// Filesystem-backed RFQ repository using the S3 key layout
+pub struct FsRfqRepository {
+    store: FsStore,
+    config: Arc<Config>,
+}
+
+impl FsRfqRepository {
+    pub fn new(store: FsStore, config: Arc<Config>) -> Self {
+        Self { store, config }
+    }
+
+    /// All RFQ ids with objects in the private bucket
+    pub async fn list_rfq_ids(&self) -> Result<Vec<RfqId>> {
+        let dirs = self
+            .store
+            .list_dirs(&self.config.private_bucket, "rfq/")
+            .await?;
+
+        Ok(dirs
+            .into_iter()
+            .filter_map(|id| RfqId::new(id).ok())
+            .collect())
+    }
+
+    /// Every inbox entry of one manufacturer, in no particular order. A local
+    /// inbox is small enough to page in memory.
+    async fn inbox_entries(&self, manufacturer_id: &ManufacturerId) -> Result<Vec<RfqInboxEntry>> {
+        let prefix = format!("mfg/{}/rfqs/", manufacturer_id.as_str());
+
+        let mut entries = Vec::new();
+        for key in self
+            .store
+            .list_keys(&self.config.private_bucket, &prefix)
+            .await?
+        {
+            match self
+                .store
+                .read_json::<RfqInboxEntry>(&self.config.private_bucket, &key)
+                .await
+            {
+                Ok(Some(entry)) => entries.push(entry),
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Failed to read inbox entry {}: {}", key, e),
+            }
+        }
+        Ok(entries)
+    }
+}
+
+#[async_trait]
+impl RfqRepository for FsRfqRepository {
+    async fn save_rfq_meta(&self, rfq: &RfqMeta, expected_version: Option<&str>) -> Result<String> {
+        let key = format!("rfq/{}/meta.json", rfq.id);
+        self.store
+            .write_json_if(&self.config.private_bucket, &key, rfq, expected_version)
+            .await
+    }
+
+    async fn get_rfq_meta(&self, id: &RfqId) -> Result<Option<Versioned<RfqMeta>>> {
+        let key = format!("rfq/{}/meta.json", id.as_str());
+        self.store
+            .read_json_versioned(&self.config.private_bucket, &key)
+            .await
+    }
+
+    async fn save_rfq_index(
+        &self,
+        rfq_id: &RfqId,
+        index: &RfqIndex,
+        expected_version: Option<&str>,
+    ) -> Result<String> {
+        let key = format!("rfq/{}/index.json", rfq_id.as_str());
+        self.store
+            .write_json_if(&self.config.private_bucket, &key, index, expected_version)
+            .await
+    }
+
+    async fn get_rfq_index(&self, id: &RfqId) -> Result<Option<Versioned<RfqIndex>>> {
+        let key = format!("rfq/{}/index.json", id.as_str());
+        self.store
+            .read_json_versioned(&self.config.private_bucket, &key)
+            .await
+    }
+
+    async fn save_rfq_event(&self, event: &RfqEvent) -> Result<()> {
+        let key = format!("rfq/{}/events/{}.json", event.rfq_id(), event.sort_key());
+        self.store
+            .write_json(&self.config.private_bucket, &key, event)
+            .await
+    }
+
+    async fn list_rfq_events(&self, rfq_id: &RfqId, query: &EventQuery) -> Result<EventPage> {
+        let prefix = format!("rfq/{}/events/", rfq_id.as_str());
+
+        let positions = self
+            .store
+            .list_keys(&self.config.private_bucket, &prefix)
+            .await?
+            .iter()
+            .filter_map(|key| key.strip_prefix(&prefix)?.strip_suffix(".json"))
+            .map(str::to_string)
+            .collect();
+        let (positions, has_more) = query.select(positions);
+
+        let mut events = Vec::new();
+        for position in &positions {
+            let key = format!("{}{}.json", prefix, position);
+            match self
+                .store
+                .read_json::<RfqEvent>(&self.config.private_bucket, &key)
+                .await
+            {
+                Ok(Some(event)) => events.push(event),
+                Ok(None) => {}
+                Err(e) => {
+                    // Log the error but continue processing other events
+                    tracing::warn!("Failed to read event {}: {}", key, e);
+                }
+            }
+        }
+
+        Ok(EventPage::new(query, &positions, events, has_more))
+    }
+
+    async fn save_attachment_access(&self, access: &AttachmentAccess) -> Result<()> {
+        let key = format!(
+            "rfq/{}/audit/downloads/{}-{}.json",
+            access.rfq_id,
+            RfqEvent::sort_key_prefix(access.issued_at),
+            access.id
+        );
+        self.store
+            .write_json(&self.config.private_bucket, &key, access)
+            .await
+    }
+
+    async fn save_campaign(&self, campaign: &RfqCampaign) -> Result<()> {
+        let key = format!("campaign/{}.json", campaign.id);
+        self.store
+            .write_json(&self.config.private_bucket, &key, campaign)
+            .await
+    }
+
+    async fn get_campaign(&self, id: &CampaignId) -> Result<Option<RfqCampaign>> {
+        let key = format!("campaign/{}.json", id.as_str());
+        self.store
+            .read_json(&self.config.private_bucket, &key)
+            .await
+    }
+
+    async fn save_inbox_entry(
+        &self,
+        manufacturer_id: &ManufacturerId,
+        entry: &RfqInboxEntry,
+        expected_version: Option<&str>,
+    ) -> Result<String> {
+        let key = format!(
+            "mfg/{}/rfqs/{}.json",
+            manufacturer_id.as_str(),
+            entry.rfq_id
+        );
+        self.store
+            .write_json_if(&self.config.private_bucket, &key, entry, expected_version)
+            .await
+    }
+
+    async fn get_inbox_entry(
+        &self,
+        manufacturer_id: &ManufacturerId,
+        rfq_id: &RfqId,
+    ) -> Result<Option<Versioned<RfqInboxEntry>>> {
+        let key = format!(
+            "mfg/{}/rfqs/{}.json",
+            manufacturer_id.as_str(),
+            rfq_id.as_str()
+        );
+        self.store
+            .read_json_versioned(&self.config.private_bucket, &key)
+            .await
+    }
+
+    async fn list_inbox(
+        &self,
+        manufacturer_id: &ManufacturerId,
+        query: &InboxPageQuery,
+    ) -> Result<InboxPage> {
+        Ok(query.select(self.inbox_entries(manufacturer_id).await?))
+    }
+
+    async fn count_unread_inbox(&self, manufacturer_id: &ManufacturerId) -> Result<usize> {
+        Ok(self
+            .inbox_entries(manufacturer_id)
+            .await?
+            .iter()
+            .filter(|entry| entry.is_unread())
+            .count())
+    }
+}
+
+/// Filesystem-backed manufacturer repository using the S3 key layout
+pub struct FsManufacturerRepository {
+    store: FsStore,
+    config: Arc<Config>,
+}
+
+impl FsManufacturerRepository {
+    pub fn new(store: FsStore, config: Arc<Config>) -> Self {
+        Self { store, config }
+    }
+}
+
+#[async_trait]
+impl ManufacturerRepository for FsManufacturerRepository {
+    async fn save_manufacturer(&self, manufacturer: &ManufacturerProfile) -> Result<()> {
+        let key = format!("manufacturer/{}.json", manufacturer.id);
+        self.store
+            .write_json(&self.config.public_bucket, &key, manufacturer)
+            .await
+    }
+
+    async fn get_manufacturer(&self, id: &ManufacturerId) -> Result<Option<ManufacturerProfile>> {
+        let key = format!("manufacturer/{}.json", id.as_str());
+        self.store.read_json(&self.config.public_bucket, &key).await
+    }
+
+    async fn delete_manufacturer(&self, id: &ManufacturerId) -> Result<()> {
+        let key = format!("manufacturer/{}.json", id.as_str());
+        self.store.delete(&self.config.public_bucket, &key).await
+    }
+}
+
+/// Filesystem-backed tenant API key repository using the S3 key layout
+pub struct FsApiKeyRepository {
+    store: FsStore,
+    config: Arc<Config>,
+}
+
+impl FsApiKeyRepository {
+    pub fn new(store: FsStore, config: Arc<Config>) -> Self {
+        Self { store, config }
+    }
+}
+
+#[async_trait]
+impl ApiKeyRepository for FsApiKeyRepository {
+    async fn save_api_key(&self, key: &TenantApiKey) -> Result<()> {
+        let path = format!("tenants/{}/api_keys/{}.json", key.tenant_id, key.id);
+        self.store
+            .write_json(&self.config.private_bucket, &path, key)
+            .await
+    }
+
+    async fn get_api_key(
+        &self,
+        tenant_id: &TenantId,
+        id: &ApiKeyId,
+    ) -> Result<Option<TenantApiKey>> {
+        let path = format!(
+            "tenants/{}/api_keys/{}.json",
+            tenant_id.as_str(),
+            id.as_str()
+        );
+        self.store
+            .read_json(&self.config.private_bucket, &path)
+            .await
+    }
+
+    async fn list_api_keys(&self, tenant_id: &TenantId) -> Result<Vec<TenantApiKey>> {
+        let prefix = format!("tenants/{}/api_keys/", tenant_id.as_str());
+
+        let mut keys = Vec::new();
+        for path in self
+            .store
+            .list_keys(&self.config.private_bucket, &prefix)
+            .await?
+        {
+            if let Some(key) = self
+                .store
+                .read_json(&self.config.private_bucket, &path)
+                .await?
+            {
+                keys.push(key);
+            }
+        }
+        Ok(keys)
+    }
+}
+
+/// Filesystem-backed catalog repository using the S3 key layout
+pub struct FsCatalogRepository {
+    store: FsStore,
+    config: Arc<Config>,
+}
+
+impl FsCatalogRepository {
+    pub fn new(store: FsStore, config: Arc<Config>) -> Self {
+        Self { store, config }
+    }
+}
+
+#[async_trait]
+impl CatalogRepository for FsCatalogRepository {
+    async fn save_category_slice(&self, slice: &CategorySlice) -> Result<()> {
+        let key = format!("catalog/category/{}.json", slice.category);
+        self.store
+            .write_json(&self.config.public_bucket, &key, slice)
+            .await
+    }
+
+    async fn get_category_slice(&self, category: &str) -> Result<Option<CategorySlice>> {
+        let key = format!("catalog/category/{}.json", category);
+        self.store.read_json(&self.config.public_bucket, &key).await
+    }
+
+    async fn save_category_state_slice(
+        &self,
+        category: &str,
+        state: &str,
+        slice: &CategorySlice,
+    ) -> Result<()> {
+        let key = format!("catalog/category_state/{}/{}.json", category, state);
+        self.store
+            .write_json(&self.config.public_bucket, &key, slice)
+            .await
+    }
+
+    async fn get_category_state_slice(
+        &self,
+        category: &str,
+        state: &str,
+    ) -> Result<Option<CategorySlice>> {
+        let key = format!("catalog/category_state/{}/{}.json", category, state);
+        self.store.read_json(&self.config.public_bucket, &key).await
+    }
+}
+
+/// Headers a presigned S3 upload would carry, kept next to the upload target
+/// at `{key}.upload.json` since plain files have no object metadata
+#[derive(Debug, Serialize, Deserialize)]
+struct UploadMetadata {
+    tenant_id: String,
+    content_type: String,
+}
+
+/// Filesystem-backed image service using the S3 key layout
+pub struct FsImageService {
+    store: FsStore,
+    config: Arc<Config>,
+}
+
+impl FsImageService {
+    pub fn new(store: FsStore, config: Arc<Config>) -> Self {
+        Self { store, config }
+    }
+}
+
+#[async_trait]
+impl ImageService for FsImageService {
+    async fn generate_presigned_upload_url(
+        &self,
+        tenant_id: &TenantId,
+        content_type: &ContentType,
+        _size: &FileSize,
+    ) -> Result<application::dto::PresignUploadResponse> {
+        let file_id = uuid::Uuid::new_v4();
+        let extension = match content_type.as_str() {
+            "image/jpeg" => "jpg",
+            "image/png" => "png",
+            "image/webp" => "webp",
+            "image/avif" => "avif",
+            "application/pdf" => "pdf",
+            _ => "bin",
+        };
+        let key = format!(
+            "tenants/{}/images/raw/{}.{}",
+            tenant_id.as_str(),
+            file_id,
+            extension
+        );
+
+        // There is nothing to presign locally; hand out the target file path instead
+        let path = self.store.path(&self.config.private_bucket, &key)?;
+        self.store
+            .write_json(
+                &self.config.private_bucket,
+                &format!("{}.upload.json", key),
+                &UploadMetadata {
+                    tenant_id: tenant_id.as_str().to_string(),
+                    content_type: content_type.as_str().to_string(),
+                },
+            )
+            .await?;
+
+        Ok(application::dto::PresignUploadResponse {
+            url: format!("file://{}", path.display()),
+            key,
+            expires_in: 600,
+        })
+    }
+
+    async fn save_image_manifest(&self, manifest: &ImageManifest) -> Result<()> {
+        let key = format!("tenants/shared/manifests/{}.json", manifest.id);
+        self.store
+            .write_json(&self.config.public_bucket, &key, manifest)
+            .await
+    }
+
+    async fn get_image_manifest(&self, id: &str) -> Result<Option<ImageManifest>> {
+        let key = format!("tenants/shared/manifests/{}.json", id);
+        self.store.read_json(&self.config.public_bucket, &key).await
+    }
+
+    /// Locally the object's file path stands in for a download URL
+    async fn generate_presigned_download_url(
+        &self,
+        key: &S3Key,
+        _file_name: &str,
+        _expires_in_seconds: u32,
+    ) -> Result<String> {
+        let path = self.store.path(&self.config.private_bucket, key.as_str())?;
+        Ok(format!("file://{}", path.display()))
+    }
+
+    async fn head_upload(&self, key: &S3Key) -> Result<Option<UploadedObject>> {
+        let bucket = &self.config.private_bucket;
+        let Some(size_bytes) = self.store.size(bucket, key.as_str()).await? else {
+            return Ok(None);
+        };
+        let metadata: Option<UploadMetadata> = self
+            .store
+            .read_json(bucket, &format!("{}.upload.json", key.as_str()))
+            .await?;
+
+        Ok(Some(UploadedObject {
+            size_bytes,
+            content_type: metadata.as_ref().map(|m| m.content_type.clone()),
+            tenant_id: metadata.map(|m| m.tenant_id),
+        }))
+    }
+}
+
+/// Filesystem-backed idempotency service using the S3 key layout
+pub struct FsIdempotencyService {
+    store: FsStore,
+    config: Arc<Config>,
+}
+
+impl FsIdempotencyService {
+    pub fn new(store: FsStore, config: Arc<Config>) -> Self {
+        Self { store, config }
+    }
+
+    fn idempotency_key(&self, key: &str) -> String {
+        let hash = sha2::Sha256::digest(key.as_bytes());
+        format!("idem/{:x}.json", hash)
+    }
+}
+
+#[async_trait]
+impl IdempotencyService for FsIdempotencyService {
+    async fn claim_idempotency(&self, key: &str, body_hash: &str) -> Result<IdempotencyClaim> {
+        let fs_key = self.idempotency_key(key);
+        let bucket = &self.config.private_bucket;
+
+        let marker = IdempotencyRecord {
+            state: IdempotencyState::InProgress,
+            body_hash: body_hash.to_string(),
+            response: String::new(),
+            stored_at: Utc::now(),
+        };
+
+        match self
+            .store
+            .write_json_if(bucket, &fs_key, &marker, None)
+            .await
+        {
+            Ok(version) => return Ok(IdempotencyClaim::Acquired(version)),
+            Err(DomainError::Conflict(_)) => {}
+            Err(e) => return Err(e),
+        }
+
+        let record = match self
+            .store
+            .read_json_versioned::<IdempotencyRecord>(bucket, &fs_key)
+            .await?
+        {
+            Some(current) if !current.value.is_reclaimable(&self.config) => current.value,
+            // Gone, expired or stale: take it over unless someone else just did
+            current => {
+                let version = self
+                    .store
+                    .write_json_if(
+                        bucket,
+                        &fs_key,
+                        &marker,
+                        current.map(|current| current.version).as_deref(),
+                    )
+                    .await?;
+                return Ok(IdempotencyClaim::Acquired(version));
+            }
+        };
+
+        if record.body_hash != body_hash {
+            return Err(DomainError::Conflict(
+                "Idempotency-Key was already used with a different request body".to_string(),
+            ));
+        }
+
+        match record.state {
+            IdempotencyState::Completed => Ok(IdempotencyClaim::Replay(record.response)),
+            IdempotencyState::InProgress => Err(DomainError::Conflict(
+                "A request with this Idempotency-Key is already in progress".to_string(),
+            )),
+        }
+    }
+
+    async fn store_idempotency(
+        &self,
+        key: &str,
+        claim: &str,
+        body_hash: &str,
+        response: &str,
+    ) -> Result<()> {
+        let record = IdempotencyRecord {
+            state: IdempotencyState::Completed,
+            body_hash: body_hash.to_string(),
+            response: response.to_string(),
+            stored_at: Utc::now(),
+        };
+
+        self.store
+            .write_json_if(
+                &self.config.private_bucket,
+                &self.idempotency_key(key),
+                &record,
+                Some(claim),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn release_idempotency(&self, key: &str, claim: &str) -> Result<()> {
+        self.store
+            .delete_if(
+                &self.config.private_bucket,
+                &self.idempotency_key(key),
+                claim,
+            )
+            .await
+    }
+}
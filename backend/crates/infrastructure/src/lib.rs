@@ -1,3 +1,6 @@
 pub mod config;
+pub mod fs;
+pub mod memory;
 pub mod s3;
+pub mod s3_store;
 pub mod ses;
@@ -0,0 +1,914 @@
+use application::ports::*;
+use async_trait::async_trait;
+use domain::entities::*;
+use domain::error::{DomainError, Result};
+use domain::events::*;
+use domain::value_objects::*;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard};
+
+/// Lock a store, surfacing poisoning as an internal error
+fn lock<T>(mutex: &Mutex<T>) -> Result<MutexGuard<'_, T>> {
+    mutex
+        .lock()
+        .map_err(|_| DomainError::Internal("In-memory store lock poisoned".to_string()))
+}
+
+/// Lock a store for inspection helpers, where a poisoned lock is still readable
+fn inspect<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Replace a versioned entry if it is still at `expected_version` (absent when `None`)
+fn put_versioned<T>(
+    entries: &mut HashMap<String, Versioned<T>>,
+    key: &str,
+    value: T,
+    expected_version: Option<&str>,
+) -> Result<String> {
+    let current_version = entries.get(key).map(|entry| entry.version.as_str());
+    if current_version != expected_version {
+        return Err(DomainError::Conflict(format!(
+            "Concurrent modification of {}",
+            key
+        )));
+    }
+
+    let version = uuid::Uuid::new_v4().to_string();
+    entries.insert(
+        key.to_string(),
+        Versioned {
+            value,
+            version: version.clone(),
+        },
+    );
+    Ok(version)
+}
+
+/// In-memory RFQ repository for tests and local development
+#[derive(Default)]
+pub struct InMemoryRfqRepository {
+    metas: Mutex<HashMap<String, Versioned<RfqMeta>>>,
+    indexes: Mutex<HashMap<String, Versioned<RfqIndex>>>,
+    /// Events per RFQ keyed by sort key
+    events: Mutex<HashMap<String, BTreeMap<String, RfqEvent>>>,
+    accesses: Mutex<Vec<AttachmentAccess>>,
+    campaigns: Mutex<HashMap<String, RfqCampaign>>,
+    /// Inbox entries keyed by `{manufacturer_id}/{rfq_id}`
+    inbox: Mutex<HashMap<String, Versioned<RfqInboxEntry>>>,
+    fail_event_writes: AtomicBool,
+}
+
+impl InMemoryRfqRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// All stored RFQ metas
+    pub fn rfqs(&self) -> Vec<RfqMeta> {
+        inspect(&self.metas)
+            .values()
+            .map(|rfq| rfq.value.clone())
+            .collect()
+    }
+
+    /// Events stored for an RFQ, ordered by (ts, id)
+    pub fn events_for(&self, rfq_id: &str) -> Vec<RfqEvent> {
+        let mut events: Vec<RfqEvent> = inspect(&self.events)
+            .get(rfq_id)
+            .map(|events| events.values().cloned().collect())
+            .unwrap_or_default();
+        events.sort_by(|a, b| (a.timestamp(), a.id()).cmp(&(b.timestamp(), b.id())));
+        events
+    }
+
+    /// Audited attachment downloads, oldest first
+    pub fn attachment_accesses(&self) -> Vec<AttachmentAccess> {
+        inspect(&self.accesses).clone()
+    }
+
+    /// Make event writes fail until called again with `false`, to exercise
+    /// what callers leave behind when storage errors
+    pub fn fail_event_writes(&self, fail: bool) {
+        self.fail_event_writes.store(fail, Ordering::SeqCst);
+    }
+
+    /// Every inbox entry of one manufacturer, in no particular order
+    fn inbox_entries(&self, manufacturer_id: &ManufacturerId) -> Result<Vec<RfqInboxEntry>> {
+        let prefix = format!("{}/", manufacturer_id.as_str());
+        Ok(lock(&self.inbox)?
+            .iter()
+            .filter(|(key, _)| key.starts_with(&prefix))
+            .map(|(_, entry)| entry.value.clone())
+            .collect())
+    }
+
+    /// Every stored event across all RFQs
+    pub fn stored_events(&self) -> Vec<RfqEvent> {
+        inspect(&self.events)
+            .values()
+            .flat_map(|events| events.values().cloned())
+            .collect()
+    }
+}
+
+#[async_trait]
+impl RfqRepository for InMemoryRfqRepository {
+    async fn save_rfq_meta(&self, rfq: &RfqMeta, expected_version: Option<&str>) -> Result<String> {
+        let mut metas = lock(&self.metas)?;
+        put_versioned(&mut metas, &rfq.id, rfq.clone(), expected_version)
+    }
+
+    async fn get_rfq_meta(&self, id: &RfqId) -> Result<Option<Versioned<RfqMeta>>> {
+        Ok(lock(&self.metas)?.get(id.as_str()).cloned())
+    }
+
+    async fn save_rfq_index(
+        &self,
+        rfq_id: &RfqId,
+        index: &RfqIndex,
+        expected_version: Option<&str>,
+    ) -> Result<String> {
+        let mut indexes = lock(&self.indexes)?;
+        put_versioned(
+            &mut indexes,
+            rfq_id.as_str(),
+            index.clone(),
+            expected_version,
+        )
+    }
+
+    async fn get_rfq_index(&self, id: &RfqId) -> Result<Option<Versioned<RfqIndex>>> {
+        Ok(lock(&self.indexes)?.get(id.as_str()).cloned())
+    }
+
+    async fn save_rfq_event(&self, event: &RfqEvent) -> Result<()> {
+        if self.fail_event_writes.load(Ordering::SeqCst) {
+            return Err(DomainError::Internal("Event write failed".to_string()));
+        }
+        lock(&self.events)?
+            .entry(event.rfq_id().to_string())
+            .or_default()
+            .insert(event.sort_key(), event.clone());
+        Ok(())
+    }
+
+    async fn list_rfq_events(&self, rfq_id: &RfqId, query: &EventQuery) -> Result<EventPage> {
+        let events = lock(&self.events)?;
+        let Some(rfq_events) = events.get(rfq_id.as_str()) else {
+            return Ok(EventPage::default());
+        };
+
+        let (positions, has_more) = query.select(rfq_events.keys().cloned().collect());
+        let page = positions
+            .iter()
+            .filter_map(|position| rfq_events.get(position).cloned())
+            .collect();
+
+        Ok(EventPage::new(query, &positions, page, has_more))
+    }
+
+    async fn save_attachment_access(&self, access: &AttachmentAccess) -> Result<()> {
+        lock(&self.accesses)?.push(access.clone());
+        Ok(())
+    }
+
+    async fn save_campaign(&self, campaign: &RfqCampaign) -> Result<()> {
+        lock(&self.campaigns)?.insert(campaign.id.clone(), campaign.clone());
+        Ok(())
+    }
+
+    async fn get_campaign(&self, id: &CampaignId) -> Result<Option<RfqCampaign>> {
+        Ok(lock(&self.campaigns)?.get(id.as_str()).cloned())
+    }
+
+    async fn save_inbox_entry(
+        &self,
+        manufacturer_id: &ManufacturerId,
+        entry: &RfqInboxEntry,
+        expected_version: Option<&str>,
+    ) -> Result<String> {
+        let key = format!("{}/{}", manufacturer_id.as_str(), entry.rfq_id);
+        let mut inbox = lock(&self.inbox)?;
+        put_versioned(&mut inbox, &key, entry.clone(), expected_version)
+    }
+
+    async fn get_inbox_entry(
+        &self,
+        manufacturer_id: &ManufacturerId,
+        rfq_id: &RfqId,
+    ) -> Result<Option<Versioned<RfqInboxEntry>>> {
+        let key = format!("{}/{}", manufacturer_id.as_str(), rfq_id.as_str());
+        Ok(lock(&self.inbox)?.get(&key).cloned())
+    }
+
+    async fn list_inbox(
+        &self,
+        manufacturer_id: &ManufacturerId,
+        query: &InboxPageQuery,
+    ) -> Result<InboxPage> {
+        Ok(query.select(self.inbox_entries(manufacturer_id)?))
+    }
+
+    async fn count_unread_inbox(&self, manufacturer_id: &ManufacturerId) -> Result<usize> {
+        Ok(self
+            .inbox_entries(manufacturer_id)?
+            .iter()
+            .filter(|entry| entry.is_unread())
+            .count())
+    }
+}
+
+/// In-memory manufacturer repository for tests and local development
+#[derive(Default)]
+pub struct InMemoryManufacturerRepository {
+    manufacturers: Mutex<HashMap<String, ManufacturerProfile>>,
+}
+
+impl InMemoryManufacturerRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a repository pre-populated with profiles
+    pub fn with_manufacturers(manufacturers: Vec<ManufacturerProfile>) -> Self {
+        Self {
+            manufacturers: Mutex::new(
+                manufacturers
+                    .into_iter()
+                    .map(|m| (m.id.clone(), m))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// All stored manufacturer profiles
+    pub fn manufacturers(&self) -> Vec<ManufacturerProfile> {
+        inspect(&self.manufacturers).values().cloned().collect()
+    }
+}
+
+#[async_trait]
+impl ManufacturerRepository for InMemoryManufacturerRepository {
+    async fn save_manufacturer(&self, manufacturer: &ManufacturerProfile) -> Result<()> {
+        lock(&self.manufacturers)?.insert(manufacturer.id.clone(), manufacturer.clone());
+        Ok(())
+    }
+
+    async fn get_manufacturer(&self, id: &ManufacturerId) -> Result<Option<ManufacturerProfile>> {
+        Ok(lock(&self.manufacturers)?.get(id.as_str()).cloned())
+    }
+
+    async fn delete_manufacturer(&self, id: &ManufacturerId) -> Result<()> {
+        lock(&self.manufacturers)?.remove(id.as_str());
+        Ok(())
+    }
+}
+
+/// In-memory tenant API key repository for tests and local development
+#[derive(Default)]
+pub struct InMemoryApiKeyRepository {
+    /// Keys by `{tenant_id}/{key_id}`
+    keys: Mutex<HashMap<String, TenantApiKey>>,
+}
+
+impl InMemoryApiKeyRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl ApiKeyRepository for InMemoryApiKeyRepository {
+    async fn save_api_key(&self, key: &TenantApiKey) -> Result<()> {
+        lock(&self.keys)?.insert(format!("{}/{}", key.tenant_id, key.id), key.clone());
+        Ok(())
+    }
+
+    async fn get_api_key(
+        &self,
+        tenant_id: &TenantId,
+        id: &ApiKeyId,
+    ) -> Result<Option<TenantApiKey>> {
+        let key = format!("{}/{}", tenant_id.as_str(), id.as_str());
+        Ok(lock(&self.keys)?.get(&key).cloned())
+    }
+
+    async fn list_api_keys(&self, tenant_id: &TenantId) -> Result<Vec<TenantApiKey>> {
+        let prefix = format!("{}/", tenant_id.as_str());
+        Ok(lock(&self.keys)?
+            .iter()
+            .filter(|(key, _)| key.starts_with(&prefix))
+            .map(|(_, api_key)| api_key.clone())
+            .collect())
+    }
+}
+
+/// In-memory catalog repository for tests and local development
+#[derive(Default)]
+pub struct InMemoryCatalogRepository {
+    category_slices: Mutex<HashMap<String, CategorySlice>>,
+    category_state_slices: Mutex<HashMap<(String, String), CategorySlice>>,
+}
+
+impl InMemoryCatalogRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Categories that have a stored slice
+    pub fn categories(&self) -> Vec<String> {
+        inspect(&self.category_slices).keys().cloned().collect()
+    }
+
+    /// (category, state) pairs that have a stored slice
+    pub fn category_states(&self) -> Vec<(String, String)> {
+        inspect(&self.category_state_slices)
+            .keys()
+            .cloned()
+            .collect()
+    }
+}
+
+#[async_trait]
+impl CatalogRepository for InMemoryCatalogRepository {
+    async fn save_category_slice(&self, slice: &CategorySlice) -> Result<()> {
+        lock(&self.category_slices)?.insert(slice.category.clone(), slice.clone());
+        Ok(())
+    }
+
+    async fn get_category_slice(&self, category: &str) -> Result<Option<CategorySlice>> {
+        Ok(lock(&self.category_slices)?.get(category).cloned())
+    }
+
+    async fn save_category_state_slice(
+        &self,
+        category: &str,
+        state: &str,
+        slice: &CategorySlice,
+    ) -> Result<()> {
+        lock(&self.category_state_slices)?
+            .insert((category.to_string(), state.to_string()), slice.clone());
+        Ok(())
+    }
+
+    async fn get_category_state_slice(
+        &self,
+        category: &str,
+        state: &str,
+    ) -> Result<Option<CategorySlice>> {
+        Ok(lock(&self.category_state_slices)?
+            .get(&(category.to_string(), state.to_string()))
+            .cloned())
+    }
+}
+
+/// Upload slot handed out by [`InMemoryImageService`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct IssuedUpload {
+    pub tenant_id: String,
+    pub key: String,
+    pub content_type: String,
+    pub size_bytes: u64,
+}
+
+/// In-memory image service for tests and local development
+#[derive(Default)]
+pub struct InMemoryImageService {
+    uploads: Mutex<Vec<IssuedUpload>>,
+    manifests: Mutex<HashMap<String, ImageManifest>>,
+}
+
+impl InMemoryImageService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Upload slots issued so far, oldest first
+    pub fn issued_uploads(&self) -> Vec<IssuedUpload> {
+        inspect(&self.uploads).clone()
+    }
+
+    /// All stored image manifests
+    pub fn manifests(&self) -> Vec<ImageManifest> {
+        inspect(&self.manifests).values().cloned().collect()
+    }
+}
+
+#[async_trait]
+impl ImageService for InMemoryImageService {
+    async fn generate_presigned_upload_url(
+        &self,
+        tenant_id: &TenantId,
+        content_type: &ContentType,
+        size: &FileSize,
+    ) -> Result<application::dto::PresignUploadResponse> {
+        let key = format!(
+            "tenants/{}/images/raw/{}",
+            tenant_id.as_str(),
+            uuid::Uuid::new_v4()
+        );
+
+        lock(&self.uploads)?.push(IssuedUpload {
+            tenant_id: tenant_id.as_str().to_string(),
+            key: key.clone(),
+            content_type: content_type.as_str().to_string(),
+            size_bytes: size.as_u64(),
+        });
+
+        Ok(application::dto::PresignUploadResponse {
+            url: format!("memory://private/{}", key),
+            key,
+            expires_in: 600,
+        })
+    }
+
+    async fn save_image_manifest(&self, manifest: &ImageManifest) -> Result<()> {
+        lock(&self.manifests)?.insert(manifest.id.clone(), manifest.clone());
+        Ok(())
+    }
+
+    async fn generate_presigned_download_url(
+        &self,
+        key: &S3Key,
+        _file_name: &str,
+        _expires_in_seconds: u32,
+    ) -> Result<String> {
+        Ok(format!("memory://private/{}", key.as_str()))
+    }
+
+    /// Nothing is actually uploaded in memory; every issued slot counts as
+    /// uploaded with the declared size and type
+    async fn head_upload(&self, key: &S3Key) -> Result<Option<UploadedObject>> {
+        Ok(lock(&self.uploads)?
+            .iter()
+            .find(|upload| upload.key == key.as_str())
+            .map(|upload| UploadedObject {
+                size_bytes: upload.size_bytes,
+                content_type: Some(upload.content_type.clone()),
+                tenant_id: Some(upload.tenant_id.clone()),
+            }))
+    }
+
+    async fn get_image_manifest(&self, id: &str) -> Result<Option<ImageManifest>> {
+        Ok(lock(&self.manifests)?.get(id).cloned())
+    }
+}
+
+/// Notification captured by [`InMemoryEmailService`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum SentNotification {
+    RfqCreated {
+        rfq: RfqMeta,
+    },
+    RfqMessage {
+        rfq: RfqMeta,
+        event: RfqEvent,
+        attachments: Vec<AttachmentRef>,
+    },
+    RfqStatus {
+        rfq: RfqMeta,
+        event: RfqEvent,
+    },
+    RfqOutcome {
+        rfq: RfqMeta,
+        event: RfqEvent,
+    },
+    CampaignCreated {
+        campaign: RfqCampaign,
+        rfqs: Vec<RfqMeta>,
+    },
+}
+
+/// In-memory email service that records notifications instead of sending them
+#[derive(Default)]
+pub struct InMemoryEmailService {
+    sent: Mutex<Vec<SentNotification>>,
+}
+
+impl InMemoryEmailService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Notifications "sent" so far, oldest first
+    pub fn sent_emails(&self) -> Vec<SentNotification> {
+        inspect(&self.sent).clone()
+    }
+}
+
+#[async_trait]
+impl EmailService for InMemoryEmailService {
+    async fn send_rfq_created_notification(&self, rfq: &RfqMeta) -> Result<()> {
+        lock(&self.sent)?.push(SentNotification::RfqCreated { rfq: rfq.clone() });
+        Ok(())
+    }
+
+    async fn send_rfq_message_notification(
+        &self,
+        rfq: &RfqMeta,
+        event: &RfqEvent,
+        attachments: &[AttachmentRef],
+    ) -> Result<()> {
+        lock(&self.sent)?.push(SentNotification::RfqMessage {
+            rfq: rfq.clone(),
+            event: event.clone(),
+            attachments: attachments.to_vec(),
+        });
+        Ok(())
+    }
+
+    async fn send_rfq_status_notification(&self, rfq: &RfqMeta, event: &RfqEvent) -> Result<()> {
+        lock(&self.sent)?.push(SentNotification::RfqStatus {
+            rfq: rfq.clone(),
+            event: event.clone(),
+        });
+        Ok(())
+    }
+
+    async fn send_campaign_created_notification(
+        &self,
+        campaign: &RfqCampaign,
+        rfqs: &[RfqMeta],
+    ) -> Result<()> {
+        lock(&self.sent)?.push(SentNotification::CampaignCreated {
+            campaign: campaign.clone(),
+            rfqs: rfqs.to_vec(),
+        });
+        Ok(())
+    }
+
+    async fn send_rfq_outcome_notification(&self, rfq: &RfqMeta, event: &RfqEvent) -> Result<()> {
+        lock(&self.sent)?.push(SentNotification::RfqOutcome {
+            rfq: rfq.clone(),
+            event: event.clone(),
+        });
+        Ok(())
+    }
+}
+
+/// Idempotency record held by [`InMemoryIdempotencyService`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredIdempotency {
+    /// Changes whenever the record is claimed or stored
+    pub version: String,
+    pub body_hash: String,
+    /// `None` while the claiming request is still in progress
+    pub response: Option<String>,
+    pub stored_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// In-memory idempotency service for tests and local development.
+/// Stored responses never expire; a process restart clears them. An
+/// in-progress marker older than the lock timeout is taken over, as the
+/// S3 and filesystem services do.
+pub struct InMemoryIdempotencyService {
+    records: Mutex<HashMap<String, StoredIdempotency>>,
+    lock_timeout: chrono::Duration,
+}
+
+impl Default for InMemoryIdempotencyService {
+    fn default() -> Self {
+        Self::with_lock_timeout(chrono::Duration::seconds(60))
+    }
+}
+
+impl InMemoryIdempotencyService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_lock_timeout(lock_timeout: chrono::Duration) -> Self {
+        Self {
+            records: Mutex::new(HashMap::new()),
+            lock_timeout,
+        }
+    }
+
+    /// Current record for a key, if any
+    pub fn record(&self, key: &str) -> Option<StoredIdempotency> {
+        inspect(&self.records).get(key).cloned()
+    }
+
+    fn is_stale(&self, record: &StoredIdempotency) -> bool {
+        record.response.is_none() && record.stored_at + self.lock_timeout <= chrono::Utc::now()
+    }
+}
+
+#[async_trait]
+impl IdempotencyService for InMemoryIdempotencyService {
+    async fn claim_idempotency(&self, key: &str, body_hash: &str) -> Result<IdempotencyClaim> {
+        let mut records = lock(&self.records)?;
+
+        match records.get(key) {
+            Some(record) if self.is_stale(record) => {
+                tracing::warn!("Recovering stale idempotency marker {}", key);
+            }
+            None => {}
+            Some(record) if record.body_hash != body_hash => {
+                return Err(DomainError::Conflict(
+                    "Idempotency-Key was already used with a different request body".to_string(),
+                ))
+            }
+            Some(StoredIdempotency {
+                response: Some(response),
+                ..
+            }) => return Ok(IdempotencyClaim::Replay(response.clone())),
+            Some(_) => {
+                return Err(DomainError::Conflict(
+                    "A request with this Idempotency-Key is already in progress".to_string(),
+                ))
+            }
+        }
+
+        let version = uuid::Uuid::new_v4().to_string();
+        records.insert(
+            key.to_string(),
+            StoredIdempotency {
+                version: version.clone(),
+                body_hash: body_hash.to_string(),
+                response: None,
+                stored_at: chrono::Utc::now(),
+            },
+        );
+        Ok(IdempotencyClaim::Acquired(version))
+    }
+
+    async fn store_idempotency(
+        &self,
+        key: &str,
+        claim: &str,
+        body_hash: &str,
+        response: &str,
+    ) -> Result<()> {
+        let mut records = lock(&self.records)?;
+        match records.get_mut(key) {
+            Some(record) if record.version == claim => {
+                *record = StoredIdempotency {
+                    version: uuid::Uuid::new_v4().to_string(),
+                    body_hash: body_hash.to_string(),
+                    response: Some(response.to_string()),
+                    stored_at: chrono::Utc::now(),
+                };
+                Ok(())
+            }
+            _ => Err(DomainError::Conflict(format!(
+                "Idempotency claim on {} was lost",
+                key
+            ))),
+        }
+    }
+
+    async fn release_idempotency(&self, key: &str, claim: &str) -> Result<()> {
+        let mut records = lock(&self.records)?;
+        if records
+            .get(key)
+            .is_some_and(|record| record.version == claim)
+        {
+            records.remove(key);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use application::access::{AccessTokens, Principal};
+    use application::dto::{
+        ContactDto, CreateRfqRequest, ListEventsQuery, PostMessageRequest, RfqEventDto,
+        SubmitQuoteRequest,
+    };
+    use application::services::RfqService;
+    use std::sync::Arc;
+
+    const BUYER: &str = "buyer@example.com";
+    const CONTACT: &str = "sales@acme.example";
+
+    struct Fixture {
+        service: RfqService,
+        rfqs: Arc<InMemoryRfqRepository>,
+        emails: Arc<InMemoryEmailService>,
+        access_tokens: Arc<AccessTokens>,
+    }
+
+    fn fixture() -> Fixture {
+        let rfqs = Arc::new(InMemoryRfqRepository::new());
+        let emails = Arc::new(InMemoryEmailService::new());
+        let access_tokens = Arc::new(AccessTokens::with_random_secret(chrono::Duration::days(1)));
+        let manufacturers =
+            InMemoryManufacturerRepository::with_manufacturers(vec![ManufacturerProfile {
+                id: "mfg_acme".to_string(),
+                tenant_id: "t1".to_string(),
+                name: "Acme".to_string(),
+                description: None,
+                location: None,
+                categories: Vec::new(),
+                capabilities: None,
+                contact_email: Some(CONTACT.to_string()),
+                media: None,
+                offerings: None,
+                updated_at: chrono::Utc::now(),
+            }]);
+
+        Fixture {
+            service: RfqService::new(
+                rfqs.clone(),
+                Arc::new(manufacturers),
+                emails.clone(),
+                Arc::new(InMemoryIdempotencyService::new()),
+                Arc::new(InMemoryImageService::new()),
+                access_tokens.clone(),
+            ),
+            rfqs,
+            emails,
+            access_tokens,
+        }
+    }
+
+    fn new_rfq(tenant_id: &str) -> CreateRfqRequest {
+        CreateRfqRequest {
+            tenant_id: tenant_id.to_string(),
+            manufacturer_id: "mfg_acme".to_string(),
+            buyer: ContactDto {
+                email: BUYER.to_string(),
+                name: Some("Buyer".to_string()),
+            },
+            subject: "Brackets".to_string(),
+            body: "Can you quote 500 brackets?".to_string(),
+            attachments: None,
+        }
+    }
+
+    fn bodies(events: &[RfqEventDto]) -> Vec<(&str, &str)> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                RfqEventDto::Message { by, body, .. } => Some((by.as_str(), body.as_str())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn rfq_thread_round_trip() {
+        let f = fixture();
+        let created = f
+            .service
+            .create_rfq(&Principal::Anonymous, new_rfq("t1"), None)
+            .await
+            .unwrap();
+        let buyer: Principal = f
+            .access_tokens
+            .verify(created.access_token.as_deref().unwrap())
+            .unwrap()
+            .into();
+        assert_eq!(f.rfqs.rfqs().len(), 1);
+        assert!(matches!(
+            f.emails.sent_emails().as_slice(),
+            [SentNotification::RfqCreated { rfq }] if rfq.id == created.id
+        ));
+
+        let maker: Principal = f
+            .access_tokens
+            .verify(
+                &f.access_tokens
+                    .issue(&created.id, ParticipantRole::Manufacturer, CONTACT),
+            )
+            .unwrap()
+            .into();
+        f.service
+            .post_message(
+                &created.id,
+                &maker,
+                PostMessageRequest {
+                    body: "Yes, 3 weeks.".to_string(),
+                    attachments: None,
+                },
+                None,
+            )
+            .await
+            .unwrap();
+
+        let quote: SubmitQuoteRequest = serde_json::from_value(serde_json::json!({
+            "line_items": [{
+                "part": "BRKT-104",
+                "quantity": { "value": "500", "unit": "pcs" },
+                "unit_price": { "amount": "12.5", "currency": "USD" }
+            }],
+            "valid_until": "2099-12-31"
+        }))
+        .unwrap();
+        f.service
+            .submit_quote(&created.id, &maker, quote, None)
+            .await
+            .unwrap();
+
+        let page = f
+            .service
+            .list_events(&created.id, &buyer, ListEventsQuery::default())
+            .await
+            .unwrap();
+        let quoted = page.items.iter().find_map(|event| match event {
+            RfqEventDto::Quote { line_items, .. } => Some(line_items),
+            _ => None,
+        });
+        assert_eq!(
+            serde_json::to_value(quoted.unwrap()).unwrap(),
+            serde_json::json!([{
+                "part": "BRKT-104",
+                "quantity": { "value": "500", "unit": "pcs" },
+                "unit_price": { "amount": "12.50", "currency": "USD" }
+            }])
+        );
+        assert_eq!(
+            bodies(&page.items),
+            vec![
+                ("buyer", "Can you quote 500 brackets?"),
+                ("manufacturer", "Yes, 3 weeks."),
+            ]
+        );
+        assert!(!page.has_more);
+        assert_eq!(f.rfqs.events_for(&created.id).len(), page.items.len());
+    }
+
+    #[tokio::test]
+    async fn idempotent_create_replays_without_a_second_rfq() {
+        let f = fixture();
+        let first = f
+            .service
+            .create_rfq(&Principal::Anonymous, new_rfq("t1"), Some("key-1"))
+            .await
+            .unwrap();
+        let replay = f
+            .service
+            .create_rfq(&Principal::Anonymous, new_rfq("t1"), Some("key-1"))
+            .await
+            .unwrap();
+
+        assert_eq!(replay.id, first.id);
+        assert!(first.access_token.is_some());
+        assert_eq!(replay.access_token, None);
+        assert_eq!(f.rfqs.rfqs().len(), 1);
+        assert_eq!(f.emails.sent_emails().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn outsiders_cannot_read_or_post() {
+        let f = fixture();
+        let created = f
+            .service
+            .create_rfq(&Principal::Anonymous, new_rfq("t1"), None)
+            .await
+            .unwrap();
+        let other = Principal::Buyer {
+            email: "mallory@example.com".to_string(),
+            resource_id: created.id.clone(),
+        };
+
+        assert!(matches!(
+            f.service
+                .list_events(&created.id, &other, ListEventsQuery::default())
+                .await,
+            Err(DomainError::Forbidden(_))
+        ));
+        assert!(matches!(
+            f.service
+                .list_events(
+                    &created.id,
+                    &Principal::Anonymous,
+                    ListEventsQuery::default()
+                )
+                .await,
+            Err(DomainError::Unauthorized(_))
+        ));
+        assert!(matches!(
+            f.service
+                .post_message(
+                    &created.id,
+                    &other,
+                    PostMessageRequest {
+                        body: "hello".to_string(),
+                        attachments: None,
+                    },
+                    None,
+                )
+                .await,
+            Err(DomainError::Forbidden(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn rfq_for_another_tenants_manufacturer_is_rejected() {
+        let f = fixture();
+        let result = f
+            .service
+            .create_rfq(&Principal::Anonymous, new_rfq("t2"), None)
+            .await;
+
+        assert!(result.is_err());
+        assert!(f.rfqs.rfqs().is_empty());
+        assert!(f.emails.sent_emails().is_empty());
+    }
+}
@@ -7,256 +7,864 @@ use domain::entities::*;
 use domain::error::{DomainError, Result};
 use domain::events::*;
 use domain::value_objects::*;
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
 use sha2::Digest;
+use std::collections::{BTreeMap, BTreeSet};
 use std::sync::Arc;
 
 use crate::config::Config;
+use crate::s3_store::{PutOptions, S3ObjectError, S3ObjectStore};
 
 /// S3-based RFQ repository (simplified for MVP)
 pub struct S3RfqRepository {
-    client: S3Client,
+    store: S3ObjectStore,
     config: Arc<Config>,
 }
 
 impl S3RfqRepository {
     pub fn new(client: S3Client, config: Arc<Config>) -> Self {
-        Self { client, config }
+        Self {
+            store: S3ObjectStore::new(client, &config),
+            config,
+        }
     }
 }
 
-#[async_trait]
-impl RfqRepository for S3RfqRepository {
-    async fn save_rfq_meta(&self, rfq: &RfqMeta) -> Result<()> {
-        let key = format!("rfq/{}/meta.json", rfq.id);
-        let body = serde_json::to_string(rfq)
-            .map_err(|e| DomainError::Internal(format!("Failed to serialize RFQ meta: {}", e)))?;
-
-        let _result = self
-            .client
-            .put_object()
-            .bucket(&self.config.private_bucket)
-            .key(&key)
-            .body(body.into_bytes().into())
-            .content_type("application/json")
-            .send()
+impl S3RfqRepository {
+    /// Fetch one listed event. Failures are logged and skipped so one bad
+    /// object does not hide the rest of the page.
+    async fn fetch_event(&self, prefix: &str, position: &str) -> Option<RfqEvent> {
+        let key = format!("{}{}.json", prefix, position);
+        match self
+            .store
+            .get_json::<RfqEvent>(&self.config.private_bucket, &key)
             .await
-            .map_err(|e| DomainError::Internal(format!("Failed to save RFQ meta: {}", e)))?;
+        {
+            Ok(event) => event,
+            Err(e @ S3ObjectError::Serialization { .. }) => {
+                // Log the error but continue processing other events
+                tracing::warn!("Failed to deserialize event {}: {}", key, e);
+                None
+            }
+            Err(e) => {
+                // Log the error but continue processing other events
+                tracing::warn!("Failed to fetch event {}: {}", key, e);
+                None
+            }
+        }
+    }
 
-        Ok(())
+    /// Positions of the individual event objects between the given bounds
+    async fn list_event_positions(
+        &self,
+        prefix: &str,
+        start_after: Option<&str>,
+        before: Option<&str>,
+        max_keys: Option<usize>,
+    ) -> Result<Vec<String>> {
+        let bound = |position: &str| format!("{}{}", prefix, position);
+        let keys = self
+            .store
+            .list_keys(
+                &self.config.private_bucket,
+                prefix,
+                start_after.map(bound).as_deref(),
+                before.map(bound).as_deref(),
+                max_keys,
+            )
+            .await?;
+
+        Ok(keys
+            .iter()
+            .filter_map(|key| key.strip_prefix(prefix)?.strip_suffix(".json"))
+            .map(str::to_string)
+            .collect())
     }
 
-    async fn get_rfq_meta(&self, id: &RfqId) -> Result<Option<RfqMeta>> {
-        let key = format!("rfq/{}/meta.json", id.as_str());
+    /// Read every event in a segment. Unlike single events a segment that
+    /// cannot be read fails the request, since it may hold a whole page.
+    async fn read_segment(&self, rfq_id: &RfqId, segment: &EventSegment) -> Result<Vec<RfqEvent>> {
+        let key = format!("rfq/{}/{}", rfq_id.as_str(), segment.key);
+        let object = self
+            .store
+            .get_bytes(&self.config.private_bucket, &key)
+            .await?
+            .ok_or_else(|| DomainError::Internal(format!("Event segment {} is missing", key)))?;
+
+        object
+            .value
+            .split(|byte| *byte == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                serde_json::from_slice(line).map_err(|e| {
+                    DomainError::Internal(format!("Failed to deserialize segment {}: {}", key, e))
+                })
+            })
+            .collect()
+    }
 
-        match self
-            .client
-            .get_object()
-            .bucket(&self.config.private_bucket)
-            .key(&key)
-            .send()
+    /// All RFQ ids with objects in the private bucket
+    pub async fn list_rfq_ids(&self) -> Result<Vec<RfqId>> {
+        let prefixes = self
+            .store
+            .list_common_prefixes(&self.config.private_bucket, "rfq/")
+            .await?;
+
+        Ok(prefixes
+            .iter()
+            .filter_map(|prefix| prefix.strip_prefix("rfq/")?.strip_suffix('/'))
+            .filter_map(|id| RfqId::new(id.to_string()).ok())
+            .collect())
+    }
+
+    /// Fold an RFQ's older event objects into one new JSONL segment.
+    ///
+    /// The segment is written under a fresh name before the index references
+    /// it, and event objects are only deleted once the index update has
+    /// succeeded, and only if the segment holds them. A crash at any point
+    /// therefore leaves either an unreferenced segment (harmless) or event
+    /// objects also held by a segment, which reads de-duplicate and the next
+    /// run deletes. Objects below the last segment that no segment holds,
+    /// e.g. written while a run was in progress, are left alone.
+    pub async fn compact_events(
+        &self,
+        rfq_id: &RfqId,
+        policy: &CompactionPolicy,
+    ) -> Result<CompactionReport> {
+        let prefix = format!("rfq/{}/events/", rfq_id.as_str());
+        let mut report = CompactionReport::default();
+
+        let Some(index) = self.get_rfq_index(rfq_id).await? else {
+            return Ok(report);
+        };
+        let compacted_until = index
+            .value
+            .segments
+            .last()
+            .map(|segment| segment.last_position.clone());
+
+        let positions = self.list_event_positions(&prefix, None, None, None).await?;
+        let (covered, pending): (Vec<String>, Vec<String>) =
+            positions.into_iter().partition(|position| {
+                compacted_until
+                    .as_deref()
+                    .is_some_and(|until| position.as_str() <= until)
+            });
+        let mut obsolete = self
+            .held_by_segments(rfq_id, &index.value.segments, covered)
+            .await?;
+
+        let cutoff = RfqEvent::sort_key_prefix(policy.older_than);
+        let eligible: Vec<String> = pending
+            .into_iter()
+            .take_while(|position| *position < cutoff)
+            .take(policy.max_events)
+            .collect();
+
+        if !eligible.is_empty() && eligible.len() >= policy.min_events {
+            let segment = self
+                .write_segment(rfq_id, &prefix, &index.value, &eligible)
+                .await?;
+
+            if self
+                .record_segment(rfq_id, &segment, compacted_until.as_deref())
+                .await?
+            {
+                // `write_segment` fails unless every eligible event made it in
+                obsolete.extend(eligible);
+                report.segment = Some(segment);
+            } else {
+                // Another run compacted first; our segment was never referenced
+                let key = format!("rfq/{}/{}", rfq_id.as_str(), segment.key);
+                if let Err(e) = self.store.delete(&self.config.private_bucket, &key).await {
+                    tracing::warn!("Failed to delete unreferenced segment {}: {}", key, e);
+                }
+            }
+        }
+
+        report.deleted_events = self.delete_event_objects(&prefix, obsolete).await;
+        Ok(report)
+    }
+
+    /// Those of `positions` whose events are held by one of `segments`.
+    /// Only the segments whose range covers a position are read.
+    async fn held_by_segments(
+        &self,
+        rfq_id: &RfqId,
+        segments: &[EventSegment],
+        positions: Vec<String>,
+    ) -> Result<Vec<String>> {
+        let mut held = BTreeSet::new();
+        for segment in segments.iter().filter(|segment| {
+            positions.iter().any(|position| {
+                segment.first_position.as_str() <= position.as_str()
+                    && position.as_str() <= segment.last_position.as_str()
+            })
+        }) {
+            held.extend(
+                self.read_segment(rfq_id, segment)
+                    .await?
+                    .iter()
+                    .map(RfqEvent::sort_key),
+            );
+        }
+
+        Ok(positions
+            .into_iter()
+            .filter(|position| held.contains(position))
+            .collect())
+    }
+
+    /// Write the given events as a new immutable segment object
+    async fn write_segment(
+        &self,
+        rfq_id: &RfqId,
+        prefix: &str,
+        index: &RfqIndex,
+        positions: &[String],
+    ) -> Result<EventSegment> {
+        // Any unreadable event aborts the run rather than being dropped
+        let events: Vec<RfqEvent> = stream::iter(positions.to_vec())
+            .map(|position| async move {
+                let key = format!("{}{}.json", prefix, position);
+                self.store
+                    .get_json::<RfqEvent>(&self.config.private_bucket, &key)
+                    .await?
+                    .ok_or_else(|| DomainError::Internal(format!("Event {} disappeared", key)))
+            })
+            .buffered(self.config.event_fetch_concurrency.max(1))
+            .collect::<Vec<Result<RfqEvent>>>()
             .await
-        {
-            Ok(response) => {
-                let body = response
-                    .body
-                    .collect()
-                    .await
-                    .map_err(|e| {
-                        DomainError::Internal(format!("Failed to read RFQ meta body: {}", e))
-                    })?
-                    .into_bytes();
-
-                let rfq_meta: RfqMeta = serde_json::from_slice(&body).map_err(|e| {
-                    DomainError::Internal(format!("Failed to deserialize RFQ meta: {}", e))
-                })?;
-
-                Ok(Some(rfq_meta))
+            .into_iter()
+            .collect::<Result<_>>()?;
+
+        let mut body = Vec::new();
+        for event in &events {
+            serde_json::to_writer(&mut body, event).map_err(|e| {
+                DomainError::Internal(format!("Failed to serialize event {}: {}", event.id(), e))
+            })?;
+            body.push(b'\n');
+        }
+
+        let nonce = uuid::Uuid::new_v4().simple().to_string();
+        let segment = EventSegment {
+            key: format!(
+                "events-{:05}-{}.jsonl",
+                index.segments.len() + 1,
+                &nonce[..8]
+            ),
+            first_position: positions[0].clone(),
+            last_position: positions[positions.len() - 1].clone(),
+            count: events.len() as u32,
+        };
+
+        let key = format!("rfq/{}/{}", rfq_id.as_str(), segment.key);
+        self.store
+            .put_bytes(
+                &self.config.private_bucket,
+                &key,
+                body,
+                "application/x-ndjson",
+                &PutOptions::conditional(None),
+            )
+            .await?;
+
+        Ok(segment)
+    }
+
+    /// Append a segment to the index, provided nothing else was compacted
+    /// since `compacted_until` was read. Returns whether it was recorded.
+    async fn record_segment(
+        &self,
+        rfq_id: &RfqId,
+        segment: &EventSegment,
+        compacted_until: Option<&str>,
+    ) -> Result<bool> {
+        for _ in 0..MAX_INDEX_ATTEMPTS {
+            let Some(mut index) = self.get_rfq_index(rfq_id).await? else {
+                return Ok(false);
+            };
+            let current = index
+                .value
+                .segments
+                .last()
+                .map(|segment| segment.last_position.as_str());
+            if current != compacted_until {
+                return Ok(false);
             }
-            Err(e) => {
-                // Check if it's a NoSuchKey error, which is expected for non-existent RFQs
-                if e.to_string().contains("NoSuchKey") {
-                    Ok(None)
-                } else {
-                    Err(DomainError::Internal(format!(
-                        "Failed to fetch RFQ meta: {}",
-                        e
-                    )))
-                }
+
+            index.value.segments.push(segment.clone());
+            match self
+                .save_rfq_index(rfq_id, &index.value, Some(&index.version))
+                .await
+            {
+                Ok(_) => return Ok(true),
+                // A message bumped the index; re-read and try again
+                Err(DomainError::Conflict(_)) => continue,
+                Err(e) => return Err(e),
             }
         }
+
+        Err(DomainError::Conflict(format!(
+            "Index of RFQ {} kept changing during compaction",
+            rfq_id.as_str()
+        )))
+    }
+
+    /// Best-effort removal of event objects now held by a segment
+    async fn delete_event_objects(&self, prefix: &str, positions: Vec<String>) -> usize {
+        stream::iter(positions)
+            .map(|position| async move {
+                let key = format!("{}{}.json", prefix, position);
+                match self.store.delete(&self.config.private_bucket, &key).await {
+                    Ok(()) => true,
+                    Err(e) => {
+                        tracing::warn!("Failed to delete compacted event {}: {}", key, e);
+                        false
+                    }
+                }
+            })
+            .buffer_unordered(self.config.event_fetch_concurrency.max(1))
+            .filter(|deleted| std::future::ready(*deleted))
+            .count()
+            .await
+    }
+}
+
+impl S3RfqRepository {
+    /// Up to `max` inbox markers past `after` in the order read, as
+    /// (position, key). Each read state and status is its own listing; newest
+    /// first they are read from `after` on, oldest first S3 cannot list
+    /// backwards so everything older than `after` is listed.
+    async fn list_inbox_markers(
+        &self,
+        manufacturer_id: &ManufacturerId,
+        query: &InboxPageQuery,
+        after: Option<&str>,
+        max: usize,
+    ) -> Result<Vec<(String, String)>> {
+        let read_states = match query.unread {
+            Some(unread) => vec![unread],
+            None => vec![true, false],
+        };
+        let statuses = if query.statuses.is_empty() {
+            &INBOX_STATUSES[..]
+        } else {
+            &query.statuses[..]
+        };
+        let prefixes: Vec<String> = read_states
+            .into_iter()
+            .flat_map(|unread| {
+                statuses
+                    .iter()
+                    .map(move |status| inbox_marker_prefix(manufacturer_id, unread, status))
+            })
+            .collect();
+
+        let listings = stream::iter(prefixes)
+            .map(|prefix| async move {
+                let bound = after.map(|after| format!("{}{}", prefix, after));
+                let keys = if query.ascending {
+                    self.store
+                        .list_keys(
+                            &self.config.private_bucket,
+                            &prefix,
+                            None,
+                            bound.as_deref(),
+                            None,
+                        )
+                        .await?
+                } else {
+                    self.store
+                        .list_keys(
+                            &self.config.private_bucket,
+                            &prefix,
+                            bound.as_deref(),
+                            None,
+                            Some(max),
+                        )
+                        .await?
+                };
+                Ok::<_, DomainError>(
+                    keys.into_iter()
+                        .filter_map(|key| Some((key.strip_prefix(&prefix)?.to_string(), key)))
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .buffer_unordered(self.config.event_fetch_concurrency.max(1))
+            .collect::<Vec<_>>()
+            .await;
+
+        let mut markers = Vec::new();
+        for listing in listings {
+            markers.extend(listing?);
+        }
+        markers.sort();
+        if query.ascending {
+            markers.reverse();
+        }
+        markers.truncate(max);
+        Ok(markers)
+    }
+
+    /// Best-effort removal of a marker that no longer matches its entry
+    async fn remove_inbox_marker(&self, key: &str) {
+        if let Err(e) = self.store.delete(&self.config.private_bucket, key).await {
+            tracing::warn!("Failed to delete stale inbox marker {}: {}", key, e);
+        }
+    }
+
+    /// Write the listing marker of every stored inbox entry, for entries
+    /// saved before inboxes were listed by marker. Returns how many were written.
+    pub async fn index_inbox(&self) -> Result<usize> {
+        let keys = self
+            .store
+            .list_keys(&self.config.private_bucket, "mfg/", None, None, None)
+            .await?;
+
+        let mut written = 0;
+        for key in &keys {
+            let Some(manufacturer_id) = key
+                .strip_prefix("mfg/")
+                .and_then(|rest| rest.split_once("/rfqs/"))
+                .map(|(manufacturer_id, _)| manufacturer_id)
+            else {
+                continue;
+            };
+            let manufacturer_id = ManufacturerId::new(manufacturer_id.to_string())?;
+            let Some(entry) = self
+                .store
+                .get_json::<RfqInboxEntry>(&self.config.private_bucket, key)
+                .await?
+            else {
+                continue;
+            };
+            self.store
+                .put_json(
+                    &self.config.private_bucket,
+                    &inbox_marker_key(&manufacturer_id, &entry),
+                    &entry.rfq_id,
+                    &PutOptions::default(),
+                )
+                .await?;
+            written += 1;
+        }
+        Ok(written)
+    }
+}
+
+/// Attempts at appending a segment while messages keep updating the index
+const MAX_INDEX_ATTEMPTS: u32 = 8;
+
+/// Limits for one compaction run over an RFQ
+#[derive(Debug, Clone)]
+pub struct CompactionPolicy {
+    /// Only events strictly older than this are compacted, leaving recent
+    /// activity as individual objects
+    pub older_than: DateTime<Utc>,
+    /// Skip the RFQ when fewer events than this are eligible
+    pub min_events: usize,
+    /// Upper bound on the number of events in one segment
+    pub max_events: usize,
+}
+
+/// Outcome of compacting one RFQ
+#[derive(Debug, Clone, Default)]
+pub struct CompactionReport {
+    /// Segment written and recorded in the index, if any
+    pub segment: Option<EventSegment>,
+    /// Event objects removed because a segment holds them
+    pub deleted_events: usize,
+}
+
+#[async_trait]
+impl RfqRepository for S3RfqRepository {
+    async fn save_rfq_meta(&self, rfq: &RfqMeta, expected_version: Option<&str>) -> Result<String> {
+        let key = format!("rfq/{}/meta.json", rfq.id);
+        Ok(self
+            .store
+            .put_json(
+                &self.config.private_bucket,
+                &key,
+                rfq,
+                &PutOptions::conditional(expected_version),
+            )
+            .await?)
+    }
+
+    async fn get_rfq_meta(&self, id: &RfqId) -> Result<Option<Versioned<RfqMeta>>> {
+        let key = format!("rfq/{}/meta.json", id.as_str());
+        Ok(self
+            .store
+            .get_json_versioned(&self.config.private_bucket, &key)
+            .await?)
     }
 
-    async fn save_rfq_index(&self, rfq_id: &RfqId, index: &RfqIndex) -> Result<()> {
+    async fn save_rfq_index(
+        &self,
+        rfq_id: &RfqId,
+        index: &RfqIndex,
+        expected_version: Option<&str>,
+    ) -> Result<String> {
         let key = format!("rfq/{}/index.json", rfq_id.as_str());
-        let body = serde_json::to_string(index)
-            .map_err(|e| DomainError::Internal(format!("Failed to serialize RFQ index: {}", e)))?;
+        Ok(self
+            .store
+            .put_json(
+                &self.config.private_bucket,
+                &key,
+                index,
+                &PutOptions::conditional(expected_version),
+            )
+            .await?)
+    }
 
-        let _result = self
-            .client
-            .put_object()
-            .bucket(&self.config.private_bucket)
-            .key(&key)
-            .body(body.into_bytes().into())
-            .content_type("application/json")
-            .send()
-            .await
-            .map_err(|e| DomainError::Internal(format!("Failed to save RFQ index: {}", e)))?;
+    async fn get_rfq_index(&self, id: &RfqId) -> Result<Option<Versioned<RfqIndex>>> {
+        let key = format!("rfq/{}/index.json", id.as_str());
+        Ok(self
+            .store
+            .get_json_versioned(&self.config.private_bucket, &key)
+            .await?)
+    }
 
+    async fn save_rfq_event(&self, event: &RfqEvent) -> Result<()> {
+        let key = format!("rfq/{}/events/{}.json", event.rfq_id(), event.sort_key());
+        self.store
+            .put_json(
+                &self.config.private_bucket,
+                &key,
+                event,
+                &PutOptions::default(),
+            )
+            .await?;
         Ok(())
     }
 
-    async fn get_rfq_index(&self, id: &RfqId) -> Result<Option<RfqIndex>> {
-        let key = format!("rfq/{}/index.json", id.as_str());
+    async fn list_rfq_events(&self, rfq_id: &RfqId, query: &EventQuery) -> Result<EventPage> {
+        let prefix = format!("rfq/{}/events/", rfq_id.as_str());
+        let limit = query.limit as usize;
+        let start_after = query.start_after();
+
+        // Compacted events live in segments recorded in the index; everything
+        // after the last segment is still one object per event. Objects below
+        // it are merged in too: a compaction may not have deleted them yet, or
+        // they were written while it ran and no segment holds them.
+        let segments = self
+            .get_rfq_index(rfq_id)
+            .await?
+            .map(|index| index.value.segments)
+            .unwrap_or_default();
+        let compacted_until = segments.last().map(|segment| segment.last_position.clone());
+        let after_segments = |position: &String| {
+            compacted_until
+                .as_deref()
+                .is_none_or(|until| position.as_str() > until)
+        };
+        let overlapping: Vec<&EventSegment> = segments
+            .iter()
+            .filter(|segment| {
+                start_after
+                    .as_deref()
+                    .is_none_or(|start| segment.last_position.as_str() > start)
+                    && query
+                        .before
+                        .as_deref()
+                        .is_none_or(|before| segment.first_position.as_str() < before)
+            })
+            .collect();
+
+        let mut compacted: BTreeMap<String, RfqEvent> = BTreeMap::new();
+        let matching = |compacted: &BTreeMap<String, RfqEvent>| {
+            compacted.keys().filter(|p| query.contains(p)).count()
+        };
 
-        match self
-            .client
-            .get_object()
-            .bucket(&self.config.private_bucket)
-            .key(&key)
-            .send()
-            .await
-        {
-            Ok(response) => {
-                let body = response
-                    .body
-                    .collect()
-                    .await
-                    .map_err(|e| {
-                        DomainError::Internal(format!("Failed to read RFQ index body: {}", e))
-                    })?
-                    .into_bytes();
-
-                let rfq_index: RfqIndex = serde_json::from_slice(&body).map_err(|e| {
-                    DomainError::Internal(format!("Failed to deserialize RFQ index: {}", e))
-                })?;
-
-                Ok(Some(rfq_index))
+        let tail = match query.before.as_deref() {
+            // Forwards: oldest segments until the page is full, and the first
+            // objects after the bound. The page plus the bound key and one more
+            // tells whether there is more.
+            None => {
+                for segment in overlapping {
+                    if matching(&compacted) > limit {
+                        break;
+                    }
+                    for event in self.read_segment(rfq_id, segment).await? {
+                        compacted.insert(event.sort_key(), event);
+                    }
+                }
+                self.list_event_positions(&prefix, start_after.as_deref(), None, Some(limit + 2))
+                    .await?
             }
-            Err(e) => {
-                if e.to_string().contains("NoSuchKey") {
-                    Ok(None)
-                } else {
-                    Err(DomainError::Internal(format!(
-                        "Failed to fetch RFQ index: {}",
-                        e
-                    )))
+            // Backwards: every object up to `before`, then newest segments
+            // until they and the objects after them fill the page
+            Some(before) => {
+                let tail = self
+                    .list_event_positions(&prefix, start_after.as_deref(), Some(before), None)
+                    .await?;
+                let in_tail = tail
+                    .iter()
+                    .filter(|p| after_segments(p) && query.contains(p))
+                    .count();
+                for segment in overlapping.into_iter().rev() {
+                    if in_tail + matching(&compacted) > limit {
+                        break;
+                    }
+                    for event in self.read_segment(rfq_id, segment).await? {
+                        compacted.insert(event.sort_key(), event);
+                    }
                 }
+                tail
             }
-        }
+        };
+
+        let positions = compacted
+            .keys()
+            .cloned()
+            .chain(tail)
+            .collect::<BTreeSet<String>>()
+            .into_iter()
+            .collect();
+        let (positions, has_more) = query.select(positions);
+
+        // Take compacted events from memory and fetch the rest concurrently;
+        // `buffered` yields them in listing order
+        let prefix = prefix.as_str();
+        let compacted = &compacted;
+        let events: Vec<RfqEvent> = stream::iter(positions.clone())
+            .map(|position| async move {
+                match compacted.get(&position) {
+                    Some(event) => Some(event.clone()),
+                    None => self.fetch_event(prefix, &position).await,
+                }
+            })
+            .buffered(self.config.event_fetch_concurrency.max(1))
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .flatten()
+            .collect();
+
+        Ok(EventPage::new(query, &positions, events, has_more))
     }
 
-    async fn save_rfq_event(&self, event: &RfqEvent) -> Result<()> {
-        let ts_str = event.timestamp().format("%Y-%m-%dT%H-%M-%SZ").to_string();
+    async fn save_attachment_access(&self, access: &AttachmentAccess) -> Result<()> {
         let key = format!(
-            "rfq/{}/events/{}-{}.json",
-            event.rfq_id(),
-            ts_str,
-            event.id()
+            "rfq/{}/audit/downloads/{}-{}.json",
+            access.rfq_id,
+            RfqEvent::sort_key_prefix(access.issued_at),
+            access.id
         );
-        let body = serde_json::to_string(event)
-            .map_err(|e| DomainError::Internal(format!("Failed to serialize RFQ event: {}", e)))?;
-
-        let _result = self
-            .client
-            .put_object()
-            .bucket(&self.config.private_bucket)
-            .key(&key)
-            .body(body.into_bytes().into())
-            .content_type("application/json")
-            .send()
-            .await
-            .map_err(|e| DomainError::Internal(format!("Failed to save RFQ event: {}", e)))?;
+        self.store
+            .put_json(
+                &self.config.private_bucket,
+                &key,
+                access,
+                &PutOptions::default(),
+            )
+            .await?;
+        Ok(())
+    }
 
+    async fn save_campaign(&self, campaign: &RfqCampaign) -> Result<()> {
+        let key = format!("campaign/{}.json", campaign.id);
+        self.store
+            .put_json(
+                &self.config.private_bucket,
+                &key,
+                campaign,
+                &PutOptions::default(),
+            )
+            .await?;
         Ok(())
     }
 
-    async fn list_rfq_events(
+    async fn get_campaign(&self, id: &CampaignId) -> Result<Option<RfqCampaign>> {
+        let key = format!("campaign/{}.json", id.as_str());
+        Ok(self
+            .store
+            .get_json(&self.config.private_bucket, &key)
+            .await?)
+    }
+
+    async fn save_inbox_entry(
+        &self,
+        manufacturer_id: &ManufacturerId,
+        entry: &RfqInboxEntry,
+        expected_version: Option<&str>,
+    ) -> Result<String> {
+        let key = inbox_entry_key(manufacturer_id, &entry.rfq_id);
+        let previous = self
+            .store
+            .get_json::<RfqInboxEntry>(&self.config.private_bucket, &key)
+            .await?;
+        let version = self
+            .store
+            .put_json(
+                &self.config.private_bucket,
+                &key,
+                entry,
+                &PutOptions::conditional(expected_version),
+            )
+            .await?;
+
+        // The marker follows the entry. One left behind by a crash or a lost
+        // race no longer matches its entry and is dropped when listed.
+        let marker = inbox_marker_key(manufacturer_id, entry);
+        self.store
+            .put_json(
+                &self.config.private_bucket,
+                &marker,
+                &entry.rfq_id,
+                &PutOptions::default(),
+            )
+            .await?;
+        if let Some(stale) = previous
+            .map(|previous| inbox_marker_key(manufacturer_id, &previous))
+            .filter(|previous| *previous != marker)
+        {
+            self.remove_inbox_marker(&stale).await;
+        }
+
+        Ok(version)
+    }
+
+    async fn get_inbox_entry(
         &self,
+        manufacturer_id: &ManufacturerId,
         rfq_id: &RfqId,
-        since: Option<DateTime<Utc>>,
-        limit: Option<u32>,
-    ) -> Result<Vec<RfqEvent>> {
-        let prefix = format!("rfq/{}/events/", rfq_id.as_str());
-        let limit = limit.unwrap_or(100).min(1000); // Reasonable upper bound
+    ) -> Result<Option<Versioned<RfqInboxEntry>>> {
+        let key = inbox_entry_key(manufacturer_id, rfq_id.as_str());
+        Ok(self
+            .store
+            .get_json_versioned(&self.config.private_bucket, &key)
+            .await?)
+    }
 
-        let list_response = self
-            .client
-            .list_objects_v2()
-            .bucket(&self.config.private_bucket)
-            .prefix(&prefix)
-            .max_keys(limit as i32)
-            .send()
-            .await
-            .map_err(|e| DomainError::Internal(format!("Failed to list RFQ events: {}", e)))?;
-
-        let mut events = Vec::new();
-
-        if let Some(objects) = list_response.contents {
-            for object in objects {
-                if let Some(key) = object.key {
-                    // Try to get the object
-                    match self
-                        .client
-                        .get_object()
-                        .bucket(&self.config.private_bucket)
-                        .key(&key)
-                        .send()
-                        .await
-                    {
-                        Ok(response) => {
-                            let body = response
-                                .body
-                                .collect()
-                                .await
-                                .map_err(|e| {
-                                    DomainError::Internal(format!(
-                                        "Failed to read event body: {}",
-                                        e
-                                    ))
-                                })?
-                                .into_bytes();
-
-                            match serde_json::from_slice::<RfqEvent>(&body) {
-                                Ok(event) => {
-                                    // Filter by since timestamp if provided
-                                    if let Some(since_time) = since {
-                                        if event.timestamp() < since_time {
-                                            continue;
-                                        }
-                                    }
-                                    events.push(event);
-                                }
-                                Err(e) => {
-                                    // Log the error but continue processing other events
-                                    tracing::warn!("Failed to deserialize event {}: {}", key, e);
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            // Log the error but continue processing other events
-                            tracing::warn!("Failed to fetch event {}: {}", key, e);
-                        }
+    async fn list_inbox(
+        &self,
+        manufacturer_id: &ManufacturerId,
+        query: &InboxPageQuery,
+    ) -> Result<InboxPage> {
+        let limit = query.limit as usize;
+        let mut after = query.after.clone();
+        let mut entries = Vec::new();
+
+        // Stale markers are skipped, so keep listing until the page is full
+        loop {
+            let wanted = limit + 1 - entries.len();
+            let markers = self
+                .list_inbox_markers(manufacturer_id, query, after.as_deref(), wanted)
+                .await?;
+            let exhausted = markers.len() < wanted;
+
+            let fetched = stream::iter(markers)
+                .map(|(position, key)| async move {
+                    let rfq_id = position.split_once('-').map_or("", |(_, id)| id);
+                    let entry = self
+                        .store
+                        .get_json::<RfqInboxEntry>(
+                            &self.config.private_bucket,
+                            &inbox_entry_key(manufacturer_id, rfq_id),
+                        )
+                        .await;
+                    (position, key, entry)
+                })
+                .buffered(self.config.event_fetch_concurrency.max(1))
+                .collect::<Vec<_>>()
+                .await;
+
+            for (position, key, entry) in fetched {
+                if entries.len() == limit {
+                    return Ok(InboxPage {
+                        entries,
+                        has_more: true,
+                    });
+                }
+                after = Some(position);
+                match entry {
+                    Ok(Some(entry)) if inbox_marker_key(manufacturer_id, &entry) == key => {
+                        entries.push(entry)
                     }
+                    Ok(_) => self.remove_inbox_marker(&key).await,
+                    // One unreadable entry should not hide the rest of the inbox
+                    Err(e) => tracing::warn!("Failed to fetch inbox entry for {}: {}", key, e),
                 }
             }
-        }
 
-        // Sort by timestamp (events should be in order, but ensure it)
-        events.sort_by_key(|e| e.timestamp());
+            if exhausted {
+                return Ok(InboxPage {
+                    entries,
+                    has_more: false,
+                });
+            }
+        }
+    }
 
-        Ok(events)
+    async fn count_unread_inbox(&self, manufacturer_id: &ManufacturerId) -> Result<usize> {
+        let prefix = format!("mfg/{}/inbox/unread/", manufacturer_id.as_str());
+        let keys = self
+            .store
+            .list_keys(&self.config.private_bucket, &prefix, None, None, None)
+            .await?;
+
+        // A stale marker may still sit next to the current one
+        Ok(keys
+            .iter()
+            .filter_map(|key| key.rsplit('/').next()?.split_once('-'))
+            .map(|(_, rfq_id)| rfq_id)
+            .collect::<BTreeSet<_>>()
+            .len())
     }
 }
 
+/// Where an RFQ's inbox entry is kept
+fn inbox_entry_key(manufacturer_id: &ManufacturerId, rfq_id: &str) -> String {
+    format!("mfg/{}/rfqs/{}.json", manufacturer_id.as_str(), rfq_id)
+}
+
+/// Listing markers of a manufacturer's RFQs in one read state and status
+fn inbox_marker_prefix(
+    manufacturer_id: &ManufacturerId,
+    unread: bool,
+    status: &RfqStatus,
+) -> String {
+    format!(
+        "mfg/{}/inbox/{}/{}/",
+        manufacturer_id.as_str(),
+        if unread { "unread" } else { "read" },
+        status.as_str()
+    )
+}
+
+/// Small object whose key files an inbox entry under its read state and
+/// status, at its position, so listing a prefix pages the inbox
+fn inbox_marker_key(manufacturer_id: &ManufacturerId, entry: &RfqInboxEntry) -> String {
+    format!(
+        "{}{}",
+        inbox_marker_prefix(manufacturer_id, entry.is_unread(), &entry.status),
+        entry.position()
+    )
+}
+
+/// Every status an inbox is listed by when the query names none
+const INBOX_STATUSES: [RfqStatus; 4] = [
+    RfqStatus::Open,
+    RfqStatus::Closed,
+    RfqStatus::Archived,
+    RfqStatus::Awarded,
+];
+
 /// S3-based manufacturer repository (simplified for MVP)
 pub struct S3ManufacturerRepository {
-    client: S3Client,
+    store: S3ObjectStore,
     config: Arc<Config>,
 }
 
 impl S3ManufacturerRepository {
     pub fn new(client: S3Client, config: Arc<Config>) -> Self {
-        Self { client, config }
+        Self {
+            store: S3ObjectStore::new(client, &config),
+            config,
+        }
     }
 }
 
@@ -264,89 +872,111 @@ impl S3ManufacturerRepository {
 impl ManufacturerRepository for S3ManufacturerRepository {
     async fn save_manufacturer(&self, manufacturer: &ManufacturerProfile) -> Result<()> {
         let key = format!("manufacturer/{}.json", manufacturer.id);
-        let body = serde_json::to_string(manufacturer).map_err(|e| {
-            DomainError::Internal(format!("Failed to serialize manufacturer: {}", e))
-        })?;
-
-        let _result = self
-            .client
-            .put_object()
-            .bucket(&self.config.public_bucket)
-            .key(&key)
-            .body(body.into_bytes().into())
-            .content_type("application/json")
-            .send()
-            .await
-            .map_err(|e| DomainError::Internal(format!("Failed to save manufacturer: {}", e)))?;
-
+        self.store
+            .put_json(
+                &self.config.public_bucket,
+                &key,
+                manufacturer,
+                &PutOptions::default(),
+            )
+            .await?;
         Ok(())
     }
 
     async fn get_manufacturer(&self, id: &ManufacturerId) -> Result<Option<ManufacturerProfile>> {
         let key = format!("manufacturer/{}.json", id.as_str());
-
-        match self
-            .client
-            .get_object()
-            .bucket(&self.config.public_bucket)
-            .key(&key)
-            .send()
-            .await
-        {
-            Ok(response) => {
-                let body = response
-                    .body
-                    .collect()
-                    .await
-                    .map_err(|e| {
-                        DomainError::Internal(format!("Failed to read manufacturer body: {}", e))
-                    })?
-                    .into_bytes();
-
-                let manufacturer: ManufacturerProfile =
-                    serde_json::from_slice(&body).map_err(|e| {
-                        DomainError::Internal(format!("Failed to deserialize manufacturer: {}", e))
-                    })?;
-
-                Ok(Some(manufacturer))
-            }
-            Err(e) => {
-                if e.to_string().contains("NoSuchKey") {
-                    Ok(None)
-                } else {
-                    Err(DomainError::Internal(format!(
-                        "Failed to fetch manufacturer: {}",
-                        e
-                    )))
-                }
-            }
-        }
+        Ok(self
+            .store
+            .get_json(&self.config.public_bucket, &key)
+            .await?)
     }
 
     async fn delete_manufacturer(&self, id: &ManufacturerId) -> Result<()> {
         let key = format!("manufacturer/{}.json", id.as_str());
+        self.store.delete(&self.config.public_bucket, &key).await?;
+        Ok(())
+    }
+}
 
-        self.client
-            .delete_object()
-            .bucket(&self.config.public_bucket)
-            .key(&key)
-            .send()
-            .await
-            .map_err(|e| DomainError::Internal(format!("Failed to delete manufacturer: {}", e)))?;
+/// S3-based tenant API key repository
+pub struct S3ApiKeyRepository {
+    store: S3ObjectStore,
+    config: Arc<Config>,
+}
+
+impl S3ApiKeyRepository {
+    pub fn new(client: S3Client, config: Arc<Config>) -> Self {
+        Self {
+            store: S3ObjectStore::new(client, &config),
+            config,
+        }
+    }
+}
 
+#[async_trait]
+impl ApiKeyRepository for S3ApiKeyRepository {
+    async fn save_api_key(&self, key: &TenantApiKey) -> Result<()> {
+        let path = format!("tenants/{}/api_keys/{}.json", key.tenant_id, key.id);
+        self.store
+            .put_json(
+                &self.config.private_bucket,
+                &path,
+                key,
+                &PutOptions::default(),
+            )
+            .await?;
         Ok(())
     }
+
+    async fn get_api_key(
+        &self,
+        tenant_id: &TenantId,
+        id: &ApiKeyId,
+    ) -> Result<Option<TenantApiKey>> {
+        let path = format!(
+            "tenants/{}/api_keys/{}.json",
+            tenant_id.as_str(),
+            id.as_str()
+        );
+        Ok(self
+            .store
+            .get_json(&self.config.private_bucket, &path)
+            .await?)
+    }
+
+    async fn list_api_keys(&self, tenant_id: &TenantId) -> Result<Vec<TenantApiKey>> {
+        let prefix = format!("tenants/{}/api_keys/", tenant_id.as_str());
+        let paths = self
+            .store
+            .list_keys(&self.config.private_bucket, &prefix, None, None, None)
+            .await?;
+
+        let mut keys = Vec::with_capacity(paths.len());
+        for path in paths {
+            if let Some(key) = self
+                .store
+                .get_json(&self.config.private_bucket, &path)
+                .await?
+            {
+                keys.push(key);
+            }
+        }
+        Ok(keys)
+    }
 }
 
 /// S3-based catalog repository (simplified for MVP)  
 pub struct S3CatalogRepository {
-    client: S3Client,
+    store: S3ObjectStore,
     config: Arc<Config>,
 }
 
 impl S3CatalogRepository {
     pub fn new(client: S3Client, config: Arc<Config>) -> Self {
-        Self { client, config }
+        Self {
+            store: S3ObjectStore::new(client, &config),
+            config,
+        }
     }
 }
 
@@ -354,62 +984,23 @@ impl S3CatalogRepository {
 impl CatalogRepository for S3CatalogRepository {
     async fn save_category_slice(&self, slice: &CategorySlice) -> Result<()> {
         let key = format!("catalog/category/{}.json", slice.category);
-        let body = serde_json::to_string(slice).map_err(|e| {
-            DomainError::Internal(format!("Failed to serialize category slice: {}", e))
-        })?;
-
-        let _result = self
-            .client
-            .put_object()
-            .bucket(&self.config.public_bucket)
-            .key(&key)
-            .body(body.into_bytes().into())
-            .content_type("application/json")
-            .send()
-            .await
-            .map_err(|e| DomainError::Internal(format!("Failed to save category slice: {}", e)))?;
-
+        self.store
+            .put_json(
+                &self.config.public_bucket,
+                &key,
+                slice,
+                &PutOptions::default(),
+            )
+            .await?;
         Ok(())
     }
 
     async fn get_category_slice(&self, category: &str) -> Result<Option<CategorySlice>> {
         let key = format!("catalog/category/{}.json", category);
-
-        match self
-            .client
-            .get_object()
-            .bucket(&self.config.public_bucket)
-            .key(&key)
-            .send()
-            .await
-        {
-            Ok(response) => {
-                let body = response
-                    .body
-                    .collect()
-                    .await
-                    .map_err(|e| {
-                        DomainError::Internal(format!("Failed to read category slice body: {}", e))
-                    })?
-                    .into_bytes();
-
-                let slice: CategorySlice = serde_json::from_slice(&body).map_err(|e| {
-                    DomainError::Internal(format!("Failed to deserialize category slice: {}", e))
-                })?;
-
-                Ok(Some(slice))
-            }
-            Err(e) => {
-                if e.to_string().contains("NoSuchKey") {
-                    Ok(None)
-                } else {
-                    Err(DomainError::Internal(format!(
-                        "Failed to fetch category slice: {}",
-                        e
-                    )))
-                }
-            }
-        }
+        Ok(self
+            .store
+            .get_json(&self.config.public_bucket, &key)
+            .await?)
     }
 
     async fn save_category_state_slice(
@@ -419,23 +1010,14 @@ impl CatalogRepository for S3CatalogRepository {
         slice: &CategorySlice,
     ) -> Result<()> {
         let key = format!("catalog/category_state/{}/{}.json", category, state);
-        let body = serde_json::to_string(slice).map_err(|e| {
-            DomainError::Internal(format!("Failed to serialize category state slice: {}", e))
-        })?;
-
-        let _result = self
-            .client
-            .put_object()
-            .bucket(&self.config.public_bucket)
-            .key(&key)
-            .body(body.into_bytes().into())
-            .content_type("application/json")
-            .send()
-            .await
-            .map_err(|e| {
-                DomainError::Internal(format!("Failed to save category state slice: {}", e))
-            })?;
-
+        self.store
+            .put_json(
+                &self.config.public_bucket,
+                &key,
+                slice,
+                &PutOptions::default(),
+            )
+            .await?;
         Ok(())
     }
 
@@ -445,60 +1027,25 @@ impl CatalogRepository for S3CatalogRepository {
         state: &str,
     ) -> Result<Option<CategorySlice>> {
         let key = format!("catalog/category_state/{}/{}.json", category, state);
-
-        match self
-            .client
-            .get_object()
-            .bucket(&self.config.public_bucket)
-            .key(&key)
-            .send()
-            .await
-        {
-            Ok(response) => {
-                let body = response
-                    .body
-                    .collect()
-                    .await
-                    .map_err(|e| {
-                        DomainError::Internal(format!(
-                            "Failed to read category state slice body: {}",
-                            e
-                        ))
-                    })?
-                    .into_bytes();
-
-                let slice: CategorySlice = serde_json::from_slice(&body).map_err(|e| {
-                    DomainError::Internal(format!(
-                        "Failed to deserialize category state slice: {}",
-                        e
-                    ))
-                })?;
-
-                Ok(Some(slice))
-            }
-            Err(e) => {
-                if e.to_string().contains("NoSuchKey") {
-                    Ok(None)
-                } else {
-                    Err(DomainError::Internal(format!(
-                        "Failed to fetch category state slice: {}",
-                        e
-                    )))
-                }
-            }
-        }
+        Ok(self
+            .store
+            .get_json(&self.config.public_bucket, &key)
+            .await?)
     }
 }
 
 /// S3-based image service (simplified for MVP)
 pub struct S3ImageService {
-    client: S3Client,
+    store: S3ObjectStore,
     config: Arc<Config>,
 }
 
 impl S3ImageService {
     pub fn new(client: S3Client, config: Arc<Config>) -> Self {
-        Self { client, config }
+        Self {
+            store: S3ObjectStore::new(client, &config),
+            config,
+        }
     }
 }
 
@@ -534,7 +1081,8 @@ impl ImageService for S3ImageService {
             .map_err(|e| DomainError::Internal(format!("Presign config error: {}", e)))?;
 
         let presigned_request = self
-            .client
+            .store
+            .client()
             .put_object()
             .bucket(&self.config.private_bucket)
             .key(&key)
@@ -557,114 +1105,272 @@ impl ImageService for S3ImageService {
     async fn save_image_manifest(&self, manifest: &ImageManifest) -> Result<()> {
         // For MVP, derive tenant from a shared pool since ImageManifest doesn't have tenant_id
         let key = format!("tenants/shared/manifests/{}.json", manifest.id);
-        let body = serde_json::to_vec(manifest)
-            .map_err(|e| DomainError::Internal(format!("Failed to serialize manifest: {}", e)))?;
-
-        self.client
-            .put_object()
-            .bucket(&self.config.public_bucket)
-            .key(&key)
-            .body(body.into())
-            .content_type("application/json")
-            .cache_control("public, max-age=31536000, immutable")
-            .send()
-            .await
-            .map_err(|e| DomainError::Internal(format!("Failed to save manifest: {}", e)))?;
+        let options = PutOptions {
+            cache_control: Some("public, max-age=31536000, immutable"),
+            ..PutOptions::default()
+        };
 
+        self.store
+            .put_json(&self.config.public_bucket, &key, manifest, &options)
+            .await?;
         Ok(())
     }
 
     async fn get_image_manifest(&self, id: &str) -> Result<Option<ImageManifest>> {
         let key = format!("tenants/shared/manifests/{}.json", id);
+        Ok(self
+            .store
+            .get_json(&self.config.public_bucket, &key)
+            .await?)
+    }
 
-        match self
-            .client
+    async fn generate_presigned_download_url(
+        &self,
+        key: &S3Key,
+        file_name: &str,
+        expires_in_seconds: u32,
+    ) -> Result<String> {
+        let presign_config = PresigningConfig::builder()
+            .expires_in(std::time::Duration::from_secs(u64::from(
+                expires_in_seconds,
+            )))
+            .build()
+            .map_err(|e| DomainError::Internal(format!("Presign config error: {}", e)))?;
+
+        let presigned_request = self
+            .store
+            .client()
             .get_object()
-            .bucket(&self.config.public_bucket)
-            .key(&key)
-            .send()
+            .bucket(&self.config.private_bucket)
+            .key(key.as_str())
+            .response_content_disposition(content_disposition(file_name))
+            .presigned(presign_config)
             .await
-        {
-            Ok(response) => {
-                let body = response
-                    .body
-                    .collect()
-                    .await
-                    .map_err(|e| {
-                        DomainError::Internal(format!("Failed to read image manifest body: {}", e))
-                    })?
-                    .into_bytes();
-
-                let manifest: ImageManifest = serde_json::from_slice(&body).map_err(|e| {
-                    DomainError::Internal(format!("Failed to deserialize image manifest: {}", e))
-                })?;
-
-                Ok(Some(manifest))
-            }
-            Err(e) => {
-                if e.to_string().contains("NoSuchKey") {
-                    Ok(None)
-                } else {
-                    Err(DomainError::Internal(format!(
-                        "Failed to fetch image manifest: {}",
-                        e
-                    )))
-                }
+            .map_err(|e| {
+                DomainError::Internal(format!("Failed to generate presigned URL: {}", e))
+            })?;
+
+        Ok(presigned_request.uri().to_string())
+    }
+
+    async fn head_upload(&self, key: &S3Key) -> Result<Option<UploadedObject>> {
+        let head = self
+            .store
+            .head(&self.config.private_bucket, key.as_str())
+            .await?;
+
+        Ok(head.map(|head| UploadedObject {
+            size_bytes: head.content_length.unwrap_or_default().max(0) as u64,
+            tenant_id: head
+                .metadata
+                .as_ref()
+                .and_then(|metadata| metadata.get("tenant").cloned()),
+            content_type: head.content_type,
+        }))
+    }
+}
+
+/// `Content-Disposition` that saves the download as `file_name`: an ASCII
+/// fallback plus the exact name in RFC 5987 encoding
+fn content_disposition(file_name: &str) -> String {
+    let fallback: String = file_name
+        .chars()
+        .map(|c| match c {
+            ' '..='~' if c != '"' && c != '\\' => c,
+            _ => '_',
+        })
+        .collect();
+    let encoded: String = file_name
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'.' | b'-' | b'_' | b'~' => {
+                (b as char).to_string()
             }
-        }
+            _ => format!("%{:02X}", b),
+        })
+        .collect();
+
+    format!(
+        "attachment; filename=\"{}\"; filename*=UTF-8''{}",
+        fallback, encoded
+    )
+}
+
+/// Lifecycle state of an idempotency record
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum IdempotencyState {
+    InProgress,
+    #[default]
+    Completed,
+}
+
+/// Stored idempotency record at `idem/{sha256(key)}.json`
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct IdempotencyRecord {
+    #[serde(default)]
+    pub(crate) state: IdempotencyState,
+    pub(crate) body_hash: String,
+    #[serde(default)]
+    pub(crate) response: String,
+    pub(crate) stored_at: DateTime<Utc>,
+}
+
+impl IdempotencyRecord {
+    /// A record can be taken over once it has expired, or once an in-progress
+    /// marker is older than the lock timeout (its invocation has crashed)
+    pub(crate) fn is_reclaimable(&self, config: &Config) -> bool {
+        let age_limit = match self.state {
+            IdempotencyState::Completed => config.idempotency_ttl_seconds,
+            IdempotencyState::InProgress => config.idempotency_lock_timeout_seconds,
+        };
+        self.stored_at + chrono::Duration::seconds(age_limit) <= Utc::now()
     }
 }
 
-/// S3-based idempotency service (simplified for MVP)
+/// S3-based idempotency service
 pub struct S3IdempotencyService {
-    client: S3Client,
+    store: S3ObjectStore,
     config: Arc<Config>,
 }
 
 impl S3IdempotencyService {
+    /// Attempts before giving up on a key that keeps changing under us
+    const MAX_CLAIM_ATTEMPTS: usize = 3;
+
     pub fn new(client: S3Client, config: Arc<Config>) -> Self {
-        Self { client, config }
+        Self {
+            store: S3ObjectStore::new(client, &config),
+            config,
+        }
     }
 
     fn idempotency_key(&self, key: &str) -> String {
         let hash = sha2::Sha256::digest(key.as_bytes());
         format!("idem/{:x}.json", hash)
     }
+
+    /// Write a record only if the key is absent (or still at `replace_etag`).
+    /// Returns the new ETag, or `None` when another writer got there first.
+    async fn put_record_conditionally(
+        &self,
+        s3_key: &str,
+        record: &IdempotencyRecord,
+        replace_etag: Option<&str>,
+    ) -> Result<Option<String>> {
+        let options = PutOptions::conditional(replace_etag);
+
+        match self
+            .store
+            .put_json(&self.config.private_bucket, s3_key, record, &options)
+            .await
+        {
+            Ok(etag) => Ok(Some(etag)),
+            Err(S3ObjectError::PreconditionFailed { .. }) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
 }
 
 #[async_trait]
 impl IdempotencyService for S3IdempotencyService {
-    async fn check_idempotency(&self, _key: &str, _body_hash: &str) -> Result<Option<String>> {
-        // For MVP, we'll skip idempotency checking
-        Ok(None)
+    async fn claim_idempotency(&self, key: &str, body_hash: &str) -> Result<IdempotencyClaim> {
+        let s3_key = self.idempotency_key(key);
+        let mut replace_etag: Option<String> = None;
+
+        for _ in 0..Self::MAX_CLAIM_ATTEMPTS {
+            let marker = IdempotencyRecord {
+                state: IdempotencyState::InProgress,
+                body_hash: body_hash.to_string(),
+                response: String::new(),
+                stored_at: Utc::now(),
+            };
+
+            if let Some(etag) = self
+                .put_record_conditionally(&s3_key, &marker, replace_etag.take().as_deref())
+                .await?
+            {
+                return Ok(IdempotencyClaim::Acquired(etag));
+            }
+
+            let Some(current) = self
+                .store
+                .get_json_versioned::<IdempotencyRecord>(&self.config.private_bucket, &s3_key)
+                .await?
+            else {
+                // Released between our write and read - try a fresh claim
+                continue;
+            };
+            let record = current.value;
+
+            if record.is_reclaimable(&self.config) {
+                if record.state == IdempotencyState::InProgress {
+                    tracing::warn!("Recovering stale idempotency marker {}", s3_key);
+                }
+                replace_etag = Some(current.version);
+                continue;
+            }
+
+            if record.body_hash != body_hash {
+                return Err(DomainError::Conflict(
+                    "Idempotency-Key was already used with a different request body".to_string(),
+                ));
+            }
+
+            return match record.state {
+                IdempotencyState::Completed => Ok(IdempotencyClaim::Replay(record.response)),
+                IdempotencyState::InProgress => Err(DomainError::Conflict(
+                    "A request with this Idempotency-Key is already in progress".to_string(),
+                )),
+            };
+        }
+
+        Err(DomainError::Conflict(
+            "Idempotency-Key is being claimed concurrently, please retry".to_string(),
+        ))
     }
 
-    async fn store_idempotency(&self, key: &str, body_hash: &str, response: &str) -> Result<()> {
+    async fn store_idempotency(
+        &self,
+        key: &str,
+        claim: &str,
+        body_hash: &str,
+        response: &str,
+    ) -> Result<()> {
         let s3_key = self.idempotency_key(key);
 
-        let record = serde_json::json!({
-            "body_hash": body_hash,
-            "response": response,
-            "stored_at": Utc::now().to_rfc3339()
-        });
+        let record = IdempotencyRecord {
+            state: IdempotencyState::Completed,
+            body_hash: body_hash.to_string(),
+            response: response.to_string(),
+            stored_at: Utc::now(),
+        };
 
-        let body = serde_json::to_string(&record).map_err(|e| {
-            DomainError::Internal(format!("Failed to serialize idempotency record: {}", e))
-        })?;
+        match self
+            .put_record_conditionally(&s3_key, &record, Some(claim))
+            .await?
+        {
+            Some(_) => Ok(()),
+            None => Err(DomainError::Conflict(format!(
+                "Idempotency marker {} was taken over by another request",
+                s3_key
+            ))),
+        }
+    }
 
-        let _result = self
-            .client
-            .put_object()
-            .bucket(&self.config.private_bucket)
-            .key(&s3_key)
-            .body(body.into_bytes().into())
-            .content_type("application/json")
-            .send()
+    async fn release_idempotency(&self, key: &str, claim: &str) -> Result<()> {
+        let s3_key = self.idempotency_key(key);
+        match self
+            .store
+            .delete_if_match(&self.config.private_bucket, &s3_key, claim)
             .await
-            .map_err(|e| {
-                DomainError::Internal(format!("Failed to store idempotency record: {}", e))
-            })?;
-
-        Ok(())
+        {
+            Ok(()) => Ok(()),
+            Err(S3ObjectError::PreconditionFailed { .. }) => {
+                tracing::warn!("Idempotency marker {} was taken over, keeping it", s3_key);
+                Ok(())
+            }
+            Err(e) => Err(e.into()),
+        }
     }
 }
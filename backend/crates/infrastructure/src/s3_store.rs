@@ -0,0 +1,728 @@
+use application::ports::Versioned;
+use aws_sdk_s3::config::http::HttpResponse;
+use aws_sdk_s3::config::retry::RetryConfig;
+use aws_sdk_s3::error::{DisplayErrorContext, ProvideErrorMetadata, SdkError};
+use aws_sdk_s3::operation::head_object::HeadObjectOutput;
+use aws_sdk_s3::Client as S3Client;
+use domain::error::DomainError;
+use serde::{de::DeserializeOwned, Serialize};
+use std::future::Future;
+use std::time::Duration;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::config::Config;
+
+/// Typed failure of a single S3 object operation
+#[derive(Error, Debug)]
+pub enum S3ObjectError {
+    #[error("Object {key} not found")]
+    NotFound { key: String },
+
+    #[error("Access denied to {key}")]
+    AccessDenied { key: String },
+
+    #[error("Precondition failed for {key}")]
+    PreconditionFailed { key: String },
+
+    #[error("Throttled while accessing {key}")]
+    Throttled { key: String },
+
+    #[error("Transient failure accessing {key}: {message}")]
+    Transient { key: String, message: String },
+
+    #[error("Invalid JSON in {key}: {message}")]
+    Serialization { key: String, message: String },
+
+    #[error("Failed to access {key}: {message}")]
+    Other { key: String, message: String },
+}
+
+impl S3ObjectError {
+    /// Classify an SDK failure by error code first, falling back to the HTTP status
+    /// for responses without a body (HEAD, some LocalStack replies)
+    pub fn from_sdk<E>(key: &str, error: SdkError<E, HttpResponse>) -> Self
+    where
+        E: ProvideErrorMetadata + std::error::Error + Send + Sync + 'static,
+    {
+        let key = key.to_string();
+
+        match &error {
+            SdkError::TimeoutError(_)
+            | SdkError::DispatchFailure(_)
+            | SdkError::ResponseError(_) => {
+                return S3ObjectError::Transient {
+                    key,
+                    message: DisplayErrorContext(&error).to_string(),
+                }
+            }
+            SdkError::ServiceError(_) => {}
+            _ => {
+                return S3ObjectError::Other {
+                    key,
+                    message: DisplayErrorContext(&error).to_string(),
+                }
+            }
+        }
+
+        match error.code() {
+            Some("NoSuchKey") | Some("NotFound") => return S3ObjectError::NotFound { key },
+            Some("AccessDenied") | Some("Forbidden") => return S3ObjectError::AccessDenied { key },
+            Some("PreconditionFailed") | Some("ConditionalRequestConflict") => {
+                return S3ObjectError::PreconditionFailed { key }
+            }
+            Some("SlowDown")
+            | Some("Throttling")
+            | Some("ThrottlingException")
+            | Some("RequestLimitExceeded")
+            | Some("TooManyRequests") => return S3ObjectError::Throttled { key },
+            Some("InternalError") | Some("ServiceUnavailable") | Some("RequestTimeout") => {
+                return S3ObjectError::Transient {
+                    key,
+                    message: DisplayErrorContext(&error).to_string(),
+                }
+            }
+            _ => {}
+        }
+
+        match error.raw_response().map(|r| r.status().as_u16()) {
+            Some(404) => S3ObjectError::NotFound { key },
+            Some(403) => S3ObjectError::AccessDenied { key },
+            Some(409) | Some(412) => S3ObjectError::PreconditionFailed { key },
+            Some(429) | Some(503) => S3ObjectError::Throttled { key },
+            Some(500) | Some(502) | Some(504) => S3ObjectError::Transient {
+                key,
+                message: DisplayErrorContext(&error).to_string(),
+            },
+            _ => S3ObjectError::Other {
+                key,
+                message: DisplayErrorContext(&error).to_string(),
+            },
+        }
+    }
+
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            S3ObjectError::Throttled { .. } | S3ObjectError::Transient { .. }
+        )
+    }
+}
+
+impl From<S3ObjectError> for DomainError {
+    fn from(err: S3ObjectError) -> Self {
+        match err {
+            S3ObjectError::PreconditionFailed { key } => {
+                DomainError::Conflict(format!("Concurrent modification of {}", key))
+            }
+            other => DomainError::Internal(other.to_string()),
+        }
+    }
+}
+
+/// Retry schedule for transient failures: exponential backoff capped at
+/// `max_delay`, with equal jitter so writers that failed together do not
+/// retry in lockstep
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl RetryPolicy {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            max_attempts: config.s3_max_attempts.max(1),
+            base_delay: Duration::from_millis(50),
+            max_delay: Duration::from_secs(2),
+        }
+    }
+
+    /// Wait before retrying after failed attempt `attempt` (from 1)
+    fn delay_for(&self, attempt: u32) -> Duration {
+        let jitter = Uuid::new_v4().as_u128() as f64 / u128::MAX as f64;
+        self.jittered_delay(attempt, jitter)
+    }
+
+    /// Half the capped backoff, plus `jitter` (0 to 1) of the other half
+    fn jittered_delay(&self, attempt: u32, jitter: f64) -> Duration {
+        let backoff = self.backoff(attempt);
+        backoff / 2 + (backoff / 2).mul_f64(jitter.clamp(0.0, 1.0))
+    }
+
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.base_delay
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(self.max_delay)
+    }
+}
+
+/// Condition attached to a write
+#[derive(Debug, Clone)]
+pub enum WriteCondition {
+    /// Only create the object (`If-None-Match: *`)
+    IfAbsent,
+    /// Only replace the object while it still has this ETag (`If-Match`)
+    IfMatch(String),
+}
+
+/// Optional settings for [`S3ObjectStore::put_json`]
+#[derive(Debug, Clone, Default)]
+pub struct PutOptions {
+    pub cache_control: Option<&'static str>,
+    pub condition: Option<WriteCondition>,
+}
+
+impl PutOptions {
+    /// Replace the object at `expected_etag`, or create it when `None`
+    pub fn conditional(expected_etag: Option<&str>) -> Self {
+        Self {
+            condition: Some(match expected_etag {
+                Some(etag) => WriteCondition::IfMatch(etag.to_string()),
+                None => WriteCondition::IfAbsent,
+            }),
+            ..Self::default()
+        }
+    }
+}
+
+/// Shared S3 object access used by every S3 adapter: JSON (de)serialization,
+/// typed errors and retries of throttled or transient failures
+#[derive(Clone)]
+pub struct S3ObjectStore {
+    client: S3Client,
+    retry: RetryPolicy,
+}
+
+impl S3ObjectStore {
+    pub fn new(client: S3Client, config: &Config) -> Self {
+        // Retries happen here with our own classification, so the SDK must not retry as well
+        let client = S3Client::from_conf(
+            client
+                .config()
+                .to_builder()
+                .retry_config(RetryConfig::disabled())
+                .build(),
+        );
+
+        Self {
+            client,
+            retry: RetryPolicy::from_config(config),
+        }
+    }
+
+    /// The underlying client, for operations this layer does not wrap (presigning)
+    pub fn client(&self) -> &S3Client {
+        &self.client
+    }
+
+    async fn with_retry<T, F, Fut>(&self, mut operation: F) -> Result<T, S3ObjectError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, S3ObjectError>>,
+    {
+        let mut attempt = 1;
+        loop {
+            match operation().await {
+                Err(e) if e.is_retryable() && attempt < self.retry.max_attempts => {
+                    let delay = self.retry.delay_for(attempt);
+                    tracing::warn!(
+                        "S3 attempt {} failed, retrying in {:?}: {}",
+                        attempt,
+                        delay,
+                        e
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Fetch and deserialize a JSON object, `None` if it does not exist
+    pub async fn get_json<T: DeserializeOwned>(
+        &self,
+        bucket: &str,
+        key: &str,
+    ) -> Result<Option<T>, S3ObjectError> {
+        Ok(self
+            .get_json_versioned(bucket, key)
+            .await?
+            .map(|versioned| versioned.value))
+    }
+
+    /// Fetch and deserialize a JSON object; its version is the ETag
+    pub async fn get_json_versioned<T: DeserializeOwned>(
+        &self,
+        bucket: &str,
+        key: &str,
+    ) -> Result<Option<Versioned<T>>, S3ObjectError> {
+        let Some(object) = self.get_bytes(bucket, key).await? else {
+            return Ok(None);
+        };
+
+        let value =
+            serde_json::from_slice(&object.value).map_err(|e| S3ObjectError::Serialization {
+                key: key.to_string(),
+                message: e.to_string(),
+            })?;
+
+        Ok(Some(Versioned {
+            value,
+            version: object.version,
+        }))
+    }
+
+    /// Fetch an object's raw bytes along with its ETag, `None` if it does not exist
+    pub async fn get_bytes(
+        &self,
+        bucket: &str,
+        key: &str,
+    ) -> Result<Option<Versioned<Vec<u8>>>, S3ObjectError> {
+        let fetched = self
+            .with_retry(|| async {
+                let response = self
+                    .client
+                    .get_object()
+                    .bucket(bucket)
+                    .key(key)
+                    .send()
+                    .await
+                    .map_err(|e| S3ObjectError::from_sdk(key, e))?;
+
+                let etag = response.e_tag.clone().unwrap_or_default();
+                let body = response
+                    .body
+                    .collect()
+                    .await
+                    .map_err(|e| S3ObjectError::Transient {
+                        key: key.to_string(),
+                        message: e.to_string(),
+                    })?
+                    .into_bytes();
+
+                Ok((body, etag))
+            })
+            .await;
+
+        match fetched {
+            Ok((body, etag)) => Ok(Some(Versioned {
+                value: body.to_vec(),
+                version: etag,
+            })),
+            Err(S3ObjectError::NotFound { .. }) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Fetch an object's metadata without its body, `None` if it does not exist
+    pub async fn head(
+        &self,
+        bucket: &str,
+        key: &str,
+    ) -> Result<Option<HeadObjectOutput>, S3ObjectError> {
+        let head = self
+            .with_retry(|| async {
+                self.client
+                    .head_object()
+                    .bucket(bucket)
+                    .key(key)
+                    .send()
+                    .await
+                    .map_err(|e| S3ObjectError::from_sdk(key, e))
+            })
+            .await;
+
+        match head {
+            Ok(head) => Ok(Some(head)),
+            Err(S3ObjectError::NotFound { .. }) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Serialize and store a JSON object, returning the new ETag
+    pub async fn put_json<T: Serialize>(
+        &self,
+        bucket: &str,
+        key: &str,
+        value: &T,
+        options: &PutOptions,
+    ) -> Result<String, S3ObjectError> {
+        let body = serde_json::to_vec(value).map_err(|e| S3ObjectError::Serialization {
+            key: key.to_string(),
+            message: e.to_string(),
+        })?;
+
+        self.put_bytes(bucket, key, body, "application/json", options)
+            .await
+    }
+
+    /// Store raw bytes, returning the new ETag.
+    ///
+    /// A conditional write is not simply sent again after a transient
+    /// failure: it may have landed with only its response lost, and would
+    /// then fail its own condition. The object is re-read instead; if it
+    /// already holds our bytes the write went through, and it is only retried
+    /// while the condition still holds.
+    pub async fn put_bytes(
+        &self,
+        bucket: &str,
+        key: &str,
+        body: Vec<u8>,
+        content_type: &str,
+        options: &PutOptions,
+    ) -> Result<String, S3ObjectError> {
+        let Some(condition) = &options.condition else {
+            return self
+                .with_retry(|| self.send_put(bucket, key, &body, content_type, options))
+                .await;
+        };
+
+        let mut attempt = 1;
+        loop {
+            match self
+                .send_put(bucket, key, &body, content_type, options)
+                .await
+            {
+                Err(e) if e.is_retryable() && attempt < self.retry.max_attempts => {
+                    let delay = self.retry.delay_for(attempt);
+                    tracing::warn!(
+                        "Conditional S3 write attempt {} failed, checking in {:?}: {}",
+                        attempt,
+                        delay,
+                        e
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+
+                    let current = self.get_bytes(bucket, key).await?;
+                    if let Some(current) = &current {
+                        if current.value == body {
+                            return Ok(current.version.clone());
+                        }
+                    }
+                    let holds = match condition {
+                        WriteCondition::IfAbsent => current.is_none(),
+                        WriteCondition::IfMatch(etag) => {
+                            current.is_some_and(|current| current.version == *etag)
+                        }
+                    };
+                    if !holds {
+                        return Err(S3ObjectError::PreconditionFailed {
+                            key: key.to_string(),
+                        });
+                    }
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// One PUT request, without retries
+    async fn send_put(
+        &self,
+        bucket: &str,
+        key: &str,
+        body: &[u8],
+        content_type: &str,
+        options: &PutOptions,
+    ) -> Result<String, S3ObjectError> {
+        let mut request = self
+            .client
+            .put_object()
+            .bucket(bucket)
+            .key(key)
+            .body(body.to_vec().into())
+            .content_type(content_type);
+
+        if let Some(cache_control) = options.cache_control {
+            request = request.cache_control(cache_control);
+        }
+
+        request = match &options.condition {
+            Some(WriteCondition::IfAbsent) => request.if_none_match("*"),
+            Some(WriteCondition::IfMatch(etag)) => request.if_match(etag),
+            None => request,
+        };
+
+        let response = request.send().await.map_err(|e| {
+            match S3ObjectError::from_sdk(key, e) {
+                // Replacing an object that vanished is a lost race like any other
+                S3ObjectError::NotFound { key } if options.condition.is_some() => {
+                    S3ObjectError::PreconditionFailed { key }
+                }
+                other => other,
+            }
+        })?;
+
+        Ok(response.e_tag.unwrap_or_default())
+    }
+
+    /// Delete an object; deleting a missing object succeeds
+    pub async fn delete(&self, bucket: &str, key: &str) -> Result<(), S3ObjectError> {
+        self.with_retry(|| async {
+            self.client
+                .delete_object()
+                .bucket(bucket)
+                .key(key)
+                .send()
+                .await
+                .map_err(|e| S3ObjectError::from_sdk(key, e))?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Delete an object only while it still has `etag` (`If-Match`); deleting
+    /// a missing object succeeds. A retry after a lost response finds the
+    /// object gone, so conditional deletes are safe to retry.
+    pub async fn delete_if_match(
+        &self,
+        bucket: &str,
+        key: &str,
+        etag: &str,
+    ) -> Result<(), S3ObjectError> {
+        let deleted = self
+            .with_retry(|| async {
+                self.client
+                    .delete_object()
+                    .bucket(bucket)
+                    .key(key)
+                    .if_match(etag)
+                    .send()
+                    .await
+                    .map_err(|e| S3ObjectError::from_sdk(key, e))?;
+                Ok(())
+            })
+            .await;
+
+        match deleted {
+            Err(S3ObjectError::NotFound { .. }) => Ok(()),
+            result => result,
+        }
+    }
+
+    /// List keys under a prefix in lexical order, starting after `start_after` and
+    /// following continuation tokens. Stops before the first key at or past
+    /// `end_before`, or once `max_keys` keys have been collected.
+    pub async fn list_keys(
+        &self,
+        bucket: &str,
+        prefix: &str,
+        start_after: Option<&str>,
+        end_before: Option<&str>,
+        max_keys: Option<usize>,
+    ) -> Result<Vec<String>, S3ObjectError> {
+        const PAGE_SIZE: usize = 1000;
+
+        let mut keys = Vec::new();
+        let mut continuation_token: Option<String> = None;
+
+        loop {
+            let page_size = max_keys.map_or(PAGE_SIZE, |max| (max - keys.len()).min(PAGE_SIZE));
+            let token = continuation_token.clone();
+
+            let response = self
+                .with_retry(|| async {
+                    self.client
+                        .list_objects_v2()
+                        .bucket(bucket)
+                        .prefix(prefix)
+                        .set_start_after(start_after.map(str::to_string))
+                        .set_continuation_token(token.clone())
+                        .max_keys(page_size as i32)
+                        .send()
+                        .await
+                        .map_err(|e| S3ObjectError::from_sdk(prefix, e))
+                })
+                .await?;
+
+            for key in response
+                .contents
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|object| object.key)
+            {
+                if end_before.is_some_and(|end| key.as_str() >= end) {
+                    return Ok(keys);
+                }
+                keys.push(key);
+            }
+
+            continuation_token = response.next_continuation_token;
+            let is_full = max_keys.is_some_and(|max| keys.len() >= max);
+            if is_full || continuation_token.is_none() {
+                return Ok(keys);
+            }
+        }
+    }
+
+    /// List the distinct "directories" directly under a prefix, e.g. `rfq/{id}/`
+    pub async fn list_common_prefixes(
+        &self,
+        bucket: &str,
+        prefix: &str,
+    ) -> Result<Vec<String>, S3ObjectError> {
+        let mut prefixes = Vec::new();
+        let mut continuation_token: Option<String> = None;
+
+        loop {
+            let token = continuation_token.clone();
+            let response = self
+                .with_retry(|| async {
+                    self.client
+                        .list_objects_v2()
+                        .bucket(bucket)
+                        .prefix(prefix)
+                        .delimiter("/")
+                        .set_continuation_token(token.clone())
+                        .send()
+                        .await
+                        .map_err(|e| S3ObjectError::from_sdk(prefix, e))
+                })
+                .await?;
+
+            prefixes.extend(
+                response
+                    .common_prefixes
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter_map(|common| common.prefix),
+            );
+
+            continuation_token = response.next_continuation_token;
+            if continuation_token.is_none() {
+                return Ok(prefixes);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_sdk_s3::error::ErrorMetadata;
+    use aws_sdk_s3::operation::put_object::PutObjectError;
+    use aws_sdk_s3::primitives::SdkBody;
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 5,
+            base_delay: Duration::from_millis(50),
+            max_delay: Duration::from_secs(2),
+        }
+    }
+
+    #[test]
+    fn delays_stay_within_half_and_all_of_the_backoff() {
+        let policy = policy();
+        let backoffs = [50, 100, 200, 400, 800, 1600, 2000, 2000];
+        for (attempt, backoff) in (1..).zip(backoffs) {
+            let backoff = Duration::from_millis(backoff);
+            assert_eq!(policy.backoff(attempt), backoff, "attempt {}", attempt);
+            assert_eq!(policy.jittered_delay(attempt, 0.0), backoff / 2);
+            assert_eq!(policy.jittered_delay(attempt, 1.0), backoff);
+
+            for _ in 0..100 {
+                let delay = policy.delay_for(attempt);
+                assert!(
+                    backoff / 2 <= delay && delay <= backoff,
+                    "attempt {} waited {:?}",
+                    attempt,
+                    delay
+                );
+            }
+        }
+
+        assert_eq!(policy.backoff(0), Duration::from_millis(50));
+        assert_eq!(policy.backoff(u32::MAX), Duration::from_secs(2));
+        assert_eq!(policy.jittered_delay(1, 7.0), Duration::from_millis(50));
+    }
+
+    #[test]
+    fn jitter_spreads_retries_apart() {
+        let policy = policy();
+        let delays: std::collections::BTreeSet<Duration> =
+            (0..20).map(|_| policy.delay_for(4)).collect();
+        assert!(delays.len() > 1);
+    }
+
+    fn service_error(status: u16, code: Option<&str>) -> SdkError<PutObjectError, HttpResponse> {
+        let mut metadata = ErrorMetadata::builder();
+        if let Some(code) = code {
+            metadata = metadata.code(code);
+        }
+        SdkError::service_error(
+            PutObjectError::generic(metadata.build()),
+            HttpResponse::new(status.try_into().unwrap(), SdkBody::empty()),
+        )
+    }
+
+    #[test]
+    fn sdk_errors_are_classified_by_code_then_status() {
+        let cases = [
+            (404, Some("NoSuchKey"), "NotFound"),
+            (404, None, "NotFound"),
+            (403, Some("AccessDenied"), "AccessDenied"),
+            (412, Some("PreconditionFailed"), "PreconditionFailed"),
+            (412, None, "PreconditionFailed"),
+            (
+                409,
+                Some("ConditionalRequestConflict"),
+                "PreconditionFailed",
+            ),
+            (409, None, "PreconditionFailed"),
+            (503, Some("SlowDown"), "Throttled"),
+            (400, Some("ThrottlingException"), "Throttled"),
+            (429, None, "Throttled"),
+            (503, None, "Throttled"),
+            (500, Some("InternalError"), "Transient"),
+            (502, None, "Transient"),
+            (400, Some("InvalidArgument"), "Other"),
+            (418, None, "Other"),
+        ];
+
+        for (status, code, expected) in cases {
+            let error = S3ObjectError::from_sdk("k", service_error(status, code));
+            let kind = match &error {
+                S3ObjectError::NotFound { .. } => "NotFound",
+                S3ObjectError::AccessDenied { .. } => "AccessDenied",
+                S3ObjectError::PreconditionFailed { .. } => "PreconditionFailed",
+                S3ObjectError::Throttled { .. } => "Throttled",
+                S3ObjectError::Transient { .. } => "Transient",
+                S3ObjectError::Serialization { .. } => "Serialization",
+                S3ObjectError::Other { .. } => "Other",
+            };
+            assert_eq!(kind, expected, "{} {:?}", status, code);
+            assert_eq!(
+                error.is_retryable(),
+                matches!(expected, "Throttled" | "Transient"),
+                "{} {:?}",
+                status,
+                code
+            );
+        }
+
+        let timeout: SdkError<PutObjectError, HttpResponse> =
+            SdkError::timeout_error("operation timed out");
+        assert!(matches!(
+            S3ObjectError::from_sdk("k", timeout),
+            S3ObjectError::Transient { .. }
+        ));
+    }
+
+    #[test]
+    fn only_precondition_failures_map_to_conflicts() {
+        let conflict = DomainError::from(S3ObjectError::PreconditionFailed {
+            key: "k".to_string(),
+        });
+        assert!(matches!(conflict, DomainError::Conflict(_)));
+
+        let throttled = DomainError::from(S3ObjectError::Throttled {
+            key: "k".to_string(),
+        });
+        assert!(matches!(throttled, DomainError::Internal(_)));
+    }
+}
@@ -1,3 +1,4 @@
+use application::access::AccessTokens;
 use application::ports::EmailService;
 use async_trait::async_trait;
 use aws_sdk_sesv2::Client as SesClient;
@@ -14,14 +15,21 @@ pub struct SesEmailService {
     client: SesClient,
     config: Arc<Config>,
     from_email: String,
+    access_tokens: Arc<AccessTokens>,
 }
 
 impl SesEmailService {
-    pub fn new(client: SesClient, config: Arc<Config>, from_email: String) -> Self {
+    pub fn new(
+        client: SesClient,
+        config: Arc<Config>,
+        from_email: String,
+        access_tokens: Arc<AccessTokens>,
+    ) -> Self {
         Self {
             client,
             config,
             from_email,
+            access_tokens,
         }
     }
 }
@@ -30,31 +38,7 @@ impl SesEmailService {
 impl EmailService for SesEmailService {
     async fn send_rfq_created_notification(&self, rfq: &RfqMeta) -> Result<()> {
         // Send notification to manufacturer
-        let manufacturer_subject = format!("New RFQ: {}", rfq.subject);
-        let manufacturer_body = format!(
-            "Hello,\n\n\
-            You have received a new Request for Quote (RFQ).\n\n\
-            Subject: {}\n\
-            From: {} ({})\n\n\
-            Please log in to your account to view the details and respond.\n\n\
-            Best regards,\n\
-            Terra Platform",
-            rfq.subject,
-            rfq.buyer.name.as_ref().unwrap_or(&"Anonymous".to_string()),
-            rfq.buyer.email
-        );
-
-        // Find manufacturer email from participants
-        let manufacturer_email = rfq
-            .participants
-            .iter()
-            .find(|p| p.role == ParticipantRole::Manufacturer)
-            .map(|p| &p.email);
-
-        if let Some(to_email) = manufacturer_email {
-            self.send_email(to_email, &manufacturer_subject, &manufacturer_body)
-                .await?;
-        }
+        self.notify_manufacturer_of_new_rfq(rfq).await?;
 
         // Send confirmation to buyer
         let buyer_subject = "RFQ Submitted Successfully";
@@ -65,11 +49,13 @@ impl EmailService for SesEmailService {
             RFQ ID: {}\n\n\
             The manufacturer will be notified and should respond within a few business days.\n\
             You will receive notifications for any updates.\n\n\
+            Follow the conversation: {}\n\n\
             Best regards,\n\
             Terra Platform",
             rfq.buyer.name.as_ref().unwrap_or(&"Customer".to_string()),
             rfq.subject,
-            rfq.id
+            rfq.id,
+            self.rfq_link(rfq, ParticipantRole::Buyer, &rfq.buyer.email)
         );
 
         self.send_email(&rfq.buyer.email, buyer_subject, &buyer_body)
@@ -78,9 +64,14 @@ impl EmailService for SesEmailService {
         Ok(())
     }
 
-    async fn send_rfq_message_notification(&self, rfq: &RfqMeta, event: &RfqEvent) -> Result<()> {
+    async fn send_rfq_message_notification(
+        &self,
+        rfq: &RfqMeta,
+        event: &RfqEvent,
+        attachments: &[AttachmentRef],
+    ) -> Result<()> {
         if let RfqEvent::Message(message_event) = event {
-            let (to_email, from_role) = match message_event.base.by {
+            let (to_email, to_role, from_role) = match message_event.base.by {
                 EventAuthor::Buyer => {
                     // Message from buyer, notify manufacturer
                     let manufacturer_email = rfq
@@ -88,11 +79,15 @@ impl EmailService for SesEmailService {
                         .iter()
                         .find(|p| p.role == ParticipantRole::Manufacturer)
                         .map(|p| &p.email);
-                    (manufacturer_email, "buyer")
+                    (manufacturer_email, ParticipantRole::Manufacturer, "buyer")
                 }
                 EventAuthor::Manufacturer => {
                     // Message from manufacturer, notify buyer
-                    (Some(&rfq.buyer.email), "manufacturer")
+                    (
+                        Some(&rfq.buyer.email),
+                        ParticipantRole::Buyer,
+                        "manufacturer",
+                    )
                 }
                 EventAuthor::System => {
                     // System messages don't trigger notifications
@@ -110,10 +105,16 @@ impl EmailService for SesEmailService {
                     From: {}\n\n\
                     Message:\n\
                     {}\n\n\
-                    Please log in to your account to view the full conversation and respond.\n\n\
+                    {}\
+                    View the conversation and reply: {}\n\n\
                     Best regards,\n\
                     Terra Platform",
-                    rfq.subject, rfq.id, from_role, message_event.body
+                    rfq.subject,
+                    rfq.id,
+                    from_role,
+                    message_event.body,
+                    attachment_list(attachments),
+                    self.rfq_link(rfq, to_role, recipient_email)
                 );
 
                 self.send_email(recipient_email, &subject, &body).await?;
@@ -122,9 +123,266 @@ impl EmailService for SesEmailService {
 
         Ok(())
     }
+
+    async fn send_rfq_status_notification(&self, rfq: &RfqMeta, event: &RfqEvent) -> Result<()> {
+        if let RfqEvent::Status(status_event) = event {
+            let manufacturer_email = rfq
+                .participants
+                .iter()
+                .find(|p| p.role == ParticipantRole::Manufacturer)
+                .map(|p| &p.email);
+
+            // Notify the other party; system changes go to both
+            let manufacturer =
+                manufacturer_email.map(|email| (email, ParticipantRole::Manufacturer));
+            let buyer = (&rfq.buyer.email, ParticipantRole::Buyer);
+            let recipients: Vec<(&String, ParticipantRole)> = match status_event.base.by {
+                EventAuthor::Buyer => manufacturer.into_iter().collect(),
+                EventAuthor::Manufacturer => vec![buyer],
+                EventAuthor::System => manufacturer.into_iter().chain(Some(buyer)).collect(),
+            };
+
+            let change = match status_event.status {
+                StatusType::Reopened => "reopened",
+                _ => rfq.status.as_str(),
+            };
+            let subject = format!("RFQ {}: {}", change, rfq.subject);
+            for (recipient_email, role) in recipients {
+                let body = format!(
+                    "Hello,\n\n\
+                    The {} has {} an RFQ.\n\n\
+                    Subject: {}\n\
+                    RFQ ID: {}\n\
+                    {}\n\
+                    View the conversation: {}\n\n\
+                    Best regards,\n\
+                    Terra Platform",
+                    status_event.base.by.as_str(),
+                    change,
+                    rfq.subject,
+                    rfq.id,
+                    status_event
+                        .note
+                        .as_ref()
+                        .map(|note| format!("Note: {}\n", note))
+                        .unwrap_or_default(),
+                    self.rfq_link(rfq, role, recipient_email)
+                );
+                self.send_email(recipient_email, &subject, &body).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn send_campaign_created_notification(
+        &self,
+        campaign: &RfqCampaign,
+        rfqs: &[RfqMeta],
+    ) -> Result<()> {
+        // One email per manufacturer, so no supplier learns of the others
+        for rfq in rfqs {
+            self.notify_manufacturer_of_new_rfq(rfq).await?;
+        }
+
+        let manufacturers: String = rfqs
+            .iter()
+            .map(|rfq| {
+                let name = rfq
+                    .participants
+                    .iter()
+                    .find(|p| p.role == ParticipantRole::Manufacturer)
+                    .and_then(|p| p.name.clone())
+                    .unwrap_or_else(|| rfq.manufacturer_id.clone());
+                format!("- {} (RFQ ID: {})\n", name, rfq.id)
+            })
+            .collect();
+
+        let buyer_subject = "RFQ Campaign Submitted Successfully";
+        let buyer_body = format!(
+            "Hello {},\n\n\
+            Your Request for Quote has been sent to {} manufacturers.\n\n\
+            Subject: {}\n\
+            Campaign ID: {}\n\n\
+            {}\n\
+            Each manufacturer sees only its own conversation with you.\n\
+            You will receive notifications for any updates.\n\n\
+            Compare the responses: {}\n\n\
+            Best regards,\n\
+            Terra Platform",
+            campaign
+                .buyer
+                .name
+                .as_ref()
+                .unwrap_or(&"Customer".to_string()),
+            rfqs.len(),
+            campaign.subject,
+            campaign.id,
+            manufacturers,
+            self.link(
+                "campaign",
+                &campaign.id,
+                ParticipantRole::Buyer,
+                &campaign.buyer.email
+            )
+        );
+
+        self.send_email(&campaign.buyer.email, buyer_subject, &buyer_body)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn send_rfq_outcome_notification(&self, rfq: &RfqMeta, event: &RfqEvent) -> Result<()> {
+        for email in self.outcome_emails(rfq, event) {
+            self.send_email(&email.to, &email.subject, &email.body)
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+/// A composed email, ready for `send_email`
+struct OutgoingEmail {
+    to: String,
+    subject: String,
+    body: String,
+}
+
+/// Plain-text list of attached files, empty when there are none
+fn attachment_list(attachments: &[AttachmentRef]) -> String {
+    if attachments.is_empty() {
+        return String::new();
+    }
+
+    let files: String = attachments
+        .iter()
+        .map(|a| {
+            format!(
+                "- {} ({}, {} KB)\n",
+                a.file_name,
+                a.content_type,
+                a.size_bytes.div_ceil(1024)
+            )
+        })
+        .collect();
+    format!("Attachments:\n{}\n", files)
 }
 
 impl SesEmailService {
+    async fn notify_manufacturer_of_new_rfq(&self, rfq: &RfqMeta) -> Result<()> {
+        // Find manufacturer email from participants
+        let manufacturer_email = rfq
+            .participants
+            .iter()
+            .find(|p| p.role == ParticipantRole::Manufacturer)
+            .map(|p| &p.email);
+
+        if let Some(to_email) = manufacturer_email {
+            let manufacturer_subject = format!("New RFQ: {}", rfq.subject);
+            let manufacturer_body = format!(
+                "Hello,\n\n\
+                You have received a new Request for Quote (RFQ).\n\n\
+                Subject: {}\n\
+                From: {} ({})\n\n\
+                View the details and respond: {}\n\n\
+                All RFQs sent to you: {}\n\n\
+                Best regards,\n\
+                Terra Platform",
+                rfq.subject,
+                rfq.buyer.name.as_ref().unwrap_or(&"Anonymous".to_string()),
+                rfq.buyer.email,
+                self.rfq_link(rfq, ParticipantRole::Manufacturer, to_email),
+                self.link(
+                    "inbox",
+                    &rfq.manufacturer_id,
+                    ParticipantRole::Manufacturer,
+                    to_email
+                )
+            );
+            self.send_email(to_email, &manufacturer_subject, &manufacturer_body)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Emails telling the manufacturer and then the buyer how the RFQ was
+    /// decided; none for events other than an award or a decline
+    fn outcome_emails(&self, rfq: &RfqMeta, event: &RfqEvent) -> Vec<OutgoingEmail> {
+        let RfqEvent::Status(status_event) = event else {
+            return Vec::new();
+        };
+        let (decision, manufacturer_line, buyer_line) = match status_event.status {
+            StatusType::Awarded => (
+                "awarded",
+                "Congratulations, the buyer has awarded you this RFQ.",
+                "You have awarded this RFQ to the manufacturer.",
+            ),
+            StatusType::Declined => (
+                "declined",
+                "The buyer has decided not to proceed with this RFQ.",
+                "You have declined this RFQ.",
+            ),
+            _ => return Vec::new(),
+        };
+
+        let subject = format!("RFQ {}: {}", decision, rfq.subject);
+        let details = format!(
+            "Subject: {}\n\
+            RFQ ID: {}\n\
+            {}",
+            rfq.subject,
+            rfq.id,
+            status_event
+                .note
+                .as_ref()
+                .map(|reason| format!("Reason: {}\n", reason))
+                .unwrap_or_default()
+        );
+
+        let manufacturer_email = rfq
+            .participants
+            .iter()
+            .find(|p| p.role == ParticipantRole::Manufacturer)
+            .map(|p| &p.email);
+        manufacturer_email
+            .map(|email| (email, ParticipantRole::Manufacturer, manufacturer_line))
+            .into_iter()
+            .chain(Some((&rfq.buyer.email, ParticipantRole::Buyer, buyer_line)))
+            .map(|(recipient_email, role, line)| OutgoingEmail {
+                to: recipient_email.clone(),
+                subject: subject.clone(),
+                body: format!(
+                    "Hello,\n\n\
+                    {}\n\n\
+                    {}\n\
+                    View the conversation: {}\n\n\
+                    Best regards,\n\
+                    Terra Platform",
+                    line,
+                    details,
+                    self.rfq_link(rfq, role, recipient_email)
+                ),
+            })
+            .collect()
+    }
+
+    /// Link that lets the recipient open the RFQ as `role` without an account
+    fn rfq_link(&self, rfq: &RfqMeta, role: ParticipantRole, email: &str) -> String {
+        self.link("rfq", &rfq.id, role, email)
+    }
+
+    /// Frontend page `{kind}/{id}` carrying a fresh access token for it
+    fn link(&self, kind: &str, id: &str, role: ParticipantRole, email: &str) -> String {
+        format!(
+            "{}/{}/{}?token={}",
+            self.config.app_base_url.trim_end_matches('/'),
+            kind,
+            id,
+            self.access_tokens.issue(id, role, email)
+        )
+    }
+
     async fn send_email(&self, _to_email: &str, _subject: &str, _body: &str) -> Result<()> {
         // For MVP, we'll just log the email instead of actually sending it
         tracing::info!(
@@ -135,3 +393,120 @@ impl SesEmailService {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_sdk_sesv2::config::{BehaviorVersion, Region};
+
+    fn service(access_tokens: Arc<AccessTokens>) -> SesEmailService {
+        let client = SesClient::from_conf(
+            aws_sdk_sesv2::Config::builder()
+                .behavior_version(BehaviorVersion::latest())
+                .region(Region::new("us-east-1"))
+                .build(),
+        );
+        let mut config = Config::from_env();
+        config.app_base_url = "https://app.example/".to_string();
+        SesEmailService::new(
+            client,
+            Arc::new(config),
+            "rfq@example.com".to_string(),
+            access_tokens,
+        )
+    }
+
+    fn rfq() -> RfqMeta {
+        let created_at = "2025-01-01T00:00:00Z".parse().unwrap();
+        RfqMeta {
+            id: "r_1".to_string(),
+            tenant_id: "t1".to_string(),
+            manufacturer_id: "mfg_acme".to_string(),
+            buyer: Contact {
+                email: "buyer@example.com".to_string(),
+                name: None,
+            },
+            subject: "Brackets".to_string(),
+            status: RfqStatus::Awarded,
+            created_at,
+            last_event_ts: created_at,
+            participants: vec![
+                Participant {
+                    role: ParticipantRole::Buyer,
+                    email: "buyer@example.com".to_string(),
+                    name: None,
+                },
+                Participant {
+                    role: ParticipantRole::Manufacturer,
+                    email: "sales@acme.example".to_string(),
+                    name: None,
+                },
+            ],
+            attachments: None,
+            latest_quote: None,
+            outcome: None,
+            campaign_id: None,
+            last_reply_position: None,
+        }
+    }
+
+    fn decided(status: StatusType) -> RfqEvent {
+        RfqEvent::new_status(
+            "r_1".to_string(),
+            EventAuthor::Buyer,
+            status,
+            Some("Best lead time".to_string()),
+            None,
+        )
+    }
+
+    #[test]
+    fn outcome_emails_go_to_both_parties_with_their_own_link() {
+        let tokens = Arc::new(AccessTokens::with_random_secret(chrono::Duration::days(1)));
+        let ses = service(tokens.clone());
+
+        let emails = ses.outcome_emails(&rfq(), &decided(StatusType::Awarded));
+        let recipients: Vec<_> = emails.iter().map(|e| e.to.as_str()).collect();
+        assert_eq!(recipients, ["sales@acme.example", "buyer@example.com"]);
+
+        for (email, role) in emails
+            .iter()
+            .zip([ParticipantRole::Manufacturer, ParticipantRole::Buyer])
+        {
+            assert_eq!(email.subject, "RFQ awarded: Brackets");
+            assert!(email.body.contains("Reason: Best lead time"));
+
+            let token = email
+                .body
+                .split("https://app.example/rfq/r_1?token=")
+                .nth(1)
+                .and_then(|rest| rest.split_whitespace().next())
+                .expect("link to the RFQ");
+            let claims = tokens.verify(token).unwrap();
+            assert_eq!(claims.role, role);
+            assert_eq!(claims.email, email.to);
+        }
+        assert!(emails[0].body.contains("awarded you"));
+        assert!(emails[1].body.contains("You have awarded"));
+    }
+
+    #[test]
+    fn only_awards_and_declines_send_outcome_emails() {
+        let ses = service(Arc::new(AccessTokens::with_random_secret(
+            chrono::Duration::days(1),
+        )));
+
+        let declined = ses.outcome_emails(&rfq(), &decided(StatusType::Declined));
+        assert_eq!(declined.len(), 2);
+        assert!(declined
+            .iter()
+            .all(|e| e.subject == "RFQ declined: Brackets"));
+
+        assert!(ses
+            .outcome_emails(&rfq(), &decided(StatusType::Closed))
+            .is_empty());
+        let message =
+            RfqEvent::new_message("r_1".to_string(), EventAuthor::Buyer, "Thanks".to_string());
+        assert!(ses.outcome_emails(&rfq(), &message).is_empty());
+    }
+}
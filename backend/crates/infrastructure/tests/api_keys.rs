@@ -0,0 +1,51 @@
+mod common;
+
+use application::access::Principal;
+use application::dto::CreateApiKeyRequest;
+use application::policy::{authorize, Action, Resource, Role};
+use application::services::ApiKeyService;
+use common::*;
+use domain::entities::ApiKeyScope;
+use domain::error::DomainError;
+use infrastructure::memory::InMemoryApiKeyRepository;
+use std::sync::Arc;
+
+fn admin() -> Principal {
+    Principal::Admin {
+        subject: "ops".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn a_revoked_key_never_becomes_a_principal() {
+    let f = Fixture::new();
+    let rfq = f.meta(&f.open_rfq().await).await;
+    let service = ApiKeyService::new(Arc::new(InMemoryApiKeyRepository::new()));
+    let created = service
+        .create_key(
+            "t1",
+            &admin(),
+            CreateApiKeyRequest {
+                name: "ERP".to_string(),
+                scopes: vec![ApiKeyScope::RfqRead],
+                expires_at: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    let principal = service.authenticate(&created.key).await.unwrap();
+    assert_eq!(
+        authorize(&principal, Action::ReadRfq, &Resource::Rfq(&rfq)).unwrap(),
+        Role::TenantAdmin
+    );
+
+    service
+        .revoke_key("t1", &created.api_key.id, &admin())
+        .await
+        .unwrap();
+    assert!(matches!(
+        service.authenticate(&created.key).await,
+        Err(DomainError::Unauthorized(_))
+    ));
+}
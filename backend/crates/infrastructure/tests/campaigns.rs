@@ -0,0 +1,97 @@
+mod common;
+
+use application::access::Principal;
+use application::dto::{CampaignResponse, ContactDto, CreateCampaignRequest, RfqEventDto};
+use application::ports::ManufacturerRepository;
+use common::*;
+
+const OTHER: &str = "mfg_bolt";
+
+/// A campaign to the fixture manufacturer and one other, with the buyer's
+/// campaign principal
+async fn campaign(f: &Fixture) -> (String, Principal) {
+    f.manufacturers
+        .save_manufacturer(&manufacturer(OTHER, "sales@bolt.example"))
+        .await
+        .unwrap();
+    let created = f
+        .service
+        .create_campaign(
+            &Principal::Anonymous,
+            CreateCampaignRequest {
+                tenant_id: "t1".to_string(),
+                manufacturer_ids: vec![MANUFACTURER.to_string(), OTHER.to_string()],
+                buyer: ContactDto {
+                    email: BUYER.to_string(),
+                    name: None,
+                },
+                subject: "Brackets".to_string(),
+                body: "Can you quote 500 brackets?".to_string(),
+                attachments: None,
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    let principal = f
+        .access_tokens
+        .verify(created.access_token.as_deref().unwrap())
+        .unwrap()
+        .into();
+    (created.id, principal)
+}
+
+async fn view(f: &Fixture, id: &str, principal: &Principal) -> CampaignResponse {
+    f.service.get_campaign(id, principal).await.unwrap()
+}
+
+#[tokio::test]
+async fn the_campaign_view_shows_each_latest_quote_and_reply() {
+    let f = Fixture::new();
+    let (id, principal) = campaign(&f).await;
+    let before = view(&f, &id, &principal).await;
+    assert_eq!(before.rfqs.len(), 2);
+    assert!(before
+        .rfqs
+        .iter()
+        .all(|rfq| rfq.quote.is_none() && rfq.last_reply.is_none()));
+
+    let rfq_id = before.rfqs[0].rfq_id.clone();
+    assert_eq!(before.rfqs[0].manufacturer_id, MANUFACTURER);
+    let maker = maker(&rfq_id);
+    f.service
+        .post_message(&rfq_id, &maker, message("Looking at it"), None)
+        .await
+        .unwrap();
+    f.service
+        .submit_quote(&rfq_id, &maker, quote(), None)
+        .await
+        .unwrap();
+    f.service
+        .submit_quote(&rfq_id, &maker, quote(), None)
+        .await
+        .unwrap();
+    let reply = f
+        .service
+        .post_message(&rfq_id, &maker, message("Revised for volume"), None)
+        .await
+        .unwrap();
+    // The buyer writing last does not change the manufacturer's reply
+    f.service
+        .post_message(&rfq_id, &buyer(&rfq_id), message("Thanks"), None)
+        .await
+        .unwrap();
+
+    let after = view(&f, &id, &principal).await;
+    let quoted = &after.rfqs[0];
+    assert!(matches!(
+        &quoted.quote,
+        Some(RfqEventDto::Quote { revision: 2, .. })
+    ));
+    assert!(matches!(
+        &quoted.last_reply,
+        Some(RfqEventDto::Message { ts, body, .. })
+            if *ts == reply.ts && body == "Revised for volume"
+    ));
+    assert!(after.rfqs[1].quote.is_none() && after.rfqs[1].last_reply.is_none());
+}
@@ -0,0 +1,438 @@
+//! In-process S3 speaking just enough of the REST API for the S3 adapters:
+//! GET, HEAD, PUT and DELETE of objects with `If-Match`/`If-None-Match`, and
+//! ListObjectsV2. Failures can be queued per operation to exercise retries.
+
+use aws_sdk_s3::config::http::{HttpRequest, HttpResponse};
+use aws_sdk_s3::config::{
+    BehaviorVersion, Credentials, HttpClient, Region, RequestChecksumCalculation,
+    ResponseChecksumValidation, RuntimeComponents,
+};
+use aws_sdk_s3::primitives::SdkBody;
+use aws_sdk_s3::Client as S3Client;
+use aws_smithy_runtime_api::client::http::{
+    HttpConnector, HttpConnectorFuture, HttpConnectorSettings, SharedHttpConnector,
+};
+use infrastructure::config::Config;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+pub const PUBLIC_BUCKET: &str = "public";
+pub const PRIVATE_BUCKET: &str = "private";
+
+/// The S3 operations the fake tells apart
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Get,
+    Head,
+    Put,
+    Delete,
+    List,
+}
+
+/// What a queued fault does to the next request of its operation
+#[derive(Debug, Clone)]
+enum Fault {
+    /// Answer with this error instead of serving the request
+    Fail { status: u16, code: String },
+    /// Serve the request, then answer `500 InternalError` as if the response was lost
+    LoseResponse,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// (bucket, key) -> (body, etag)
+    objects: BTreeMap<(String, String), (Vec<u8>, String)>,
+    faults: Vec<(Op, VecDeque<Fault>)>,
+    requests: Vec<(Op, String)>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FakeS3 {
+    state: Arc<Mutex<State>>,
+}
+
+impl FakeS3 {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// SDK client whose requests this fake serves
+    pub fn client(&self) -> S3Client {
+        S3Client::from_conf(
+            aws_sdk_s3::Config::builder()
+                .behavior_version(BehaviorVersion::latest())
+                .region(Region::new("us-east-1"))
+                .credentials_provider(Credentials::new("test", "test", None, None, "fake-s3"))
+                .endpoint_url("http://s3.test")
+                .force_path_style(true)
+                .request_checksum_calculation(RequestChecksumCalculation::WhenRequired)
+                .response_checksum_validation(ResponseChecksumValidation::WhenRequired)
+                .http_client(self.clone())
+                .build(),
+        )
+    }
+
+    /// Adapter config pointing at the fake's buckets
+    pub fn config(&self) -> Arc<Config> {
+        let mut config = Config::from_env();
+        config.public_bucket = PUBLIC_BUCKET.to_string();
+        config.private_bucket = PRIVATE_BUCKET.to_string();
+        config.s3_max_attempts = 3;
+        config.event_fetch_concurrency = 4;
+        Arc::new(config)
+    }
+
+    /// Answer the next `op` request with `status` and error `code`
+    pub fn fail_next(&self, op: Op, status: u16, code: &str) {
+        self.push_fault(
+            op,
+            Fault::Fail {
+                status,
+                code: code.to_string(),
+            },
+        );
+    }
+
+    /// Serve the next `op` request but answer it with a `500`
+    pub fn lose_next_response(&self, op: Op) {
+        self.push_fault(op, Fault::LoseResponse);
+    }
+
+    /// Every request served so far, with the key or prefix it addressed
+    pub fn requests(&self) -> Vec<(Op, String)> {
+        self.lock().requests.clone()
+    }
+
+    pub fn count(&self, op: Op) -> usize {
+        self.lock()
+            .requests
+            .iter()
+            .filter(|(served, _)| *served == op)
+            .count()
+    }
+
+    pub fn object(&self, bucket: &str, key: &str) -> Option<Vec<u8>> {
+        self.lock()
+            .objects
+            .get(&(bucket.to_string(), key.to_string()))
+            .map(|(body, _)| body.clone())
+    }
+
+    pub fn put_object(&self, bucket: &str, key: &str, body: &[u8]) {
+        self.lock().objects.insert(
+            (bucket.to_string(), key.to_string()),
+            (body.to_vec(), etag(body)),
+        );
+    }
+
+    pub fn remove_object(&self, bucket: &str, key: &str) {
+        self.lock()
+            .objects
+            .remove(&(bucket.to_string(), key.to_string()));
+    }
+
+    /// Keys under `prefix` in lexical order
+    pub fn keys(&self, bucket: &str, prefix: &str) -> Vec<String> {
+        self.lock()
+            .objects
+            .keys()
+            .filter(|(b, key)| b == bucket && key.starts_with(prefix))
+            .map(|(_, key)| key.clone())
+            .collect()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap()
+    }
+
+    fn push_fault(&self, op: Op, fault: Fault) {
+        let mut state = self.lock();
+        match state.faults.iter_mut().find(|(queued, _)| *queued == op) {
+            Some((_, faults)) => faults.push_back(fault),
+            None => state.faults.push((op, VecDeque::from([fault]))),
+        }
+    }
+
+    fn handle(&self, request: &HttpRequest) -> HttpResponse {
+        let (path, query) = split_uri(request.uri());
+        let (bucket, key) = match path.split_once('/') {
+            Some((bucket, key)) => (bucket.to_string(), decode(key)),
+            None => (path.clone(), String::new()),
+        };
+        let op = match request.method() {
+            "GET" if key.is_empty() => Op::List,
+            "GET" => Op::Get,
+            "HEAD" => Op::Head,
+            "PUT" => Op::Put,
+            "DELETE" => Op::Delete,
+            other => return error(405, "MethodNotAllowed", other),
+        };
+
+        let fault = {
+            let mut state = self.lock();
+            let target = if op == Op::List {
+                param(&query, "prefix").unwrap_or_default()
+            } else {
+                key.clone()
+            };
+            state.requests.push((op, target));
+            state
+                .faults
+                .iter_mut()
+                .find(|(queued, _)| *queued == op)
+                .and_then(|(_, faults)| faults.pop_front())
+        };
+
+        match fault {
+            Some(Fault::Fail { status, code }) => error(status, &code, &key),
+            Some(Fault::LoseResponse) => {
+                self.serve(op, request, &bucket, &key, &query);
+                error(500, "InternalError", &key)
+            }
+            None => self.serve(op, request, &bucket, &key, &query),
+        }
+    }
+
+    fn serve(
+        &self,
+        op: Op,
+        request: &HttpRequest,
+        bucket: &str,
+        key: &str,
+        query: &[(String, String)],
+    ) -> HttpResponse {
+        let mut state = self.lock();
+        let id = (bucket.to_string(), key.to_string());
+        let current = state.objects.get(&id).cloned();
+        let if_match = request.headers().get("if-match").map(unquote);
+
+        match op {
+            Op::Get | Op::Head => match current {
+                Some((body, etag)) => {
+                    let length = body.len();
+                    let mut response = if op == Op::Get {
+                        HttpResponse::new(200.try_into().unwrap(), SdkBody::from(body))
+                    } else {
+                        HttpResponse::new(200.try_into().unwrap(), SdkBody::empty())
+                    };
+                    response
+                        .headers_mut()
+                        .insert("ETag", format!("\"{}\"", etag));
+                    response
+                        .headers_mut()
+                        .insert("Content-Length", length.to_string());
+                    response
+                }
+                None if op == Op::Head => {
+                    HttpResponse::new(404.try_into().unwrap(), SdkBody::empty())
+                }
+                None => error(404, "NoSuchKey", key),
+            },
+            Op::Put => {
+                let if_none_match = request.headers().get("if-none-match");
+                match (&current, if_match, if_none_match) {
+                    (Some(_), _, Some("*")) => return error(412, "PreconditionFailed", key),
+                    (None, Some(_), _) => return error(404, "NoSuchKey", key),
+                    (Some((_, etag)), Some(expected), _) if *etag != expected => {
+                        return error(412, "PreconditionFailed", key)
+                    }
+                    _ => {}
+                }
+                let body = request.body().bytes().unwrap_or_default().to_vec();
+                let etag = etag(&body);
+                state.objects.insert(id, (body, etag.clone()));
+                let mut response = HttpResponse::new(200.try_into().unwrap(), SdkBody::empty());
+                response
+                    .headers_mut()
+                    .insert("ETag", format!("\"{}\"", etag));
+                response
+            }
+            Op::Delete => {
+                if let (Some((_, etag)), Some(expected)) = (&current, if_match) {
+                    if *etag != expected {
+                        return error(412, "PreconditionFailed", key);
+                    }
+                }
+                state.objects.remove(&id);
+                HttpResponse::new(204.try_into().unwrap(), SdkBody::empty())
+            }
+            Op::List => list(&state, bucket, query),
+        }
+    }
+}
+
+impl HttpConnector for FakeS3 {
+    fn call(&self, request: HttpRequest) -> HttpConnectorFuture {
+        HttpConnectorFuture::ready(Ok(self.handle(&request)))
+    }
+}
+
+impl HttpClient for FakeS3 {
+    fn http_connector(
+        &self,
+        _settings: &HttpConnectorSettings,
+        _components: &RuntimeComponents,
+    ) -> SharedHttpConnector {
+        SharedHttpConnector::new(self.clone())
+    }
+}
+
+/// ListObjectsV2 over the keys of one bucket. The continuation token is the
+/// last key or common prefix returned.
+fn list(state: &State, bucket: &str, query: &[(String, String)]) -> HttpResponse {
+    let prefix = param(query, "prefix").unwrap_or_default();
+    let delimiter = param(query, "delimiter");
+    let start_after = param(query, "start-after");
+    let token = param(query, "continuation-token");
+    let max_keys: usize = param(query, "max-keys")
+        .and_then(|max| max.parse().ok())
+        .unwrap_or(1000);
+
+    // (name, is common prefix), in lexical order and without repeats
+    let mut items: Vec<(String, bool)> = Vec::new();
+    for (_, key) in state.objects.keys().filter(|(b, _)| b == bucket) {
+        let Some(rest) = key.strip_prefix(prefix.as_str()) else {
+            continue;
+        };
+        if start_after.as_ref().is_some_and(|after| key <= after) {
+            continue;
+        }
+        let item = match delimiter
+            .as_deref()
+            .and_then(|d| rest.find(d).map(|i| (d, i)))
+        {
+            Some((d, i)) => (format!("{}{}", prefix, &rest[..i + d.len()]), true),
+            None => (key.clone(), false),
+        };
+        if items.last() != Some(&item) {
+            items.push(item);
+        }
+    }
+    if let Some(token) = &token {
+        items.retain(|(name, _)| name > token);
+    }
+
+    let truncated = items.len() > max_keys;
+    items.truncate(max_keys);
+
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+        <ListBucketResult xmlns=\"http://s3.amazonaws.com/doc/2006-03-01/\">",
+    );
+    xml.push_str(&format!(
+        "<Name>{}</Name><Prefix>{}</Prefix><KeyCount>{}</KeyCount><MaxKeys>{}</MaxKeys>\
+        <IsTruncated>{}</IsTruncated>",
+        escape(bucket),
+        escape(&prefix),
+        items.len(),
+        max_keys,
+        truncated
+    ));
+    if truncated {
+        if let Some((last, _)) = items.last() {
+            xml.push_str(&format!(
+                "<NextContinuationToken>{}</NextContinuationToken>",
+                escape(last)
+            ));
+        }
+    }
+    for (name, is_prefix) in &items {
+        if *is_prefix {
+            xml.push_str(&format!(
+                "<CommonPrefixes><Prefix>{}</Prefix></CommonPrefixes>",
+                escape(name)
+            ));
+        } else {
+            let (body, etag) = &state.objects[&(bucket.to_string(), name.clone())];
+            xml.push_str(&format!(
+                "<Contents><Key>{}</Key><ETag>&quot;{}&quot;</ETag><Size>{}</Size>\
+                <StorageClass>STANDARD</StorageClass></Contents>",
+                escape(name),
+                etag,
+                body.len()
+            ));
+        }
+    }
+    xml.push_str("</ListBucketResult>");
+
+    HttpResponse::new(200.try_into().unwrap(), SdkBody::from(xml))
+}
+
+fn error(status: u16, code: &str, key: &str) -> HttpResponse {
+    let xml = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+        <Error><Code>{}</Code><Message>{} for {}</Message><Key>{}</Key></Error>",
+        code,
+        code,
+        escape(key),
+        escape(key)
+    );
+    HttpResponse::new(status.try_into().unwrap(), SdkBody::from(xml))
+}
+
+fn etag(body: &[u8]) -> String {
+    Sha256::digest(body)[..16]
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn unquote(etag: &str) -> String {
+    etag.trim_matches('"').to_string()
+}
+
+/// Path without the leading `/`, and the decoded query parameters
+fn split_uri(uri: &str) -> (String, Vec<(String, String)>) {
+    let after_scheme = uri.split_once("://").map_or(uri, |(_, rest)| rest);
+    let path_and_query = after_scheme
+        .find('/')
+        .map_or("", |start| &after_scheme[start + 1..]);
+    let (path, query) = path_and_query
+        .split_once('?')
+        .unwrap_or((path_and_query, ""));
+
+    let params = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (decode(name), decode(value))
+        })
+        .collect();
+    (path.to_string(), params)
+}
+
+fn param(query: &[(String, String)], name: &str) -> Option<String> {
+    query
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.clone())
+}
+
+fn decode(encoded: &str) -> String {
+    let bytes = encoded.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap();
+                decoded.push(u8::from_str_radix(hex, 16).unwrap());
+                i += 3;
+            }
+            byte => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).unwrap()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,146 @@
+//! RFQ service wired to the in-memory adapters, shared by the service tests
+#![allow(dead_code)]
+
+pub mod fake_s3;
+
+use application::access::{AccessTokens, Principal};
+use application::dto::{
+    ChangeStatusRequest, ContactDto, CreateRfqRequest, PostMessageRequest, SubmitQuoteRequest,
+};
+use application::services::RfqService;
+use domain::entities::{ManufacturerProfile, RfqMeta};
+use domain::value_objects::RfqId;
+use infrastructure::memory::*;
+use std::sync::Arc;
+
+use application::ports::RfqRepository;
+
+pub const BUYER: &str = "buyer@example.com";
+pub const CONTACT: &str = "sales@acme.example";
+pub const MANUFACTURER: &str = "mfg_acme";
+
+pub struct Fixture {
+    pub service: RfqService,
+    pub rfqs: Arc<InMemoryRfqRepository>,
+    pub manufacturers: Arc<InMemoryManufacturerRepository>,
+    pub emails: Arc<InMemoryEmailService>,
+    pub idempotency: Arc<InMemoryIdempotencyService>,
+    pub access_tokens: Arc<AccessTokens>,
+}
+
+pub fn manufacturer(id: &str, contact_email: &str) -> ManufacturerProfile {
+    ManufacturerProfile {
+        id: id.to_string(),
+        tenant_id: "t1".to_string(),
+        name: "Acme".to_string(),
+        description: None,
+        location: None,
+        categories: Vec::new(),
+        capabilities: None,
+        contact_email: Some(contact_email.to_string()),
+        media: None,
+        offerings: None,
+        updated_at: chrono::Utc::now(),
+    }
+}
+
+impl Fixture {
+    pub fn new() -> Self {
+        let rfqs = Arc::new(InMemoryRfqRepository::new());
+        let manufacturers = Arc::new(InMemoryManufacturerRepository::with_manufacturers(vec![
+            manufacturer(MANUFACTURER, CONTACT),
+        ]));
+        let emails = Arc::new(InMemoryEmailService::new());
+        let idempotency = Arc::new(InMemoryIdempotencyService::new());
+        let access_tokens = Arc::new(AccessTokens::with_random_secret(chrono::Duration::days(1)));
+
+        Self {
+            service: RfqService::new(
+                rfqs.clone(),
+                manufacturers.clone(),
+                emails.clone(),
+                idempotency.clone(),
+                Arc::new(InMemoryImageService::new()),
+                access_tokens.clone(),
+            ),
+            rfqs,
+            manufacturers,
+            emails,
+            idempotency,
+            access_tokens,
+        }
+    }
+
+    /// Open an RFQ from the buyer to the fixture manufacturer
+    pub async fn open_rfq(&self) -> String {
+        self.service
+            .create_rfq(&Principal::Anonymous, new_rfq("t1"), None)
+            .await
+            .unwrap()
+            .id
+    }
+
+    pub async fn meta(&self, rfq_id: &str) -> RfqMeta {
+        self.rfqs
+            .get_rfq_meta(&RfqId::new(rfq_id.to_string()).unwrap())
+            .await
+            .unwrap()
+            .unwrap()
+            .value
+    }
+}
+
+pub fn new_rfq(tenant_id: &str) -> CreateRfqRequest {
+    CreateRfqRequest {
+        tenant_id: tenant_id.to_string(),
+        manufacturer_id: MANUFACTURER.to_string(),
+        buyer: ContactDto {
+            email: BUYER.to_string(),
+            name: Some("Buyer".to_string()),
+        },
+        subject: "Brackets".to_string(),
+        body: "Can you quote 500 brackets?".to_string(),
+        attachments: None,
+    }
+}
+
+pub fn buyer(rfq_id: &str) -> Principal {
+    Principal::Buyer {
+        email: BUYER.to_string(),
+        resource_id: rfq_id.to_string(),
+    }
+}
+
+pub fn maker(rfq_id: &str) -> Principal {
+    Principal::ManufacturerUser {
+        email: CONTACT.to_string(),
+        resource_id: rfq_id.to_string(),
+    }
+}
+
+pub fn message(body: &str) -> PostMessageRequest {
+    PostMessageRequest {
+        body: body.to_string(),
+        attachments: None,
+    }
+}
+
+pub fn status(status: &str) -> ChangeStatusRequest {
+    ChangeStatusRequest {
+        status: status.to_string(),
+        note: None,
+    }
+}
+
+/// One line of 500 brackets at 12.50 USD
+pub fn quote() -> SubmitQuoteRequest {
+    serde_json::from_value(serde_json::json!({
+        "line_items": [{
+            "part": "BRKT-104",
+            "quantity": { "value": "500", "unit": "pcs" },
+            "unit_price": { "amount": "12.50", "currency": "USD" }
+        }],
+        "valid_until": "2099-12-31"
+    }))
+    .unwrap()
+}
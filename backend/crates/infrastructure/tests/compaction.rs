@@ -0,0 +1,252 @@
+mod common;
+
+use application::ports::{EventPage, EventQuery, RfqRepository};
+use chrono::{DateTime, Duration, Utc};
+use common::fake_s3::{FakeS3, Op, PRIVATE_BUCKET};
+use domain::entities::RfqIndex;
+use domain::events::{EventAuthor, RfqEvent};
+use domain::value_objects::RfqId;
+use infrastructure::s3::{CompactionPolicy, S3RfqRepository};
+
+const RFQ: &str = "r_compact";
+const EVENTS: &str = "rfq/r_compact/events/";
+
+fn rfq_id() -> RfqId {
+    RfqId::new(RFQ.to_string()).unwrap()
+}
+
+fn at(minutes: i64) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339("2026-03-01T09:00:00Z")
+        .unwrap()
+        .with_timezone(&Utc)
+        + Duration::minutes(minutes)
+}
+
+fn message(minutes: i64) -> RfqEvent {
+    let mut event = RfqEvent::new_message(
+        RFQ.to_string(),
+        EventAuthor::Buyer,
+        format!("Message at minute {}", minutes),
+    );
+    if let RfqEvent::Message(message) = &mut event {
+        message.base.ts = at(minutes);
+    }
+    event
+}
+
+/// Compact events strictly older than `minutes`, however few there are
+fn older_than(minutes: i64) -> CompactionPolicy {
+    CompactionPolicy {
+        older_than: at(minutes),
+        min_events: 1,
+        max_events: 100,
+    }
+}
+
+/// An RFQ on the fake S3 with one message per given minute
+async fn thread(minutes: impl IntoIterator<Item = i64>) -> (FakeS3, S3RfqRepository) {
+    let s3 = FakeS3::new();
+    let repository = S3RfqRepository::new(s3.client(), s3.config());
+    repository
+        .save_rfq_index(&rfq_id(), &RfqIndex::new(at(0)), None)
+        .await
+        .unwrap();
+    for minute in minutes {
+        repository.save_rfq_event(&message(minute)).await.unwrap();
+    }
+    (s3, repository)
+}
+
+async fn read(repository: &S3RfqRepository, query: EventQuery) -> EventPage {
+    repository.list_rfq_events(&rfq_id(), &query).await.unwrap()
+}
+
+async fn everything(repository: &S3RfqRepository) -> Vec<RfqEvent> {
+    let page = read(
+        repository,
+        EventQuery {
+            limit: 200,
+            ..Default::default()
+        },
+    )
+    .await;
+    assert!(!page.has_more);
+    page.events
+}
+
+fn positions(events: &[RfqEvent]) -> Vec<String> {
+    events.iter().map(RfqEvent::sort_key).collect()
+}
+
+/// Sort keys of every page read oldest first, `limit` at a time
+async fn forwards(repository: &S3RfqRepository, limit: u32) -> Vec<Vec<String>> {
+    let mut pages = Vec::new();
+    let mut after = None;
+    loop {
+        let page = read(
+            repository,
+            EventQuery {
+                after: after.clone(),
+                limit,
+                ..Default::default()
+            },
+        )
+        .await;
+        pages.push(positions(&page.events));
+        if !page.has_more {
+            return pages;
+        }
+        after = page.last_position;
+    }
+}
+
+/// Sort keys of every page read newest first from `before`, `limit` at a time
+async fn backwards(repository: &S3RfqRepository, before: String, limit: u32) -> Vec<Vec<String>> {
+    let mut pages = Vec::new();
+    let mut before = Some(before);
+    loop {
+        let page = read(
+            repository,
+            EventQuery {
+                before: before.clone(),
+                limit,
+                ..Default::default()
+            },
+        )
+        .await;
+        pages.push(positions(&page.events));
+        if !page.has_more {
+            return pages;
+        }
+        before = page.first_position;
+    }
+}
+
+#[tokio::test]
+async fn compaction_leaves_the_events_unchanged() {
+    let (s3, repository) = thread(1..=10).await;
+    let before = everything(&repository).await;
+
+    let report = repository
+        .compact_events(&rfq_id(), &older_than(8))
+        .await
+        .unwrap();
+
+    let segment = report.segment.expect("a segment");
+    assert_eq!(segment.count, 7);
+    assert_eq!(segment.first_position, before[0].sort_key());
+    assert_eq!(segment.last_position, before[6].sort_key());
+    assert_eq!(report.deleted_events, 7);
+    assert!(s3
+        .object(PRIVATE_BUCKET, &format!("rfq/{}/{}", RFQ, segment.key))
+        .is_some());
+    assert_eq!(s3.keys(PRIVATE_BUCKET, EVENTS).len(), 3);
+
+    let index = repository.get_rfq_index(&rfq_id()).await.unwrap().unwrap();
+    assert_eq!(index.value.segments, vec![segment]);
+    assert_eq!(everything(&repository).await, before);
+}
+
+#[tokio::test]
+async fn pages_across_segments_and_tail_stay_ordered() {
+    let (_s3, repository) = thread(1..=12).await;
+    let all = positions(&everything(&repository).await);
+
+    for minutes in [4, 8] {
+        repository
+            .compact_events(&rfq_id(), &older_than(minutes))
+            .await
+            .unwrap();
+    }
+    let index = repository.get_rfq_index(&rfq_id()).await.unwrap().unwrap();
+    assert_eq!(index.value.segments.len(), 2);
+    assert_eq!(positions(&everything(&repository).await), all);
+
+    for limit in [1, 2, 5, 12] {
+        let pages = forwards(&repository, limit).await;
+        assert!(pages.iter().all(|page| page.len() <= limit as usize));
+        assert_eq!(pages.concat(), all, "forwards by {}", limit);
+
+        let mut pages = backwards(&repository, "9999".to_string(), limit).await;
+        pages.reverse();
+        assert_eq!(pages.concat(), all, "backwards by {}", limit);
+    }
+
+    let since = read(
+        &repository,
+        EventQuery {
+            since: Some(at(3)),
+            before: Some(all[9].clone()),
+            limit: 3,
+            ..Default::default()
+        },
+    )
+    .await;
+    assert_eq!(positions(&since.events), all[6..9]);
+    assert!(since.has_more);
+}
+
+#[tokio::test]
+async fn events_written_below_a_segment_are_kept() {
+    let (s3, repository) = thread(1..=6).await;
+    repository
+        .compact_events(&rfq_id(), &older_than(5))
+        .await
+        .unwrap();
+
+    // Saved while the run was listing, so no segment holds it
+    let mut late = message(2);
+    if let RfqEvent::Message(message) = &mut late {
+        message.base.ts += Duration::seconds(30);
+    }
+    repository.save_rfq_event(&late).await.unwrap();
+    let all = positions(&everything(&repository).await);
+    assert_eq!(all.len(), 7);
+    assert_eq!(all[2], late.sort_key());
+
+    let report = repository
+        .compact_events(&rfq_id(), &older_than(5))
+        .await
+        .unwrap();
+    assert!(report.segment.is_none());
+    assert_eq!(report.deleted_events, 0);
+    assert!(s3
+        .object(
+            PRIVATE_BUCKET,
+            &format!("{}{}.json", EVENTS, late.sort_key())
+        )
+        .is_some());
+    assert_eq!(positions(&everything(&repository).await), all);
+}
+
+#[tokio::test]
+async fn a_crash_before_deleting_does_not_duplicate_events() {
+    let (s3, repository) = thread(1..=6).await;
+    let before = everything(&repository).await;
+
+    // Every delete fails as if the run died right after recording the segment
+    for _ in 0..4 {
+        s3.fail_next(Op::Delete, 403, "AccessDenied");
+    }
+    let report = repository
+        .compact_events(&rfq_id(), &older_than(5))
+        .await
+        .unwrap();
+    assert_eq!(report.segment.map(|segment| segment.count), Some(4));
+    assert_eq!(report.deleted_events, 0);
+    assert_eq!(s3.keys(PRIVATE_BUCKET, EVENTS).len(), 6);
+    assert_eq!(everything(&repository).await, before);
+    assert_eq!(forwards(&repository, 4).await.concat(), positions(&before));
+
+    // The next run deletes what the segment holds and writes nothing new
+    let report = repository
+        .compact_events(&rfq_id(), &older_than(5))
+        .await
+        .unwrap();
+    assert!(report.segment.is_none());
+    assert_eq!(report.deleted_events, 4);
+    assert_eq!(s3.keys(PRIVATE_BUCKET, EVENTS).len(), 2);
+    let index = repository.get_rfq_index(&rfq_id()).await.unwrap().unwrap();
+    assert_eq!(index.value.segments.len(), 1);
+    assert_eq!(everything(&repository).await, before);
+}
@@ -0,0 +1,158 @@
+mod common;
+
+use application::dto::{ListEventsQuery, ListEventsResponse, RfqEventDto};
+use common::*;
+use domain::error::DomainError;
+
+fn ids(response: &ListEventsResponse) -> Vec<String> {
+    response
+        .items
+        .iter()
+        .map(|item| match item {
+            RfqEventDto::Message { id, .. }
+            | RfqEventDto::Status { id, .. }
+            | RfqEventDto::Attachment { id, .. }
+            | RfqEventDto::Quote { id, .. } => id.clone(),
+        })
+        .collect()
+}
+
+async fn list(f: &Fixture, id: &str, query: ListEventsQuery) -> ListEventsResponse {
+    f.service.list_events(id, &buyer(id), query).await.unwrap()
+}
+
+/// An RFQ with its creation events followed by `messages` replies
+async fn thread(f: &Fixture, messages: usize) -> String {
+    let id = f.open_rfq().await;
+    for n in 0..messages {
+        let principal = if n % 2 == 0 { maker(&id) } else { buyer(&id) };
+        f.service
+            .post_message(&id, &principal, message(&format!("Reply {}", n)), None)
+            .await
+            .unwrap();
+    }
+    id
+}
+
+#[tokio::test]
+async fn a_thread_pages_forwards_and_backwards() {
+    let f = Fixture::new();
+    let id = thread(&f, 7).await;
+    let all = ids(&list(&f, &id, ListEventsQuery::default()).await);
+    assert_eq!(all.len(), f.rfqs.events_for(&id).len());
+
+    // Forwards from the start, following `next_cursor`
+    let mut pages = Vec::new();
+    let mut cursor = None;
+    loop {
+        let page = list(
+            &f,
+            &id,
+            ListEventsQuery {
+                cursor: cursor.clone(),
+                limit: Some(3),
+                ..Default::default()
+            },
+        )
+        .await;
+        assert!(page.items.len() <= 3);
+        pages.push(page);
+        let last = pages.last().unwrap();
+        if !last.has_more {
+            break;
+        }
+        cursor = last.next_cursor.clone();
+    }
+    assert_eq!(
+        pages.iter().flat_map(ids).collect::<Vec<_>>(),
+        all,
+        "forwards"
+    );
+
+    // Backwards from the last page, following `prev_cursor`
+    let last = pages.pop().unwrap();
+    let mut backwards = vec![ids(&last)];
+    let mut before = last.prev_cursor;
+    loop {
+        let page = list(
+            &f,
+            &id,
+            ListEventsQuery {
+                before: before.clone(),
+                limit: Some(3),
+                ..Default::default()
+            },
+        )
+        .await;
+        assert!(page.items.len() <= 3);
+        backwards.push(ids(&page));
+        if !page.has_more {
+            break;
+        }
+        before = page.prev_cursor;
+    }
+    backwards.reverse();
+    assert_eq!(backwards.concat(), all, "backwards");
+}
+
+#[tokio::test]
+async fn polling_past_the_end_keeps_the_cursor() {
+    let f = Fixture::new();
+    let id = thread(&f, 2).await;
+    let page = list(&f, &id, ListEventsQuery::default()).await;
+    let cursor = page.next_cursor.clone();
+
+    let empty = list(
+        &f,
+        &id,
+        ListEventsQuery {
+            cursor: cursor.clone(),
+            ..Default::default()
+        },
+    )
+    .await;
+    assert!(empty.items.is_empty());
+    assert!(!empty.has_more);
+    assert_eq!(empty.next_cursor, cursor);
+
+    f.service
+        .post_message(&id, &maker(&id), message("Anything else?"), None)
+        .await
+        .unwrap();
+    let fresh = list(
+        &f,
+        &id,
+        ListEventsQuery {
+            cursor: empty.next_cursor,
+            ..Default::default()
+        },
+    )
+    .await;
+    assert_eq!(fresh.items.len(), 1);
+}
+
+#[tokio::test]
+async fn bad_event_queries_are_rejected() {
+    let f = Fixture::new();
+    let id = thread(&f, 1).await;
+
+    for query in [
+        ListEventsQuery {
+            since: Some("yesterday".to_string()),
+            ..Default::default()
+        },
+        ListEventsQuery {
+            cursor: Some("not a cursor".to_string()),
+            ..Default::default()
+        },
+        ListEventsQuery {
+            before: Some("%%%".to_string()),
+            ..Default::default()
+        },
+    ] {
+        assert!(matches!(
+            f.service.list_events(&id, &buyer(&id), query).await,
+            Err(DomainError::ValidationFailed(_))
+        ));
+    }
+}
@@ -0,0 +1,297 @@
+use application::ports::{EventQuery, RfqRepository};
+use chrono::{DateTime, Duration, Utc};
+use domain::entities::RfqIndex;
+use domain::error::DomainError;
+use domain::events::{EventAuthor, RfqEvent};
+use domain::value_objects::RfqId;
+use infrastructure::config::Config;
+use infrastructure::fs::{FsRfqRepository, FsStore};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+const BUCKET: &str = "private";
+const KEY: &str = "rfq/r_1/meta.json";
+
+/// A fresh directory under the system temp dir, removed again on drop
+struct TempRoot(PathBuf);
+
+impl TempRoot {
+    fn new() -> Self {
+        let path =
+            std::env::temp_dir().join(format!("fs-store-test-{}", uuid::Uuid::new_v4().simple()));
+        std::fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    fn store(&self) -> FsStore {
+        FsStore::new(&self.0)
+    }
+}
+
+impl Drop for TempRoot {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+async fn version(store: &FsStore) -> Option<String> {
+    store
+        .read_json_versioned::<Value>(BUCKET, KEY)
+        .await
+        .unwrap()
+        .map(|versioned| versioned.version)
+}
+
+#[tokio::test]
+async fn objects_round_trip() {
+    let root = TempRoot::new();
+    let store = root.store();
+    assert!(store
+        .read_json::<Value>(BUCKET, KEY)
+        .await
+        .unwrap()
+        .is_none());
+    assert!(store.size(BUCKET, KEY).await.unwrap().is_none());
+
+    store
+        .write_json(BUCKET, KEY, &json!({ "status": "open" }))
+        .await
+        .unwrap();
+    assert_eq!(
+        store.read_json::<Value>(BUCKET, KEY).await.unwrap(),
+        Some(json!({ "status": "open" }))
+    );
+    assert!(store.size(BUCKET, KEY).await.unwrap().unwrap() > 0);
+    let first = version(&store).await.unwrap();
+
+    store
+        .write_json(BUCKET, KEY, &json!({ "status": "closed" }))
+        .await
+        .unwrap();
+    assert_ne!(version(&store).await.unwrap(), first);
+
+    // A create never replaces what is there
+    assert!(!store
+        .create_json(BUCKET, KEY, &json!({ "status": "archived" }))
+        .await
+        .unwrap());
+    assert!(store
+        .create_json(BUCKET, "rfq/r_2/meta.json", &json!({}))
+        .await
+        .unwrap());
+    assert_eq!(
+        store.read_json::<Value>(BUCKET, KEY).await.unwrap(),
+        Some(json!({ "status": "closed" }))
+    );
+
+    store.delete(BUCKET, KEY).await.unwrap();
+    store.delete(BUCKET, KEY).await.unwrap();
+    assert!(version(&store).await.is_none());
+}
+
+#[tokio::test]
+async fn keys_outside_the_bucket_are_refused() {
+    let root = TempRoot::new();
+    let store = root.store();
+
+    for key in ["", "../escape.json", "/etc/passwd", "rfq/../../meta.json"] {
+        assert!(
+            matches!(
+                store.write_json(BUCKET, key, &json!({})).await,
+                Err(DomainError::Internal(_))
+            ),
+            "{:?}",
+            key
+        );
+    }
+}
+
+#[tokio::test]
+async fn conditional_writes_fail_on_a_stale_version() {
+    let root = TempRoot::new();
+    let store = root.store();
+
+    let first = store
+        .write_json_if(BUCKET, KEY, &json!(1), None)
+        .await
+        .unwrap();
+    assert_eq!(version(&store).await.unwrap(), first);
+    assert!(matches!(
+        store.write_json_if(BUCKET, KEY, &json!(2), None).await,
+        Err(DomainError::Conflict(_))
+    ));
+
+    let second = store
+        .write_json_if(BUCKET, KEY, &json!(2), Some(&first))
+        .await
+        .unwrap();
+    assert!(matches!(
+        store
+            .write_json_if(BUCKET, KEY, &json!(3), Some(&first))
+            .await,
+        Err(DomainError::Conflict(_))
+    ));
+    assert!(matches!(
+        store
+            .write_json_if(BUCKET, "rfq/r_2/meta.json", &json!(1), Some(&first))
+            .await,
+        Err(DomainError::Conflict(_))
+    ));
+    assert_eq!(version(&store).await.unwrap(), second);
+    assert_eq!(
+        store.read_json::<Value>(BUCKET, KEY).await.unwrap(),
+        Some(json!(2))
+    );
+
+    // A conditional delete leaves a replaced object alone
+    store.delete_if(BUCKET, KEY, &first).await.unwrap();
+    assert_eq!(version(&store).await.unwrap(), second);
+    store.delete_if(BUCKET, KEY, &second).await.unwrap();
+    assert!(version(&store).await.is_none());
+}
+
+#[tokio::test]
+async fn concurrent_conditional_writes_have_one_winner() {
+    let root = TempRoot::new();
+    let store = root.store();
+    let base = store
+        .write_json_if(BUCKET, KEY, &json!(0), None)
+        .await
+        .unwrap();
+
+    let writes = (1..=8).map(|n| {
+        let store = store.clone();
+        let base = base.clone();
+        tokio::spawn(async move {
+            store
+                .write_json_if(BUCKET, KEY, &json!(n), Some(&base))
+                .await
+        })
+    });
+    let results = futures::future::join_all(writes).await;
+
+    let won: Vec<_> = results
+        .into_iter()
+        .map(|result| result.unwrap())
+        .filter(Result::is_ok)
+        .collect();
+    assert_eq!(won.len(), 1);
+}
+
+#[tokio::test]
+async fn listings_are_in_key_order() {
+    let root = TempRoot::new();
+    let store = root.store();
+    for key in [
+        "rfq/r_1/events/0003.json",
+        "rfq/r_1/events/0001.json",
+        "rfq/r_1/events/0002.json",
+        "rfq/r_1/events/nested/0004.json",
+        "rfq/r_2/meta.json",
+    ] {
+        store.write_json(BUCKET, key, &json!({})).await.unwrap();
+    }
+    // Only objects count, not their leftovers
+    std::fs::write(root.0.join(BUCKET).join("rfq/r_1/events/0005.tmp-1"), b"").unwrap();
+
+    assert_eq!(
+        store.list_keys(BUCKET, "rfq/r_1/events/").await.unwrap(),
+        [
+            "rfq/r_1/events/0001.json",
+            "rfq/r_1/events/0002.json",
+            "rfq/r_1/events/0003.json",
+        ]
+    );
+    assert_eq!(
+        store.list_dirs(BUCKET, "rfq/").await.unwrap(),
+        ["r_1", "r_2"]
+    );
+    assert!(store
+        .list_keys(BUCKET, "rfq/r_9/events/")
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+fn at(minutes: i64) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339("2026-03-01T09:00:00Z")
+        .unwrap()
+        .with_timezone(&Utc)
+        + Duration::minutes(minutes)
+}
+
+fn message(rfq_id: &RfqId, minutes: i64) -> RfqEvent {
+    let mut event = RfqEvent::new_message(
+        rfq_id.as_str().to_string(),
+        EventAuthor::Buyer,
+        format!("Message at minute {}", minutes),
+    );
+    if let RfqEvent::Message(message) = &mut event {
+        message.base.ts = at(minutes);
+    }
+    event
+}
+
+fn repository(root: &TempRoot) -> FsRfqRepository {
+    let mut config = Config::from_env();
+    config.private_bucket = BUCKET.to_string();
+    FsRfqRepository::new(root.store(), Arc::new(config))
+}
+
+#[tokio::test]
+async fn rfq_documents_follow_the_s3_contract() {
+    let root = TempRoot::new();
+    let repository = repository(&root);
+    let id = RfqId::new("r_fs".to_string()).unwrap();
+
+    let created = repository
+        .save_rfq_index(&id, &RfqIndex::new(at(0)), None)
+        .await
+        .unwrap();
+    assert!(matches!(
+        repository
+            .save_rfq_index(&id, &RfqIndex::new(at(1)), None)
+            .await,
+        Err(DomainError::Conflict(_))
+    ));
+    let stored = repository.get_rfq_index(&id).await.unwrap().unwrap();
+    assert_eq!(stored.version, created);
+    assert_eq!(stored.value, RfqIndex::new(at(0)));
+
+    // Saved out of order, read back oldest first
+    for minute in [3, 1, 4, 2, 5] {
+        repository
+            .save_rfq_event(&message(&id, minute))
+            .await
+            .unwrap();
+    }
+
+    let mut pages = Vec::new();
+    let mut after = None;
+    loop {
+        let page = repository
+            .list_rfq_events(
+                &id,
+                &EventQuery {
+                    after: after.clone(),
+                    limit: 2,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        pages.push(
+            page.events
+                .iter()
+                .map(|event| event.timestamp())
+                .collect::<Vec<_>>(),
+        );
+        if !page.has_more {
+            break;
+        }
+        after = page.last_position;
+    }
+    assert_eq!(pages.len(), 3);
+    assert_eq!(pages.concat(), (1..=5).map(at).collect::<Vec<_>>());
+}
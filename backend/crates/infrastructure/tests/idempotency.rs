@@ -0,0 +1,278 @@
+mod common;
+
+use application::ports::{IdempotencyClaim, IdempotencyService};
+use common::fake_s3::{FakeS3, Op, PRIVATE_BUCKET};
+use common::*;
+use domain::entities::RfqStatus;
+use domain::error::DomainError;
+use domain::events::RfqEvent;
+use infrastructure::memory::InMemoryIdempotencyService;
+use infrastructure::s3::S3IdempotencyService;
+use std::sync::Arc;
+
+const KEY: &str = "POST /rfqs/r_1/messages|buyer|k1";
+
+fn messages(f: &Fixture, rfq_id: &str) -> Vec<String> {
+    f.rfqs
+        .events_for(rfq_id)
+        .into_iter()
+        .filter_map(|event| match event {
+            RfqEvent::Message(e) => Some(e.body),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn a_retried_request_is_replayed_once() {
+    let f = Fixture::new();
+    let id = f.open_rfq().await;
+
+    let first = f
+        .service
+        .post_message(&id, &buyer(&id), message("Any update?"), Some("k1"))
+        .await
+        .unwrap();
+    let retried = f
+        .service
+        .post_message(&id, &buyer(&id), message("Any update?"), Some("k1"))
+        .await
+        .unwrap();
+
+    assert_eq!(retried.ts, first.ts);
+    assert_eq!(
+        messages(&f, &id),
+        ["Can you quote 500 brackets?", "Any update?"]
+    );
+}
+
+#[tokio::test]
+async fn the_same_key_from_two_callers_does_not_collide() {
+    let f = Fixture::new();
+    let id = f.open_rfq().await;
+
+    let from_buyer = f
+        .service
+        .post_message(&id, &buyer(&id), message("Hello"), Some("k1"))
+        .await
+        .unwrap();
+    // Same key and body from the other party is a request of its own
+    let from_maker = f
+        .service
+        .post_message(&id, &maker(&id), message("Hello"), Some("k1"))
+        .await
+        .unwrap();
+
+    assert_ne!(from_maker.ts, from_buyer.ts);
+    assert_eq!(
+        messages(&f, &id),
+        ["Can you quote 500 brackets?", "Hello", "Hello"]
+    );
+
+    // Another RFQ's buyer reusing the key is not answered from this one
+    let other = f.open_rfq().await;
+    let elsewhere = f
+        .service
+        .post_message(&other, &buyer(&other), message("Hello"), Some("k1"))
+        .await
+        .unwrap();
+    assert_ne!(elsewhere.ts, from_buyer.ts);
+    assert_eq!(messages(&f, &other).len(), 2);
+}
+
+#[tokio::test]
+async fn the_same_key_on_two_routes_does_not_collide() {
+    let f = Fixture::new();
+    let id = f.open_rfq().await;
+
+    f.service
+        .post_message(&id, &buyer(&id), message("Closing this"), Some("k1"))
+        .await
+        .unwrap();
+    let closed = f
+        .service
+        .change_status(&id, &buyer(&id), status("closed"), Some("k1"))
+        .await
+        .unwrap();
+
+    assert_eq!(closed.status, RfqStatus::Closed);
+    assert_eq!(f.meta(&id).await.status, RfqStatus::Closed);
+    assert_eq!(messages(&f, &id).len(), 2);
+}
+
+#[tokio::test]
+async fn a_reused_key_with_another_body_is_a_conflict() {
+    let f = Fixture::new();
+    let id = f.open_rfq().await;
+
+    f.service
+        .post_message(&id, &buyer(&id), message("First"), Some("k1"))
+        .await
+        .unwrap();
+    assert!(matches!(
+        f.service
+            .post_message(&id, &buyer(&id), message("Second"), Some("k1"))
+            .await,
+        Err(DomainError::Conflict(_))
+    ));
+    f.service
+        .change_status(&id, &buyer(&id), status("closed"), Some("k2"))
+        .await
+        .unwrap();
+    assert!(matches!(
+        f.service
+            .change_status(&id, &buyer(&id), status("archived"), Some("k2"))
+            .await,
+        Err(DomainError::Conflict(_))
+    ));
+
+    assert_eq!(messages(&f, &id), ["Can you quote 500 brackets?", "First"]);
+    assert_eq!(f.meta(&id).await.status, RfqStatus::Closed);
+}
+
+#[tokio::test]
+async fn a_failed_request_can_be_retried_with_its_key() {
+    let f = Fixture::new();
+    let id = f.open_rfq().await;
+
+    f.rfqs.fail_event_writes(true);
+    assert!(f
+        .service
+        .post_message(&id, &buyer(&id), message("Retry me"), Some("k1"))
+        .await
+        .is_err());
+    f.rfqs.fail_event_writes(false);
+
+    f.service
+        .post_message(&id, &buyer(&id), message("Retry me"), Some("k1"))
+        .await
+        .unwrap();
+    assert_eq!(
+        messages(&f, &id),
+        ["Can you quote 500 brackets?", "Retry me"]
+    );
+}
+
+fn acquired(claim: IdempotencyClaim) -> String {
+    match claim {
+        IdempotencyClaim::Acquired(version) => version,
+        IdempotencyClaim::Replay(response) => panic!("unexpected replay of {}", response),
+    }
+}
+
+/// An S3 service whose in-progress markers go stale after `lock_timeout` seconds
+fn s3_service(s3: &FakeS3, lock_timeout: i64) -> S3IdempotencyService {
+    let mut config = Arc::into_inner(s3.config()).unwrap();
+    config.idempotency_lock_timeout_seconds = lock_timeout;
+    S3IdempotencyService::new(s3.client(), Arc::new(config))
+}
+
+#[tokio::test]
+async fn an_in_progress_claim_is_only_taken_over_once_stale() {
+    let fresh = InMemoryIdempotencyService::new();
+    acquired(fresh.claim_idempotency(KEY, "h1").await.unwrap());
+    assert!(matches!(
+        fresh.claim_idempotency(KEY, "h1").await,
+        Err(DomainError::Conflict(_))
+    ));
+
+    let stale = InMemoryIdempotencyService::with_lock_timeout(chrono::Duration::zero());
+    let first = acquired(stale.claim_idempotency(KEY, "h1").await.unwrap());
+    let second = acquired(stale.claim_idempotency(KEY, "h1").await.unwrap());
+    assert_ne!(first, second);
+    assert_eq!(stale.record(KEY).unwrap().version, second);
+}
+
+#[tokio::test]
+async fn a_store_after_a_lost_claim_is_a_conflict() {
+    let service = InMemoryIdempotencyService::with_lock_timeout(chrono::Duration::zero());
+    let lost = acquired(service.claim_idempotency(KEY, "h1").await.unwrap());
+    let winner = acquired(service.claim_idempotency(KEY, "h1").await.unwrap());
+
+    assert!(matches!(
+        service.store_idempotency(KEY, &lost, "h1", "late").await,
+        Err(DomainError::Conflict(_))
+    ));
+    service
+        .store_idempotency(KEY, &winner, "h1", "first")
+        .await
+        .unwrap();
+    assert!(matches!(
+        service.claim_idempotency(KEY, "h1").await.unwrap(),
+        IdempotencyClaim::Replay(response) if response == "first"
+    ));
+}
+
+#[tokio::test]
+async fn a_release_leaves_a_taken_over_marker_alone() {
+    let service = InMemoryIdempotencyService::with_lock_timeout(chrono::Duration::zero());
+    let lost = acquired(service.claim_idempotency(KEY, "h1").await.unwrap());
+    let winner = acquired(service.claim_idempotency(KEY, "h1").await.unwrap());
+
+    service.release_idempotency(KEY, &lost).await.unwrap();
+    assert_eq!(service.record(KEY).unwrap().version, winner);
+
+    service.release_idempotency(KEY, &winner).await.unwrap();
+    assert!(service.record(KEY).is_none());
+}
+
+#[tokio::test]
+async fn an_s3_claim_whose_response_was_lost_is_still_acquired() {
+    let s3 = FakeS3::new();
+    let service = s3_service(&s3, 60);
+
+    s3.lose_next_response(Op::Put);
+    let claim = acquired(service.claim_idempotency(KEY, "h1").await.unwrap());
+    assert_eq!(s3.count(Op::Put), 1);
+
+    // The claim holds the marker's ETag, so the response can be stored with it
+    service
+        .store_idempotency(KEY, &claim, "h1", "first")
+        .await
+        .unwrap();
+    assert!(matches!(
+        service.claim_idempotency(KEY, "h1").await.unwrap(),
+        IdempotencyClaim::Replay(response) if response == "first"
+    ));
+}
+
+#[tokio::test]
+async fn an_s3_claim_that_lost_the_race_is_not_acquired() {
+    let s3 = FakeS3::new();
+    let service = s3_service(&s3, 60);
+    acquired(service.claim_idempotency(KEY, "h1").await.unwrap());
+
+    // The retried conditional put finds the other request's marker
+    s3.fail_next(Op::Put, 500, "InternalError");
+    assert!(matches!(
+        service.claim_idempotency(KEY, "h1").await,
+        Err(DomainError::Conflict(_))
+    ));
+    assert_eq!(s3.count(Op::Put), 2);
+    assert_eq!(s3.keys(PRIVATE_BUCKET, "idem/").len(), 1);
+}
+
+#[tokio::test]
+async fn a_stale_s3_marker_is_taken_over_and_kept_on_release() {
+    let s3 = FakeS3::new();
+    let service = s3_service(&s3, 0);
+    let lost = acquired(service.claim_idempotency(KEY, "h1").await.unwrap());
+    let winner = acquired(service.claim_idempotency(KEY, "h1").await.unwrap());
+    assert_ne!(lost, winner);
+
+    assert!(matches!(
+        service.store_idempotency(KEY, &lost, "h1", "late").await,
+        Err(DomainError::Conflict(_))
+    ));
+    service.release_idempotency(KEY, &lost).await.unwrap();
+    assert_eq!(s3.keys(PRIVATE_BUCKET, "idem/").len(), 1);
+
+    service
+        .store_idempotency(KEY, &winner, "h1", "first")
+        .await
+        .unwrap();
+    assert!(matches!(
+        service.claim_idempotency(KEY, "h1").await.unwrap(),
+        IdempotencyClaim::Replay(response) if response == "first"
+    ));
+}
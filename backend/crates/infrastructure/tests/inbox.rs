@@ -0,0 +1,502 @@
+mod common;
+
+use application::access::Principal;
+use application::dto::{InboxQuery, InboxResponse};
+use application::ports::{InboxPageQuery, RfqRepository};
+use chrono::{DateTime, Duration, Utc};
+use common::fake_s3::{FakeS3, Op, PRIVATE_BUCKET};
+use common::*;
+use domain::entities::{Contact, RfqInboxEntry, RfqStatus};
+use domain::error::DomainError;
+use domain::value_objects::ManufacturerId;
+use infrastructure::s3::S3RfqRepository;
+
+fn inbox_reader() -> Principal {
+    Principal::ManufacturerUser {
+        email: CONTACT.to_string(),
+        resource_id: MANUFACTURER.to_string(),
+    }
+}
+
+async fn list(f: &Fixture, query: InboxQuery) -> InboxResponse {
+    f.service
+        .list_inbox(MANUFACTURER, query, &inbox_reader())
+        .await
+        .unwrap()
+}
+
+fn ids(response: &InboxResponse) -> Vec<String> {
+    response
+        .items
+        .iter()
+        .map(|item| item.rfq_id.clone())
+        .collect()
+}
+
+/// Every page of the inbox, following cursors
+async fn walk(f: &Fixture, query: InboxQuery) -> Vec<Vec<String>> {
+    let mut pages = Vec::new();
+    let mut cursor = None;
+    loop {
+        let page = list(
+            f,
+            InboxQuery {
+                cursor,
+                ..query.clone()
+            },
+        )
+        .await;
+        pages.push(ids(&page));
+        if !page.has_more {
+            return pages;
+        }
+        cursor = page.next_cursor;
+    }
+}
+
+#[tokio::test]
+async fn inbox_filters_by_status_and_unread() {
+    let f = Fixture::new();
+    let a = f.open_rfq().await;
+    let b = f.open_rfq().await;
+    let c = f.open_rfq().await;
+    f.service
+        .change_status(&b, &buyer(&b), status("closed"), None)
+        .await
+        .unwrap();
+    f.service
+        .mark_inbox_read(MANUFACTURER, &c, &inbox_reader())
+        .await
+        .unwrap();
+
+    let all = list(&f, InboxQuery::default()).await;
+    assert_eq!(ids(&all), [b.as_str(), c.as_str(), a.as_str()]);
+    assert_eq!(all.unread_count, 2);
+    assert!(!all.has_more);
+
+    let filtered = |status: Option<&str>, unread: Option<bool>| InboxQuery {
+        status: status.map(str::to_string),
+        unread,
+        ..InboxQuery::default()
+    };
+    assert_eq!(
+        ids(&list(&f, filtered(Some("closed"), None)).await),
+        [b.as_str()]
+    );
+    assert_eq!(
+        ids(&list(&f, filtered(Some("open"), None)).await),
+        [c.as_str(), a.as_str()]
+    );
+    assert_eq!(
+        ids(&list(&f, filtered(Some("open, closed"), None)).await),
+        [b.as_str(), c.as_str(), a.as_str()]
+    );
+    assert_eq!(
+        ids(&list(&f, filtered(None, Some(true))).await),
+        [b.as_str(), a.as_str()]
+    );
+    assert_eq!(
+        ids(&list(&f, filtered(None, Some(false))).await),
+        [c.as_str()]
+    );
+    assert!(list(&f, filtered(Some("awarded"), None))
+        .await
+        .items
+        .is_empty());
+
+    // Filters never change the unread count
+    assert_eq!(
+        list(&f, filtered(Some("closed"), Some(false)))
+            .await
+            .unread_count,
+        2
+    );
+}
+
+#[tokio::test]
+async fn the_manufacturers_own_activity_keeps_an_rfq_read() {
+    let f = Fixture::new();
+    let a = f.open_rfq().await;
+    let b = f.open_rfq().await;
+    f.service
+        .mark_inbox_read(MANUFACTURER, &a, &inbox_reader())
+        .await
+        .unwrap();
+
+    f.service
+        .post_message(&a, &maker(&a), message("We can do that"), None)
+        .await
+        .unwrap();
+    let inbox = list(&f, InboxQuery::default()).await;
+    assert_eq!(ids(&inbox), [a.as_str(), b.as_str()]);
+    assert!(!inbox.items[0].unread);
+    assert_eq!(inbox.unread_count, 1);
+
+    f.service
+        .post_message(&a, &buyer(&a), message("Great"), None)
+        .await
+        .unwrap();
+    let inbox = list(&f, InboxQuery::default()).await;
+    assert!(inbox.items[0].unread);
+    assert_eq!(inbox.unread_count, 2);
+}
+
+#[tokio::test]
+async fn inbox_pages_follow_the_cursor_both_ways() {
+    let f = Fixture::new();
+    let mut opened = Vec::new();
+    for _ in 0..5 {
+        opened.push(f.open_rfq().await);
+    }
+    let newest_first: Vec<String> = opened.iter().rev().cloned().collect();
+
+    let pages = walk(
+        &f,
+        InboxQuery {
+            limit: Some(2),
+            ..InboxQuery::default()
+        },
+    )
+    .await;
+    assert_eq!(
+        pages,
+        [&newest_first[0..2], &newest_first[2..4], &newest_first[4..]]
+    );
+
+    let pages = walk(
+        &f,
+        InboxQuery {
+            order: Some("asc".to_string()),
+            limit: Some(2),
+            ..InboxQuery::default()
+        },
+    )
+    .await;
+    assert_eq!(pages, [&opened[0..2], &opened[2..4], &opened[4..]]);
+
+    // A cursor keeps its place when the filter leaves entries out
+    f.service
+        .mark_inbox_read(MANUFACTURER, &opened[3], &inbox_reader())
+        .await
+        .unwrap();
+    let pages = walk(
+        &f,
+        InboxQuery {
+            unread: Some(true),
+            limit: Some(3),
+            ..InboxQuery::default()
+        },
+    )
+    .await;
+    assert_eq!(
+        pages,
+        [
+            vec![opened[4].clone(), opened[2].clone(), opened[1].clone()],
+            vec![opened[0].clone()],
+        ]
+    );
+}
+
+#[tokio::test]
+async fn bad_inbox_queries_are_rejected() {
+    let f = Fixture::new();
+    f.open_rfq().await;
+
+    for query in [
+        InboxQuery {
+            status: Some("pending".to_string()),
+            ..InboxQuery::default()
+        },
+        InboxQuery {
+            order: Some("newest".to_string()),
+            ..InboxQuery::default()
+        },
+        InboxQuery {
+            cursor: Some("not a cursor!".to_string()),
+            ..InboxQuery::default()
+        },
+    ] {
+        assert!(matches!(
+            f.service
+                .list_inbox(MANUFACTURER, query, &inbox_reader())
+                .await,
+            Err(DomainError::ValidationFailed(_))
+        ));
+    }
+}
+
+fn manufacturer_id() -> ManufacturerId {
+    ManufacturerId::new(MANUFACTURER.to_string()).unwrap()
+}
+
+fn at(minutes: i64) -> DateTime<Utc> {
+    "2025-01-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap() + Duration::minutes(minutes)
+}
+
+/// Inbox entry last active `minutes` into the day, read when `read`
+fn entry(rfq_id: &str, status: RfqStatus, minutes: i64, read: bool) -> RfqInboxEntry {
+    RfqInboxEntry {
+        rfq_id: rfq_id.to_string(),
+        subject: format!("RFQ {}", rfq_id),
+        buyer: Contact {
+            email: BUYER.to_string(),
+            name: None,
+        },
+        status,
+        created_at: at(0),
+        last_event_ts: at(minutes),
+        last_inbound_ts: at(minutes),
+        last_read_ts: read.then(|| at(minutes)),
+    }
+}
+
+fn page_query(limit: u32) -> InboxPageQuery {
+    InboxPageQuery {
+        limit,
+        ..InboxPageQuery::default()
+    }
+}
+
+fn rfq_ids(entries: &[RfqInboxEntry]) -> Vec<&str> {
+    entries.iter().map(|entry| entry.rfq_id.as_str()).collect()
+}
+
+async fn s3_inbox(entries: &[RfqInboxEntry]) -> (FakeS3, S3RfqRepository) {
+    let s3 = FakeS3::new();
+    let repository = S3RfqRepository::new(s3.client(), s3.config());
+    for entry in entries {
+        repository
+            .save_inbox_entry(&manufacturer_id(), entry, None)
+            .await
+            .unwrap();
+    }
+    (s3, repository)
+}
+
+#[tokio::test]
+async fn s3_inbox_pages_by_marker_without_reading_every_entry() {
+    let (s3, repository) = s3_inbox(&[
+        entry("r_a", RfqStatus::Open, 1, false),
+        entry("r_b", RfqStatus::Closed, 2, true),
+        entry("r_c", RfqStatus::Open, 3, true),
+        entry("r_d", RfqStatus::Awarded, 4, false),
+        entry("r_e", RfqStatus::Open, 5, false),
+    ])
+    .await;
+    assert_eq!(s3.keys(PRIVATE_BUCKET, "mfg/mfg_acme/inbox/").len(), 5);
+
+    let mut query = page_query(2);
+    let mut pages = Vec::new();
+    loop {
+        let gets = s3.count(Op::Get);
+        let page = repository
+            .list_inbox(&manufacturer_id(), &query)
+            .await
+            .unwrap();
+        assert!(s3.count(Op::Get) - gets <= 3, "one GET per entry shown");
+        pages.push(rfq_ids(&page.entries).join(","));
+        if !page.has_more {
+            break;
+        }
+        query.after = page.entries.last().map(RfqInboxEntry::position);
+    }
+    assert_eq!(pages, ["r_e,r_d", "r_c,r_b", "r_a"]);
+
+    // One bounded listing per read state and status for each page
+    assert_eq!(s3.count(Op::List), 3 * 8);
+
+    let ascending = repository
+        .list_inbox(
+            &manufacturer_id(),
+            &InboxPageQuery {
+                ascending: true,
+                ..page_query(10)
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        rfq_ids(&ascending.entries),
+        ["r_a", "r_b", "r_c", "r_d", "r_e"]
+    );
+}
+
+#[tokio::test]
+async fn s3_inbox_filters_by_status_and_unread() {
+    let (_, repository) = s3_inbox(&[
+        entry("r_a", RfqStatus::Open, 1, false),
+        entry("r_b", RfqStatus::Closed, 2, true),
+        entry("r_c", RfqStatus::Open, 3, true),
+        entry("r_d", RfqStatus::Awarded, 4, false),
+    ])
+    .await;
+
+    let listed = |statuses: Vec<RfqStatus>, unread: Option<bool>| {
+        let repository = &repository;
+        async move {
+            let page = repository
+                .list_inbox(
+                    &manufacturer_id(),
+                    &InboxPageQuery {
+                        statuses,
+                        unread,
+                        ..page_query(10)
+                    },
+                )
+                .await
+                .unwrap();
+            rfq_ids(&page.entries)
+                .into_iter()
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        }
+    };
+
+    assert_eq!(listed(vec![RfqStatus::Open], None).await, ["r_c", "r_a"]);
+    assert_eq!(
+        listed(vec![RfqStatus::Open, RfqStatus::Awarded], Some(true)).await,
+        ["r_d", "r_a"]
+    );
+    assert_eq!(listed(Vec::new(), Some(false)).await, ["r_c", "r_b"]);
+    assert_eq!(
+        repository
+            .count_unread_inbox(&manufacturer_id())
+            .await
+            .unwrap(),
+        2
+    );
+}
+
+#[tokio::test]
+async fn s3_inbox_markers_move_with_their_entry() {
+    let (s3, repository) = s3_inbox(&[entry("r_a", RfqStatus::Open, 1, false)]).await;
+    let stored = repository
+        .get_inbox_entry(
+            &manufacturer_id(),
+            &domain::value_objects::RfqId::new("r_a".to_string()).unwrap(),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+    let read = entry("r_a", RfqStatus::Closed, 7, true);
+    repository
+        .save_inbox_entry(&manufacturer_id(), &read, Some(&stored.version))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        s3.keys(PRIVATE_BUCKET, "mfg/mfg_acme/inbox/"),
+        [format!(
+            "mfg/mfg_acme/inbox/read/closed/{}",
+            read.position()
+        )]
+    );
+    assert_eq!(
+        repository
+            .count_unread_inbox(&manufacturer_id())
+            .await
+            .unwrap(),
+        0
+    );
+
+    // A lost race moves no marker
+    assert!(matches!(
+        repository
+            .save_inbox_entry(
+                &manufacturer_id(),
+                &entry("r_a", RfqStatus::Open, 9, false),
+                Some(&stored.version)
+            )
+            .await,
+        Err(DomainError::Conflict(_))
+    ));
+    assert_eq!(s3.keys(PRIVATE_BUCKET, "mfg/mfg_acme/inbox/").len(), 1);
+}
+
+#[tokio::test]
+async fn s3_inbox_drops_markers_left_behind() {
+    let (s3, repository) = s3_inbox(&[
+        entry("r_a", RfqStatus::Open, 1, false),
+        entry("r_b", RfqStatus::Open, 2, false),
+        entry("r_c", RfqStatus::Open, 3, false),
+    ])
+    .await;
+    // As if a save crashed after writing r_c's marker for newer activity,
+    // and another left r_b's old unread marker behind
+    let ahead = entry("r_c", RfqStatus::Open, 10, false);
+    let stale = format!("mfg/mfg_acme/inbox/unread/open/{}", ahead.position());
+    s3.put_object(PRIVATE_BUCKET, &stale, b"\"r_c\"");
+    let behind = entry("r_b", RfqStatus::Open, 2, false);
+    repository
+        .save_inbox_entry(
+            &manufacturer_id(),
+            &RfqInboxEntry {
+                last_read_ts: Some(at(2)),
+                ..behind.clone()
+            },
+            repository
+                .get_inbox_entry(
+                    &manufacturer_id(),
+                    &domain::value_objects::RfqId::new("r_b".to_string()).unwrap(),
+                )
+                .await
+                .unwrap()
+                .map(|stored| stored.version)
+                .as_deref(),
+        )
+        .await
+        .unwrap();
+    let left_behind = format!("mfg/mfg_acme/inbox/unread/open/{}", behind.position());
+    s3.put_object(PRIVATE_BUCKET, &left_behind, b"\"r_b\"");
+
+    let page = repository
+        .list_inbox(&manufacturer_id(), &page_query(2))
+        .await
+        .unwrap();
+    assert_eq!(rfq_ids(&page.entries), ["r_c", "r_b"]);
+    assert!(page.has_more);
+    assert!(s3.object(PRIVATE_BUCKET, &stale).is_none());
+
+    let rest = repository
+        .list_inbox(
+            &manufacturer_id(),
+            &InboxPageQuery {
+                after: page.entries.last().map(RfqInboxEntry::position),
+                ..page_query(2)
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(rfq_ids(&rest.entries), ["r_a"]);
+    assert!(!rest.has_more);
+}
+
+#[tokio::test]
+async fn s3_index_inbox_backfills_markers() {
+    let s3 = FakeS3::new();
+    let repository = S3RfqRepository::new(s3.client(), s3.config());
+    for entry in [
+        entry("r_a", RfqStatus::Open, 1, false),
+        entry("r_b", RfqStatus::Closed, 2, true),
+    ] {
+        s3.put_object(
+            PRIVATE_BUCKET,
+            &format!("mfg/mfg_acme/rfqs/{}.json", entry.rfq_id),
+            &serde_json::to_vec(&entry).unwrap(),
+        );
+    }
+    assert!(repository
+        .list_inbox(&manufacturer_id(), &page_query(10))
+        .await
+        .unwrap()
+        .entries
+        .is_empty());
+
+    assert_eq!(repository.index_inbox().await.unwrap(), 2);
+    let page = repository
+        .list_inbox(&manufacturer_id(), &page_query(10))
+        .await
+        .unwrap();
+    assert_eq!(rfq_ids(&page.entries), ["r_b", "r_a"]);
+}
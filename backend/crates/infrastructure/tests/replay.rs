@@ -0,0 +1,186 @@
+mod common;
+
+use application::ports::RfqRepository;
+use common::*;
+use domain::entities::{RfqIndex, RfqStatus};
+use domain::error::DomainError;
+use domain::value_objects::{ManufacturerId, RfqId};
+
+fn rfq_id(id: &str) -> RfqId {
+    RfqId::new(id.to_string()).unwrap()
+}
+
+fn manufacturer_id() -> ManufacturerId {
+    ManufacturerId::new(MANUFACTURER.to_string()).unwrap()
+}
+
+/// An RFQ with a quote, a reply and a read inbox entry, closed by the buyer
+async fn busy_rfq(f: &Fixture) -> String {
+    let id = f.open_rfq().await;
+    f.service
+        .submit_quote(&id, &maker(&id), quote(), None)
+        .await
+        .unwrap();
+    f.service
+        .post_message(&id, &buyer(&id), message("Thanks"), None)
+        .await
+        .unwrap();
+    f.service
+        .mark_inbox_read(
+            MANUFACTURER,
+            &id,
+            &application::access::Principal::ManufacturerUser {
+                email: CONTACT.to_string(),
+                resource_id: MANUFACTURER.to_string(),
+            },
+        )
+        .await
+        .unwrap();
+    f.service
+        .change_status(&id, &buyer(&id), status("closed"), None)
+        .await
+        .unwrap();
+    id
+}
+
+#[tokio::test]
+async fn documents_written_by_the_service_match_their_replay() {
+    let f = Fixture::new();
+    let id = busy_rfq(&f).await;
+
+    let replay = f.rfqs.replay_rfq(&rfq_id(&id)).await.unwrap().unwrap();
+    assert!(!replay.has_drift());
+    assert_eq!(replay.meta.status, RfqStatus::Closed);
+    assert_eq!(replay.index.count, f.rfqs.events_for(&id).len() as u32);
+    assert!(replay.inbox.last_read_ts.is_some());
+
+    assert!(f
+        .rfqs
+        .replay_rfq(&rfq_id("r_missing"))
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn corrupted_meta_and_index_are_detected_and_repaired() {
+    let f = Fixture::new();
+    let id = busy_rfq(&f).await;
+    let healthy = f.rfqs.replay_rfq(&rfq_id(&id)).await.unwrap().unwrap();
+
+    let stored = f.rfqs.get_rfq_meta(&rfq_id(&id)).await.unwrap().unwrap();
+    let mut corrupted = stored.value.clone();
+    corrupted.status = RfqStatus::Archived;
+    corrupted.latest_quote = None;
+    f.rfqs
+        .save_rfq_meta(&corrupted, Some(&stored.version))
+        .await
+        .unwrap();
+
+    let index = f.rfqs.get_rfq_index(&rfq_id(&id)).await.unwrap().unwrap();
+    f.rfqs
+        .save_rfq_index(
+            &rfq_id(&id),
+            &RfqIndex {
+                count: 99,
+                ..index.value.clone()
+            },
+            Some(&index.version),
+        )
+        .await
+        .unwrap();
+
+    let replay = f.rfqs.replay_rfq(&rfq_id(&id)).await.unwrap().unwrap();
+    assert!(replay.has_drift());
+    assert_eq!(replay.meta, healthy.meta);
+    assert_eq!(replay.index, healthy.index);
+
+    replay.repair(f.rfqs.as_ref()).await.unwrap();
+    assert_eq!(f.meta(&id).await, healthy.meta);
+    assert_eq!(
+        f.rfqs
+            .get_rfq_index(&rfq_id(&id))
+            .await
+            .unwrap()
+            .unwrap()
+            .value,
+        healthy.index
+    );
+    assert!(!f
+        .rfqs
+        .replay_rfq(&rfq_id(&id))
+        .await
+        .unwrap()
+        .unwrap()
+        .has_drift());
+}
+
+#[tokio::test]
+async fn a_repaired_inbox_entry_keeps_its_read_marker() {
+    let f = Fixture::new();
+    let id = busy_rfq(&f).await;
+    let stored = f
+        .rfqs
+        .get_inbox_entry(&manufacturer_id(), &rfq_id(&id))
+        .await
+        .unwrap()
+        .unwrap();
+    let read_at = stored.value.last_read_ts;
+
+    let mut corrupted = stored.value.clone();
+    corrupted.status = RfqStatus::Open;
+    corrupted.subject = "Stale subject".to_string();
+    f.rfqs
+        .save_inbox_entry(&manufacturer_id(), &corrupted, Some(&stored.version))
+        .await
+        .unwrap();
+
+    let replay = f.rfqs.replay_rfq(&rfq_id(&id)).await.unwrap().unwrap();
+    assert!(replay.has_drift());
+    replay.repair(f.rfqs.as_ref()).await.unwrap();
+
+    let repaired = f
+        .rfqs
+        .get_inbox_entry(&manufacturer_id(), &rfq_id(&id))
+        .await
+        .unwrap()
+        .unwrap()
+        .value;
+    assert_eq!(repaired.status, RfqStatus::Closed);
+    assert_eq!(repaired.subject, "Brackets");
+    assert_eq!(repaired.last_read_ts, read_at);
+}
+
+#[tokio::test]
+async fn a_repair_never_overwrites_a_newer_write() {
+    let f = Fixture::new();
+    let id = busy_rfq(&f).await;
+
+    let stored = f.rfqs.get_rfq_meta(&rfq_id(&id)).await.unwrap().unwrap();
+    let mut corrupted = stored.value.clone();
+    corrupted.latest_quote = None;
+    f.rfqs
+        .save_rfq_meta(&corrupted, Some(&stored.version))
+        .await
+        .unwrap();
+    let replay = f.rfqs.replay_rfq(&rfq_id(&id)).await.unwrap().unwrap();
+
+    // The buyer reopens between the replay and the repair
+    f.service
+        .change_status(&id, &buyer(&id), status("open"), None)
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        replay.repair(f.rfqs.as_ref()).await,
+        Err(DomainError::Conflict(_))
+    ));
+    assert_eq!(f.meta(&id).await.status, RfqStatus::Open);
+
+    // A fresh replay picks the reopen up and repairs the rest
+    let replay = f.rfqs.replay_rfq(&rfq_id(&id)).await.unwrap().unwrap();
+    assert_eq!(replay.meta.status, RfqStatus::Open);
+    assert!(replay.meta.latest_quote.is_some());
+    replay.repair(f.rfqs.as_ref()).await.unwrap();
+    assert!(f.meta(&id).await.latest_quote.is_some());
+}
@@ -0,0 +1,262 @@
+mod common;
+
+use application::dto::RfqDecisionRequest;
+use common::*;
+use domain::entities::{Decision, OfferKind, OfferReference, RfqStatus};
+use domain::error::DomainError;
+use domain::events::{EventAuthor, RfqEvent, StatusType};
+use infrastructure::memory::SentNotification;
+
+fn decision(reason: Option<&str>, reference: Option<OfferReference>) -> RfqDecisionRequest {
+    RfqDecisionRequest {
+        reason: reason.map(str::to_string),
+        reference,
+    }
+}
+
+fn reference(kind: OfferKind, id: &str) -> Option<OfferReference> {
+    Some(OfferReference {
+        kind,
+        id: id.to_string(),
+    })
+}
+
+/// Id of the latest message event `author` wrote on the RFQ
+fn last_message_by(f: &Fixture, rfq_id: &str, author: EventAuthor) -> String {
+    f.rfqs
+        .events_for(rfq_id)
+        .into_iter()
+        .rev()
+        .find_map(|event| match event {
+            RfqEvent::Message(e) if e.base.by == author => Some(e.base.id),
+            _ => None,
+        })
+        .unwrap()
+}
+
+#[tokio::test]
+async fn buyer_awards_a_quote() {
+    let f = Fixture::new();
+    let id = f.open_rfq().await;
+    let quoted = f
+        .service
+        .submit_quote(&id, &maker(&id), quote(), None)
+        .await
+        .unwrap();
+
+    let awarded = f
+        .service
+        .award(
+            &id,
+            &buyer(&id),
+            decision(
+                Some("Best lead time"),
+                reference(OfferKind::Quote, &quoted.id),
+            ),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(awarded.status, RfqStatus::Awarded);
+
+    let meta = f.meta(&id).await;
+    let outcome = meta.outcome.unwrap();
+    assert_eq!(outcome.decision, Decision::Awarded);
+    assert_eq!(outcome.reference, reference(OfferKind::Quote, &quoted.id));
+
+    let sent = f.emails.sent_emails();
+    let Some(SentNotification::RfqOutcome { rfq, event }) = sent.last() else {
+        panic!("expected an outcome notification, got {:?}", sent);
+    };
+    assert_eq!(rfq.status, RfqStatus::Awarded);
+    assert!(matches!(
+        event,
+        RfqEvent::Status(e) if e.status == StatusType::Awarded
+            && e.note.as_deref() == Some("Best lead time")
+    ));
+}
+
+#[tokio::test]
+async fn buyer_declines_and_may_reopen() {
+    let f = Fixture::new();
+    let id = f.open_rfq().await;
+    f.service
+        .post_message(&id, &maker(&id), message("We can do 3 weeks"), None)
+        .await
+        .unwrap();
+    let offer = last_message_by(&f, &id, EventAuthor::Manufacturer);
+
+    let declined = f
+        .service
+        .decline(
+            &id,
+            &buyer(&id),
+            decision(None, reference(OfferKind::Message, &offer)),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(declined.status, RfqStatus::Closed);
+    assert_eq!(
+        f.meta(&id).await.outcome.unwrap().decision,
+        Decision::Declined
+    );
+    assert!(matches!(
+        f.emails.sent_emails().last(),
+        Some(SentNotification::RfqOutcome { .. })
+    ));
+
+    f.service
+        .change_status(&id, &buyer(&id), status("open"), None)
+        .await
+        .unwrap();
+    f.service
+        .award(&id, &buyer(&id), decision(None, None), None)
+        .await
+        .unwrap();
+    assert_eq!(f.meta(&id).await.status, RfqStatus::Awarded);
+}
+
+#[tokio::test]
+async fn an_award_locks_the_rfq() {
+    let f = Fixture::new();
+    let id = f.open_rfq().await;
+    f.service
+        .award(&id, &buyer(&id), decision(None, None), None)
+        .await
+        .unwrap();
+
+    for attempt in [
+        f.service
+            .award(&id, &buyer(&id), decision(None, None), None)
+            .await,
+        f.service
+            .decline(&id, &buyer(&id), decision(None, None), None)
+            .await,
+    ] {
+        assert!(matches!(attempt, Err(DomainError::Conflict(_))));
+    }
+
+    f.service
+        .change_status(&id, &maker(&id), status("closed"), None)
+        .await
+        .unwrap();
+    assert!(matches!(
+        f.service
+            .change_status(&id, &buyer(&id), status("open"), None)
+            .await,
+        Err(DomainError::Conflict(_))
+    ));
+    f.service
+        .change_status(&id, &buyer(&id), status("archived"), None)
+        .await
+        .unwrap();
+    assert!(matches!(
+        f.service
+            .change_status(&id, &buyer(&id), status("open"), None)
+            .await,
+        Err(DomainError::Conflict(_))
+    ));
+
+    let meta = f.meta(&id).await;
+    assert_eq!(meta.status, RfqStatus::Archived);
+    assert!(meta.is_awarded());
+}
+
+#[tokio::test]
+async fn closed_or_archived_rfqs_cannot_be_decided() {
+    for to in ["closed", "archived"] {
+        let f = Fixture::new();
+        let id = f.open_rfq().await;
+        f.service
+            .change_status(&id, &buyer(&id), status(to), None)
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            f.service
+                .award(&id, &buyer(&id), decision(None, None), None)
+                .await,
+            Err(DomainError::Conflict(_))
+        ));
+        assert!(matches!(
+            f.service
+                .decline(&id, &buyer(&id), decision(None, None), None)
+                .await,
+            Err(DomainError::Conflict(_))
+        ));
+        assert_eq!(f.meta(&id).await.outcome, None);
+    }
+}
+
+#[tokio::test]
+async fn only_the_buyer_decides() {
+    let f = Fixture::new();
+    let id = f.open_rfq().await;
+
+    assert!(matches!(
+        f.service
+            .award(&id, &maker(&id), decision(None, None), None)
+            .await,
+        Err(DomainError::Forbidden(_))
+    ));
+    assert!(matches!(
+        f.service
+            .decline(&id, &buyer("r_other"), decision(None, None), None)
+            .await,
+        Err(DomainError::Forbidden(_))
+    ));
+}
+
+#[tokio::test]
+async fn references_must_be_the_manufacturers_offers_on_this_rfq() {
+    let f = Fixture::new();
+    let id = f.open_rfq().await;
+    let other = f.open_rfq().await;
+    let foreign_quote = f
+        .service
+        .submit_quote(&other, &maker(&other), quote(), None)
+        .await
+        .unwrap();
+    let own_message = last_message_by(&f, &id, EventAuthor::Buyer);
+
+    for bad in [
+        reference(OfferKind::Quote, "no-such-event"),
+        reference(OfferKind::Quote, &foreign_quote.id),
+        reference(OfferKind::Message, &own_message),
+        reference(OfferKind::Attachment, "att_unknown"),
+    ] {
+        assert!(
+            matches!(
+                f.service
+                    .award(&id, &buyer(&id), decision(None, bad.clone()), None)
+                    .await,
+                Err(DomainError::ValidationFailed(_))
+            ),
+            "{:?}",
+            bad
+        );
+    }
+    assert_eq!(f.meta(&id).await.status, RfqStatus::Open);
+}
+
+#[tokio::test]
+async fn a_failed_event_write_records_no_award() {
+    let f = Fixture::new();
+    let id = f.open_rfq().await;
+    let before = f.meta(&id).await;
+
+    f.rfqs.fail_event_writes(true);
+    assert!(f
+        .service
+        .award(&id, &buyer(&id), decision(None, None), None)
+        .await
+        .is_err());
+    f.rfqs.fail_event_writes(false);
+
+    assert_eq!(f.meta(&id).await, before);
+    f.service
+        .award(&id, &buyer(&id), decision(None, None), None)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,162 @@
+mod common;
+
+use application::access::Principal;
+use common::*;
+use domain::entities::RfqStatus;
+use domain::error::DomainError;
+use domain::events::{EventAuthor, RfqEvent, StatusType};
+use infrastructure::memory::SentNotification;
+
+fn status_changes(f: &Fixture, rfq_id: &str) -> Vec<(EventAuthor, StatusType)> {
+    f.rfqs
+        .events_for(rfq_id)
+        .into_iter()
+        .filter_map(|event| match event {
+            RfqEvent::Status(e) => Some((e.base.by, e.status)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn participants_close_archive_and_reopen() {
+    let f = Fixture::new();
+    let id = f.open_rfq().await;
+
+    let closed = f
+        .service
+        .change_status(&id, &maker(&id), status("closed"), None)
+        .await
+        .unwrap();
+    assert_eq!(closed.status, RfqStatus::Closed);
+
+    let archived = f
+        .service
+        .change_status(&id, &buyer(&id), status("archived"), None)
+        .await
+        .unwrap();
+    assert_eq!(archived.status, RfqStatus::Archived);
+
+    assert!(matches!(
+        f.service
+            .change_status(&id, &maker(&id), status("open"), None)
+            .await,
+        Err(DomainError::Forbidden(_))
+    ));
+    let reopened = f
+        .service
+        .change_status(&id, &buyer(&id), status("open"), None)
+        .await
+        .unwrap();
+    assert_eq!(reopened.status, RfqStatus::Open);
+
+    assert_eq!(
+        status_changes(&f, &id),
+        vec![
+            (EventAuthor::System, StatusType::RfqCreated),
+            (EventAuthor::Manufacturer, StatusType::Closed),
+            (EventAuthor::Buyer, StatusType::Archived),
+            (EventAuthor::Buyer, StatusType::Reopened),
+        ]
+    );
+    assert_eq!(f.meta(&id).await.status, RfqStatus::Open);
+}
+
+#[tokio::test]
+async fn invalid_changes_are_rejected() {
+    let f = Fixture::new();
+    let id = f.open_rfq().await;
+
+    let change = |principal: Principal, to: &'static str| {
+        let id = id.clone();
+        let f = &f;
+        async move {
+            f.service
+                .change_status(&id, &principal, status(to), None)
+                .await
+        }
+    };
+
+    assert!(matches!(
+        change(buyer(&id), "open").await,
+        Err(DomainError::Conflict(_))
+    ));
+    assert!(matches!(
+        change(buyer(&id), "awarded").await,
+        Err(DomainError::ValidationFailed(_))
+    ));
+    assert!(matches!(
+        change(Principal::Anonymous, "closed").await,
+        Err(DomainError::Unauthorized(_))
+    ));
+    assert!(matches!(
+        change(buyer("r_other"), "closed").await,
+        Err(DomainError::Forbidden(_))
+    ));
+    assert_eq!(status_changes(&f, &id).len(), 1);
+}
+
+#[tokio::test]
+async fn closed_rfqs_take_no_messages() {
+    let f = Fixture::new();
+    let id = f.open_rfq().await;
+    f.service
+        .change_status(&id, &buyer(&id), status("closed"), None)
+        .await
+        .unwrap();
+
+    for principal in [buyer(&id), maker(&id)] {
+        assert!(matches!(
+            f.service
+                .post_message(&id, &principal, message("Still there?"), None)
+                .await,
+            Err(DomainError::Conflict(_))
+        ));
+    }
+
+    f.service
+        .change_status(&id, &buyer(&id), status("open"), None)
+        .await
+        .unwrap();
+    f.service
+        .post_message(&id, &maker(&id), message("Yes"), None)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn status_changes_notify_with_the_new_status() {
+    let f = Fixture::new();
+    let id = f.open_rfq().await;
+    f.service
+        .change_status(&id, &maker(&id), status("closed"), None)
+        .await
+        .unwrap();
+
+    let sent = f.emails.sent_emails();
+    let Some(SentNotification::RfqStatus { rfq, event }) = sent.last() else {
+        panic!("expected a status notification, got {:?}", sent);
+    };
+    assert_eq!(rfq.status, RfqStatus::Closed);
+    assert_eq!(*event.author(), EventAuthor::Manufacturer);
+    assert!(matches!(event, RfqEvent::Status(e) if e.status == StatusType::Closed));
+}
+
+#[tokio::test]
+async fn a_failed_event_write_leaves_meta_alone() {
+    let f = Fixture::new();
+    let id = f.open_rfq().await;
+    let before = f.meta(&id).await;
+
+    f.rfqs.fail_event_writes(true);
+    assert!(f
+        .service
+        .change_status(&id, &buyer(&id), status("closed"), None)
+        .await
+        .is_err());
+    f.rfqs.fail_event_writes(false);
+
+    assert_eq!(f.meta(&id).await, before);
+    assert_eq!(status_changes(&f, &id).len(), 1);
+    assert_eq!(f.emails.sent_emails().len(), 1);
+}
@@ -0,0 +1,178 @@
+mod common;
+
+use common::fake_s3::{FakeS3, Op, PRIVATE_BUCKET};
+use infrastructure::s3_store::{PutOptions, S3ObjectError, S3ObjectStore};
+
+const KEY: &str = "rfq/r_1/meta.json";
+
+fn store() -> (FakeS3, S3ObjectStore) {
+    let s3 = FakeS3::new();
+    let store = S3ObjectStore::new(s3.client(), &s3.config());
+    (s3, store)
+}
+
+async fn put(
+    store: &S3ObjectStore,
+    body: &[u8],
+    options: &PutOptions,
+) -> Result<String, S3ObjectError> {
+    store
+        .put_bytes(
+            PRIVATE_BUCKET,
+            KEY,
+            body.to_vec(),
+            "application/json",
+            options,
+        )
+        .await
+}
+
+async fn version(store: &S3ObjectStore) -> String {
+    store
+        .get_bytes(PRIVATE_BUCKET, KEY)
+        .await
+        .unwrap()
+        .unwrap()
+        .version
+}
+
+#[tokio::test]
+async fn throttled_and_transient_failures_are_retried() {
+    let (s3, store) = store();
+    s3.put_object(PRIVATE_BUCKET, KEY, b"{}");
+
+    s3.fail_next(Op::Get, 503, "SlowDown");
+    s3.fail_next(Op::Get, 500, "InternalError");
+    let object = store.get_bytes(PRIVATE_BUCKET, KEY).await.unwrap().unwrap();
+    assert_eq!(object.value, b"{}");
+    assert_eq!(s3.count(Op::Get), 3);
+
+    s3.fail_next(Op::Put, 503, "SlowDown");
+    put(&store, b"[]", &PutOptions::default()).await.unwrap();
+    assert_eq!(s3.count(Op::Put), 2);
+    assert_eq!(s3.object(PRIVATE_BUCKET, KEY).unwrap(), b"[]");
+}
+
+#[tokio::test]
+async fn retries_stop_after_the_last_attempt() {
+    let (s3, store) = store();
+    for _ in 0..3 {
+        s3.fail_next(Op::Get, 503, "SlowDown");
+    }
+    assert!(matches!(
+        store.get_bytes(PRIVATE_BUCKET, KEY).await,
+        Err(S3ObjectError::Throttled { .. })
+    ));
+    assert_eq!(s3.count(Op::Get), 3);
+}
+
+#[tokio::test]
+async fn other_failures_are_not_retried() {
+    let (s3, store) = store();
+    s3.put_object(PRIVATE_BUCKET, KEY, b"{}");
+
+    s3.fail_next(Op::Get, 403, "AccessDenied");
+    assert!(matches!(
+        store.get_bytes(PRIVATE_BUCKET, KEY).await,
+        Err(S3ObjectError::AccessDenied { .. })
+    ));
+    assert_eq!(s3.count(Op::Get), 1);
+
+    // A missing object is an answer, not a failure
+    assert!(store
+        .get_bytes(PRIVATE_BUCKET, "rfq/r_2/meta.json")
+        .await
+        .unwrap()
+        .is_none());
+    assert!(store
+        .head(PRIVATE_BUCKET, "rfq/r_2/meta.json")
+        .await
+        .unwrap()
+        .is_none());
+    assert_eq!(s3.count(Op::Get), 2);
+}
+
+#[tokio::test]
+async fn conditional_writes_fail_on_a_stale_version() {
+    let (_s3, store) = store();
+    let first = put(&store, b"1", &PutOptions::conditional(None))
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        put(&store, b"2", &PutOptions::conditional(None)).await,
+        Err(S3ObjectError::PreconditionFailed { .. })
+    ));
+    let second = put(&store, b"2", &PutOptions::conditional(Some(&first)))
+        .await
+        .unwrap();
+    assert!(matches!(
+        put(&store, b"3", &PutOptions::conditional(Some(&first))).await,
+        Err(S3ObjectError::PreconditionFailed { .. })
+    ));
+    assert_eq!(version(&store).await, second);
+}
+
+#[tokio::test]
+async fn a_conditional_write_whose_response_was_lost_is_not_sent_again() {
+    let (s3, store) = store();
+
+    s3.lose_next_response(Op::Put);
+    let created = put(&store, b"1", &PutOptions::conditional(None))
+        .await
+        .unwrap();
+    assert_eq!(created, version(&store).await);
+    assert_eq!(s3.count(Op::Put), 1);
+
+    s3.lose_next_response(Op::Put);
+    let replaced = put(&store, b"2", &PutOptions::conditional(Some(&created)))
+        .await
+        .unwrap();
+    assert_eq!(replaced, version(&store).await);
+    assert_eq!(s3.count(Op::Put), 2);
+    assert_eq!(s3.object(PRIVATE_BUCKET, KEY).unwrap(), b"2");
+}
+
+#[tokio::test]
+async fn a_failed_conditional_write_is_retried_while_its_condition_holds() {
+    let (s3, store) = store();
+
+    s3.fail_next(Op::Put, 500, "InternalError");
+    let created = put(&store, b"1", &PutOptions::conditional(None))
+        .await
+        .unwrap();
+    assert_eq!(s3.count(Op::Put), 2);
+
+    s3.fail_next(Op::Put, 503, "SlowDown");
+    put(&store, b"2", &PutOptions::conditional(Some(&created)))
+        .await
+        .unwrap();
+    assert_eq!(s3.count(Op::Put), 4);
+    assert_eq!(s3.object(PRIVATE_BUCKET, KEY).unwrap(), b"2");
+}
+
+#[tokio::test]
+async fn a_failed_conditional_write_is_dropped_once_another_writer_won() {
+    let (s3, store) = store();
+    let read = put(&store, b"1", &PutOptions::conditional(None))
+        .await
+        .unwrap();
+    // Someone else replaces the object after we read it
+    s3.put_object(PRIVATE_BUCKET, KEY, b"theirs");
+
+    s3.fail_next(Op::Put, 500, "InternalError");
+    assert!(matches!(
+        put(&store, b"ours", &PutOptions::conditional(Some(&read))).await,
+        Err(S3ObjectError::PreconditionFailed { .. })
+    ));
+
+    s3.fail_next(Op::Put, 500, "InternalError");
+    assert!(matches!(
+        put(&store, b"ours", &PutOptions::conditional(None)).await,
+        Err(S3ObjectError::PreconditionFailed { .. })
+    ));
+
+    // Neither failed write was sent a second time
+    assert_eq!(s3.count(Op::Put), 3);
+    assert_eq!(s3.object(PRIVATE_BUCKET, KEY).unwrap(), b"theirs");
+}
@@ -0,0 +1,397 @@
+use application::access::{AccessTokens, AdminClaims, AdminCredentials, Principal};
+use application::services::{ApiKeyService, API_KEY_PREFIX};
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Query, Request},
+    http::{request::Parts, HeaderMap, Method},
+    response::{IntoResponse, Response},
+    Extension,
+};
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tower::{Layer, Service};
+
+use crate::error::AppError;
+
+/// Resolves request credentials into a `Principal`, accepting whichever
+/// kinds of credential it was given verifiers for. Routers that use
+/// `Caller` need it as an `Extension` layer.
+#[derive(Default)]
+pub struct Authenticator {
+    access_tokens: Option<Arc<AccessTokens>>,
+    api_keys: Option<Arc<ApiKeyService>>,
+    admin_credentials: Option<Arc<AdminCredentials>>,
+}
+
+impl Authenticator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accept participant access tokens
+    pub fn with_access_tokens(mut self, access_tokens: Arc<AccessTokens>) -> Self {
+        self.access_tokens = Some(access_tokens);
+        self
+    }
+
+    /// Accept tenant API keys
+    pub fn with_api_keys(mut self, api_keys: Arc<ApiKeyService>) -> Self {
+        self.api_keys = Some(api_keys);
+        self
+    }
+
+    /// Accept admin bearer keys and JWTs
+    pub fn with_admin_credentials(mut self, admin_credentials: Arc<AdminCredentials>) -> Self {
+        self.admin_credentials = Some(admin_credentials);
+        self
+    }
+
+    /// Resolve the credentials of a request, sent as `Authorization: Bearer`.
+    /// GET requests may pass an access token as a `token` query parameter
+    /// instead, so links in emails open directly.
+    pub async fn authenticate(&self, parts: &Parts) -> Result<Principal, AppError> {
+        let query_token = || {
+            let Query(params) = Query::<HashMap<String, String>>::try_from_uri(&parts.uri).ok()?;
+            params.get("token").cloned()
+        };
+        let token = bearer_token(&parts.headers)
+            .map(str::to_string)
+            .or_else(|| (parts.method == Method::GET).then(query_token).flatten())
+            .ok_or_else(|| AppError::unauthorized("Missing access token"))?;
+
+        if let Some(api_keys) = self
+            .api_keys
+            .as_ref()
+            .filter(|_| token.starts_with(API_KEY_PREFIX))
+        {
+            return Ok(api_keys.authenticate(&token).await?);
+        }
+
+        let mut error = AppError::unauthorized("Invalid credentials");
+        if let Some(access_tokens) = &self.access_tokens {
+            match access_tokens.verify(&token) {
+                Ok(claims) => return Ok(claims.into()),
+                Err(e) => error = e.into(),
+            }
+        }
+        if let Some(admin_credentials) = &self.admin_credentials {
+            if let Ok(claims) = admin_credentials.verify(&token) {
+                return Ok(claims.into());
+            }
+        }
+        Err(error)
+    }
+
+    /// Whether the request carries credentials at all
+    fn has_credentials(parts: &Parts) -> bool {
+        bearer_token(&parts.headers).is_some()
+            || (parts.method == Method::GET
+                && parts.uri.query().is_some_and(|query| {
+                    form_urlencoded::parse(query.as_bytes()).any(|(k, _)| k == "token")
+                }))
+    }
+}
+
+/// The authenticated caller of a request
+pub struct Caller(pub Principal);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for Caller
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let authenticator = authenticator(parts, state).await?;
+        authenticator.authenticate(parts).await.map(Caller)
+    }
+}
+
+/// The caller of a route that also serves requests without credentials:
+/// `None` when there are none, rejected when they are invalid
+pub struct OptionalCaller(pub Option<Principal>);
+
+impl OptionalCaller {
+    /// The caller, `Principal::Anonymous` without credentials
+    pub fn principal(self) -> Principal {
+        self.0.unwrap_or(Principal::Anonymous)
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for OptionalCaller
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        if !Authenticator::has_credentials(parts) {
+            return Ok(OptionalCaller(None));
+        }
+        let authenticator = authenticator(parts, state).await?;
+        let principal = authenticator.authenticate(parts).await?;
+        Ok(OptionalCaller(Some(principal)))
+    }
+}
+
+async fn authenticator<S: Send + Sync>(
+    parts: &mut Parts,
+    state: &S,
+) -> Result<Arc<Authenticator>, AppError> {
+    Extension::<Arc<Authenticator>>::from_request_parts(parts, state)
+        .await
+        .map(|Extension(authenticator)| authenticator)
+        .map_err(|_| AppError::internal_server_error("Authentication is not configured"))
+}
+
+/// Guards admin routes: requests without valid admin credentials get a 401,
+/// or a 403 when the `Authenticator` extension recognises them as another
+/// caller, e.g. a participant or tenant key. The rest reach the handler with
+/// their `AdminClaims` and the matching `Principal::Admin` as request
+/// extensions.
+#[derive(Clone)]
+pub struct RequireAdminLayer {
+    credentials: Arc<AdminCredentials>,
+}
+
+impl RequireAdminLayer {
+    pub fn new(credentials: Arc<AdminCredentials>) -> Self {
+        Self { credentials }
+    }
+}
+
+impl<S> Layer<S> for RequireAdminLayer {
+    type Service = RequireAdmin<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RequireAdmin {
+            inner,
+            credentials: self.credentials.clone(),
+        }
+    }
+}
+
+/// Service produced by `RequireAdminLayer`
+#[derive(Clone)]
+pub struct RequireAdmin<S> {
+    inner: S,
+    credentials: Arc<AdminCredentials>,
+}
+
+impl<S> Service<Request> for RequireAdmin<S>
+where
+    S: Service<Request, Response = Response, Error = Infallible> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, Infallible>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request) -> Self::Future {
+        let claims = bearer_token(request.headers())
+            .ok_or_else(|| AppError::unauthorized("Missing admin credentials"))
+            .and_then(|token| Ok(self.credentials.verify(token)?));
+        let claims = match claims {
+            Ok(claims) => claims,
+            Err(e) => return Box::pin(reject_non_admin(request, e)),
+        };
+
+        tracing::info!(admin = %claims.subject, "Admin request authenticated");
+        request
+            .extensions_mut()
+            .insert(Principal::from(claims.clone()));
+        request.extensions_mut().insert::<AdminClaims>(claims);
+
+        // The clone that was polled ready serves this request
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        Box::pin(inner.call(request))
+    }
+}
+
+/// Answer a request that failed the admin check: forbidden if its
+/// credentials are valid for someone else, `error` otherwise
+async fn reject_non_admin(request: Request, error: AppError) -> Result<Response, Infallible> {
+    let (parts, _) = request.into_parts();
+    if let Some(authenticator) = parts.extensions.get::<Arc<Authenticator>>() {
+        if authenticator.authenticate(&parts).await.is_ok() {
+            return Ok(AppError::forbidden("Admin credentials required").into_response());
+        }
+    }
+    Ok(error.into_response())
+}
+
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get("authorization")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::{ApiKeyHandlers, ManufacturerHandlers};
+    use application::dto::CreateApiKeyRequest;
+    use application::services::ManufacturerService;
+    use axum::body::Body;
+    use axum::http::{header, StatusCode};
+    use axum::Router;
+    use domain::entities::{ApiKeyScope, ParticipantRole};
+    use infrastructure::memory::{InMemoryApiKeyRepository, InMemoryManufacturerRepository};
+    use tower::ServiceExt;
+
+    const ADMIN_KEY: &str = "admin key of at least thirty-two bytes";
+
+    struct Admin {
+        app: Router,
+        access_tokens: Arc<AccessTokens>,
+        api_keys: Arc<ApiKeyService>,
+    }
+
+    fn admin_routes() -> Admin {
+        let access_tokens = Arc::new(AccessTokens::with_random_secret(chrono::Duration::days(1)));
+        let api_keys = Arc::new(ApiKeyService::new(
+            Arc::new(InMemoryApiKeyRepository::new()),
+        ));
+        let admin_credentials =
+            Arc::new(AdminCredentials::new(&[ADMIN_KEY.to_string()], None).unwrap());
+        let authenticator = Authenticator::new()
+            .with_access_tokens(access_tokens.clone())
+            .with_api_keys(api_keys.clone())
+            .with_admin_credentials(admin_credentials.clone());
+
+        let app = Router::new()
+            .merge(ManufacturerHandlers::router(
+                Arc::new(ManufacturerService::new(Arc::new(
+                    InMemoryManufacturerRepository::new(),
+                ))),
+                admin_credentials.clone(),
+            ))
+            .merge(ApiKeyHandlers::router(api_keys.clone(), admin_credentials))
+            .layer(Extension(Arc::new(authenticator)));
+        Admin {
+            app,
+            access_tokens,
+            api_keys,
+        }
+    }
+
+    async fn status(app: &Router, method: Method, uri: &str, token: Option<&str>) -> StatusCode {
+        let mut request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(header::CONTENT_TYPE, "application/json");
+        if let Some(token) = token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        let body =
+            Body::from(serde_json::json!({ "name": "CI", "scopes": ["rfq:read"] }).to_string());
+        app.clone()
+            .oneshot(request.body(body).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    fn admin_only_routes() -> [(Method, &'static str); 4] {
+        [
+            (Method::POST, "/manufacturers"),
+            (Method::POST, "/tenants/t1/api-keys"),
+            (Method::GET, "/tenants/t1/api-keys"),
+            (Method::POST, "/tenants/t1/api-keys/k_1/revoke"),
+        ]
+    }
+
+    #[tokio::test]
+    async fn other_callers_are_forbidden_from_admin_routes() {
+        let admin = admin_routes();
+        let participant = admin.access_tokens.issue(
+            "mfg_acme",
+            ParticipantRole::Manufacturer,
+            "sales@acme.example",
+        );
+        let tenant_key = admin
+            .api_keys
+            .create_key(
+                "t1",
+                &Principal::Admin {
+                    subject: "ops".to_string(),
+                },
+                CreateApiKeyRequest {
+                    name: "ERP".to_string(),
+                    scopes: vec![ApiKeyScope::ProfileWrite],
+                    expires_at: None,
+                },
+            )
+            .await
+            .unwrap()
+            .key;
+
+        for (method, uri) in admin_only_routes() {
+            for token in [&participant, &tenant_key] {
+                assert_eq!(
+                    status(&admin.app, method.clone(), uri, Some(token)).await,
+                    StatusCode::FORBIDDEN,
+                    "{} {}",
+                    method,
+                    uri
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn missing_or_invalid_credentials_are_unauthorized() {
+        let admin = admin_routes();
+        for (method, uri) in admin_only_routes() {
+            for token in [None, Some("not-a-token"), Some("tk_bogus.key.value")] {
+                assert_eq!(
+                    status(&admin.app, method.clone(), uri, token).await,
+                    StatusCode::UNAUTHORIZED,
+                    "{} {} {:?}",
+                    method,
+                    uri,
+                    token
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn admins_reach_the_handlers() {
+        let admin = admin_routes();
+        assert_eq!(
+            status(
+                &admin.app,
+                Method::GET,
+                "/tenants/t1/api-keys",
+                Some(ADMIN_KEY)
+            )
+            .await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(
+                &admin.app,
+                Method::POST,
+                "/tenants/t1/api-keys",
+                Some(ADMIN_KEY)
+            )
+            .await,
+            StatusCode::CREATED
+        );
+    }
+}
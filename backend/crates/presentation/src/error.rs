@@ -33,6 +33,14 @@ impl AppError {
         Self::new(StatusCode::BAD_REQUEST, "bad_request", message)
     }
 
+    pub fn unauthorized(message: &str) -> Self {
+        Self::new(StatusCode::UNAUTHORIZED, "unauthorized", message)
+    }
+
+    pub fn forbidden(message: &str) -> Self {
+        Self::new(StatusCode::FORBIDDEN, "forbidden", message)
+    }
+
     pub fn not_found(message: &str) -> Self {
         Self::new(StatusCode::NOT_FOUND, "not_found", message)
     }
@@ -75,6 +83,10 @@ impl From<DomainError> for AppError {
                 AppError::new(StatusCode::BAD_REQUEST, "validation_error", &msg)
             }
             DomainError::NotFound(msg) => AppError::new(StatusCode::NOT_FOUND, "not_found", &msg),
+            DomainError::Unauthorized(msg) => {
+                AppError::new(StatusCode::UNAUTHORIZED, "unauthorized", &msg)
+            }
+            DomainError::Forbidden(msg) => AppError::new(StatusCode::FORBIDDEN, "forbidden", &msg),
             DomainError::Conflict(msg) => AppError::new(StatusCode::CONFLICT, "conflict", &msg),
             DomainError::InvalidInput(msg) => {
                 AppError::new(StatusCode::BAD_REQUEST, "invalid_input", &msg)
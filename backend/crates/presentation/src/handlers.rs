@@ -1,20 +1,20 @@
 use application::{
+    access::{AdminClaims, AdminCredentials, Principal},
     dto::*,
-    ports::{ImageService, ManufacturerRepository},
-    services::RfqService,
+    services::{ApiKeyService, ManufacturerService, RfqService, UploadService},
 };
 use axum::{
     extract::{Path, Query, State},
     http::{HeaderMap, StatusCode},
     response::Json,
-    routing::{get, post},
-    Router,
+    routing::{get, post, put},
+    Extension, Router,
 };
 use domain::entities::*;
-use domain::value_objects::*;
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, sync::Arc};
 
+use crate::auth::{Authenticator, Caller, OptionalCaller, RequireAdminLayer};
 use crate::error::{AppError, Result};
 
 /// RFQ handlers
@@ -37,12 +37,28 @@ impl RfqHandlers {
             .route("/rfqs/:id", get(Self::get_rfq))
             .route("/rfqs/:id/events", get(Self::list_events))
             .route("/rfqs/:id/messages", post(Self::post_message))
+            .route("/rfqs/:id/status", post(Self::change_status))
+            .route("/rfqs/:id/quotes", post(Self::submit_quote))
+            .route("/rfqs/:id/award", post(Self::award))
+            .route("/rfqs/:id/decline", post(Self::decline))
+            .route("/campaigns", post(Self::create_campaign))
+            .route("/campaigns/:id", get(Self::get_campaign))
+            .route("/manufacturers/:id/rfqs", get(Self::list_inbox))
+            .route(
+                "/manufacturers/:id/rfqs/:rfq_id/read",
+                post(Self::mark_inbox_read),
+            )
+            .route(
+                "/rfqs/:id/attachments/:attachment_id",
+                get(Self::download_attachment),
+            )
             .with_state(rfq_service)
     }
 
     /// POST /v1/rfqs - Create a new RFQ
     async fn create_rfq(
         State(service): State<Arc<RfqService>>,
+        caller: OptionalCaller,
         headers: HeaderMap,
         Json(request): Json<CreateRfqRequest>,
     ) -> Result<Json<CreateRfqResponse>> {
@@ -52,7 +68,7 @@ impl RfqHandlers {
         let idempotency_key = headers.get("idempotency-key").and_then(|h| h.to_str().ok());
 
         let response = service
-            .create_rfq(request, idempotency_key)
+            .create_rfq(&caller.principal(), request, idempotency_key)
             .await
             .map_err(AppError::from)?;
 
@@ -63,11 +79,12 @@ impl RfqHandlers {
     async fn get_rfq(
         State(service): State<Arc<RfqService>>,
         Path(rfq_id): Path<String>,
+        Caller(principal): Caller,
     ) -> Result<Json<RfqMeta>> {
         tracing::info!("Getting RFQ {}", rfq_id);
 
         let rfq = service
-            .get_rfq(&rfq_id)
+            .get_rfq(&rfq_id, &principal)
             .await
             .map_err(AppError::from)?
             .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, "not_found", "RFQ not found"))?;
@@ -79,18 +96,23 @@ impl RfqHandlers {
     async fn list_events(
         State(service): State<Arc<RfqService>>,
         Path(rfq_id): Path<String>,
+        Caller(principal): Caller,
         Query(params): Query<HashMap<String, String>>,
     ) -> Result<Json<ListEventsResponse>> {
         tracing::info!("Listing events for RFQ {}", rfq_id);
 
-        let since = params.get("since").cloned();
-        let limit = params
-            .get("limit")
-            .and_then(|s| s.parse().ok())
-            .map(|l: u32| l.min(200)); // Cap at 200 as per design
+        let query = ListEventsQuery {
+            since: params.get("since").cloned(),
+            cursor: params.get("cursor").cloned(),
+            before: params.get("before").cloned(),
+            limit: params
+                .get("limit")
+                .and_then(|s| s.parse().ok())
+                .map(|l: u32| l.min(200)), // Cap at 200 as per design
+        };
 
         let response = service
-            .list_events(&rfq_id, since, limit)
+            .list_events(&rfq_id, &principal, query)
             .await
             .map_err(AppError::from)?;
 
@@ -101,6 +123,7 @@ impl RfqHandlers {
     async fn post_message(
         State(service): State<Arc<RfqService>>,
         Path(rfq_id): Path<String>,
+        Caller(principal): Caller,
         headers: HeaderMap,
         Json(request): Json<PostMessageRequest>,
     ) -> Result<(StatusCode, Json<PostMessageResponse>)> {
@@ -110,148 +133,357 @@ impl RfqHandlers {
         let idempotency_key = headers.get("idempotency-key").and_then(|h| h.to_str().ok());
 
         let response = service
-            .post_message(&rfq_id, request, idempotency_key)
+            .post_message(&rfq_id, &principal, request, idempotency_key)
             .await
             .map_err(AppError::from)?;
 
         Ok((StatusCode::CREATED, Json(response)))
     }
+
+    /// GET /v1/rfqs/{id}/attachments/{attachment_id} - Presigned download URL for a participant
+    async fn download_attachment(
+        State(service): State<Arc<RfqService>>,
+        Path((rfq_id, attachment_id)): Path<(String, String)>,
+        Caller(principal): Caller,
+    ) -> Result<Json<AttachmentDownloadResponse>> {
+        tracing::info!(
+            "Issuing download of attachment {} on RFQ {}",
+            attachment_id,
+            rfq_id
+        );
+
+        let response = service
+            .attachment_download(&rfq_id, &attachment_id, &principal)
+            .await
+            .map_err(AppError::from)?;
+
+        Ok(Json(response))
+    }
+
+    /// POST /v1/rfqs/{id}/status - Close, archive or reopen an RFQ
+    async fn change_status(
+        State(service): State<Arc<RfqService>>,
+        Path(rfq_id): Path<String>,
+        Caller(principal): Caller,
+        headers: HeaderMap,
+        Json(request): Json<ChangeStatusRequest>,
+    ) -> Result<Json<ChangeStatusResponse>> {
+        tracing::info!("Changing status of RFQ {} to {}", rfq_id, request.status);
+
+        // Extract idempotency key from headers
+        let idempotency_key = headers.get("idempotency-key").and_then(|h| h.to_str().ok());
+
+        let response = service
+            .change_status(&rfq_id, &principal, request, idempotency_key)
+            .await
+            .map_err(AppError::from)?;
+
+        Ok(Json(response))
+    }
+
+    /// POST /v1/rfqs/{id}/quotes - Submit or revise the manufacturer's quote
+    async fn submit_quote(
+        State(service): State<Arc<RfqService>>,
+        Path(rfq_id): Path<String>,
+        Caller(principal): Caller,
+        headers: HeaderMap,
+        Json(request): Json<SubmitQuoteRequest>,
+    ) -> Result<Json<SubmitQuoteResponse>> {
+        tracing::info!("Submitting quote on RFQ {}", rfq_id);
+
+        // Extract idempotency key from headers
+        let idempotency_key = headers.get("idempotency-key").and_then(|h| h.to_str().ok());
+
+        let response = service
+            .submit_quote(&rfq_id, &principal, request, idempotency_key)
+            .await
+            .map_err(AppError::from)?;
+
+        Ok(Json(response))
+    }
+
+    /// POST /v1/campaigns - Send one RFQ to several manufacturers
+    async fn create_campaign(
+        State(service): State<Arc<RfqService>>,
+        caller: OptionalCaller,
+        headers: HeaderMap,
+        Json(request): Json<CreateCampaignRequest>,
+    ) -> Result<Json<CreateCampaignResponse>> {
+        tracing::info!(
+            "Creating RFQ campaign for {} manufacturers",
+            request.manufacturer_ids.len()
+        );
+
+        // Extract idempotency key from headers
+        let idempotency_key = headers.get("idempotency-key").and_then(|h| h.to_str().ok());
+
+        let response = service
+            .create_campaign(&caller.principal(), request, idempotency_key)
+            .await
+            .map_err(AppError::from)?;
+
+        Ok(Json(response))
+    }
+
+    /// GET /v1/campaigns/{id} - Buyer's side-by-side view of a campaign
+    async fn get_campaign(
+        State(service): State<Arc<RfqService>>,
+        Path(campaign_id): Path<String>,
+        Caller(principal): Caller,
+    ) -> Result<Json<CampaignResponse>> {
+        let response = service
+            .get_campaign(&campaign_id, &principal)
+            .await
+            .map_err(AppError::from)?;
+
+        Ok(Json(response))
+    }
+
+    /// GET /v1/manufacturers/{id}/rfqs - A manufacturer's RFQ inbox, for its
+    /// contact's token or a tenant API key with `rfq:read`
+    async fn list_inbox(
+        State(service): State<Arc<RfqService>>,
+        Path(manufacturer_id): Path<String>,
+        Caller(principal): Caller,
+        Query(params): Query<HashMap<String, String>>,
+    ) -> Result<Json<InboxResponse>> {
+        let unread = params
+            .get("unread")
+            .map(|unread| {
+                unread
+                    .parse()
+                    .map_err(|_| AppError::bad_request("unread must be 'true' or 'false'"))
+            })
+            .transpose()?;
+
+        let query = InboxQuery {
+            status: params.get("status").cloned(),
+            unread,
+            order: params.get("order").cloned(),
+            cursor: params.get("cursor").cloned(),
+            limit: params.get("limit").and_then(|s| s.parse().ok()),
+        };
+
+        let response = service
+            .list_inbox(&manufacturer_id, query, &principal)
+            .await
+            .map_err(AppError::from)?;
+
+        Ok(Json(response))
+    }
+
+    /// POST /v1/manufacturers/{id}/rfqs/{rfq_id}/read - Mark an inbox RFQ as read
+    async fn mark_inbox_read(
+        State(service): State<Arc<RfqService>>,
+        Path((manufacturer_id, rfq_id)): Path<(String, String)>,
+        Caller(principal): Caller,
+    ) -> Result<Json<InboxItemDto>> {
+        let response = service
+            .mark_inbox_read(&manufacturer_id, &rfq_id, &principal)
+            .await
+            .map_err(AppError::from)?;
+
+        Ok(Json(response))
+    }
+
+    /// POST /v1/rfqs/{id}/award - Buyer awards the RFQ
+    async fn award(
+        State(service): State<Arc<RfqService>>,
+        Path(rfq_id): Path<String>,
+        Caller(principal): Caller,
+        headers: HeaderMap,
+        Json(request): Json<RfqDecisionRequest>,
+    ) -> Result<Json<ChangeStatusResponse>> {
+        tracing::info!("Awarding RFQ {}", rfq_id);
+
+        // Extract idempotency key from headers
+        let idempotency_key = headers.get("idempotency-key").and_then(|h| h.to_str().ok());
+
+        let response = service
+            .award(&rfq_id, &principal, request, idempotency_key)
+            .await
+            .map_err(AppError::from)?;
+
+        Ok(Json(response))
+    }
+
+    /// POST /v1/rfqs/{id}/decline - Buyer declines the RFQ
+    async fn decline(
+        State(service): State<Arc<RfqService>>,
+        Path(rfq_id): Path<String>,
+        Caller(principal): Caller,
+        headers: HeaderMap,
+        Json(request): Json<RfqDecisionRequest>,
+    ) -> Result<Json<ChangeStatusResponse>> {
+        tracing::info!("Declining RFQ {}", rfq_id);
+
+        // Extract idempotency key from headers
+        let idempotency_key = headers.get("idempotency-key").and_then(|h| h.to_str().ok());
+
+        let response = service
+            .decline(&rfq_id, &principal, request, idempotency_key)
+            .await
+            .map_err(AppError::from)?;
+
+        Ok(Json(response))
+    }
 }
 
 /// Upload handlers for presigned URLs
 pub struct UploadHandlers;
 
 impl UploadHandlers {
-    pub fn router(image_service: Arc<dyn ImageService + Send + Sync>) -> Router {
+    pub fn router(upload_service: Arc<UploadService>) -> Router {
         Router::new()
             .route("/uploads/presign", post(Self::presign_upload))
-            .with_state(image_service)
+            .with_state(upload_service)
     }
 
-    /// POST /v1/uploads/presign - Generate presigned upload URL
+    /// POST /v1/uploads/presign - Generate presigned upload URL into the
+    /// tenant the caller's credentials are bound to
     async fn presign_upload(
-        State(image_service): State<Arc<dyn ImageService + Send + Sync>>,
+        State(upload_service): State<Arc<UploadService>>,
+        caller: OptionalCaller,
         Json(request): Json<PresignUploadRequest>,
     ) -> Result<Json<PresignUploadResponse>> {
-        tracing::info!("Generating presigned URL for tenant {}", request.tenant_id);
-
-        // Validate and convert request - using simple validation for MVP
-        if request.tenant_id.is_empty() {
-            return Err(AppError::bad_request("Tenant ID cannot be empty"));
-        }
-
-        if request.content_type.is_empty() {
-            return Err(AppError::bad_request("Content type cannot be empty"));
-        }
-
-        if request.size_bytes == 0 || request.size_bytes > 15 * 1024 * 1024 {
-            return Err(AppError::bad_request(
-                "File size must be between 1 byte and 15MB",
-            ));
-        }
-
-        // For MVP, create simple wrappers
-        let tenant_id = TenantId::new(request.tenant_id)
-            .map_err(|e| AppError::bad_request(&format!("Invalid tenant ID: {}", e)))?;
-        let content_type = ContentType::new(request.content_type)
-            .map_err(|e| AppError::bad_request(&format!("Invalid content type: {}", e)))?;
-        let file_size = FileSize::new(request.size_bytes)
-            .map_err(|e| AppError::bad_request(&format!("Invalid file size: {}", e)))?;
-
-        // Generate presigned URL
-        let response = image_service
-            .generate_presigned_upload_url(&tenant_id, &content_type, &file_size)
+        let response = upload_service
+            .presign_upload(&caller.principal(), request)
             .await
-            .map_err(|e| {
-                AppError::internal_server_error(&format!("Failed to generate upload URL: {}", e))
-            })?;
+            .map_err(AppError::from)?;
 
         Ok(Json(response))
     }
 }
 
-/// Manufacturer handlers (admin endpoints)
+/// Manufacturer handlers
 pub struct ManufacturerHandlers;
 
 impl ManufacturerHandlers {
-    pub fn router(manufacturer_repo: Arc<dyn ManufacturerRepository + Send + Sync>) -> Router {
+    pub fn router(
+        manufacturer_service: Arc<ManufacturerService>,
+        admin_credentials: Arc<AdminCredentials>,
+    ) -> Router {
         Router::new()
             .route("/manufacturers", post(Self::create_manufacturer))
-            .with_state(manufacturer_repo)
+            .route_layer(RequireAdminLayer::new(admin_credentials))
+            .route("/manufacturers/:id", put(Self::update_manufacturer))
+            .with_state(manufacturer_service)
     }
 
     /// POST /v1/manufacturers - Create/update manufacturer (admin)
     async fn create_manufacturer(
-        State(manufacturer_repo): State<Arc<dyn ManufacturerRepository + Send + Sync>>,
-        headers: HeaderMap,
+        State(service): State<Arc<ManufacturerService>>,
+        Extension(admin): Extension<AdminClaims>,
+        Extension(principal): Extension<Principal>,
         Json(request): Json<CreateManufacturerRequest>,
     ) -> Result<(StatusCode, Json<CreateManufacturerResponse>)> {
         tracing::info!(
-            "Creating/updating manufacturer for tenant {}",
-            request.tenant_id
+            "Creating/updating manufacturer for tenant {} as {}",
+            request.tenant_id,
+            admin.subject
         );
 
-        // Check for authentication header (simplified for MVP)
-        let _auth_header = headers
-            .get("authorization")
-            .and_then(|h| h.to_str().ok())
-            .ok_or_else(|| {
-                AppError::new(
-                    StatusCode::UNAUTHORIZED,
-                    "unauthorized",
-                    "Missing authorization header",
-                )
-            })?;
-
-        // Convert DTO to domain entity
-        let manufacturer = Manufacturer {
-            id: ManufacturerId::generate().as_str().to_string(),
-            tenant_id: request.tenant_id.clone(),
-            name: request.name,
-            description: request.description,
-            location: request.location.map(|loc| Location {
-                city: loc.city,
-                state: loc.state,
-                country: loc.country,
-                lat: loc.lat,
-                lng: loc.lng,
-            }),
-            categories: request.capabilities.clone(), // Using capabilities as categories for now
-            capabilities: Some(request.capabilities),
-            contact_email: Some(request.contact_email),
-            media: None, // Will be populated later via image uploads
-            updated_at: chrono::Utc::now(),
-        };
+        let response = service
+            .create_manufacturer(&principal, request)
+            .await
+            .map_err(AppError::from)?;
 
-        // Save manufacturer - convert to ManufacturerProfile first
-        let profile = ManufacturerProfile {
-            id: manufacturer.id.clone(),
-            tenant_id: manufacturer.tenant_id.clone(),
-            name: manufacturer.name.clone(),
-            description: manufacturer.description.clone(),
-            location: manufacturer.location.clone(),
-            categories: manufacturer.categories.clone(),
-            capabilities: manufacturer.capabilities.clone(),
-            contact_email: manufacturer.contact_email.clone(),
-            media: manufacturer.media.clone(),
-            offerings: None, // Will be populated later
-            updated_at: manufacturer.updated_at,
-        };
+        Ok((StatusCode::CREATED, Json(response)))
+    }
 
-        manufacturer_repo
-            .save_manufacturer(&profile)
+    /// PUT /v1/manufacturers/{id} - Tenant edits its own profile and offerings
+    async fn update_manufacturer(
+        State(service): State<Arc<ManufacturerService>>,
+        Path(manufacturer_id): Path<String>,
+        Caller(principal): Caller,
+        Json(request): Json<UpdateManufacturerRequest>,
+    ) -> Result<Json<ManufacturerProfile>> {
+        let response = service
+            .update_manufacturer(&manufacturer_id, &principal, request)
             .await
-            .map_err(|e| {
-                AppError::internal_server_error(&format!("Failed to create manufacturer: {}", e))
-            })?;
+            .map_err(AppError::from)?;
 
-        let response = CreateManufacturerResponse {
-            id: manufacturer.id.clone(),
-            tenant_id: manufacturer.tenant_id.clone(),
-        };
+        Ok(Json(response))
+    }
+}
+
+/// Tenant API key handlers (admin endpoints)
+pub struct ApiKeyHandlers;
+
+impl ApiKeyHandlers {
+    pub fn router(
+        api_key_service: Arc<ApiKeyService>,
+        admin_credentials: Arc<AdminCredentials>,
+    ) -> Router {
+        Router::new()
+            .route(
+                "/tenants/:tenant_id/api-keys",
+                post(Self::create_key).get(Self::list_keys),
+            )
+            .route(
+                "/tenants/:tenant_id/api-keys/:key_id/revoke",
+                post(Self::revoke_key),
+            )
+            .route_layer(RequireAdminLayer::new(admin_credentials))
+            .with_state(api_key_service)
+    }
+
+    /// POST /v1/tenants/{tenant_id}/api-keys - Issue a key; shown only once
+    async fn create_key(
+        State(service): State<Arc<ApiKeyService>>,
+        Path(tenant_id): Path<String>,
+        Extension(admin): Extension<AdminClaims>,
+        Extension(principal): Extension<Principal>,
+        Json(request): Json<CreateApiKeyRequest>,
+    ) -> Result<(StatusCode, Json<CreateApiKeyResponse>)> {
+        let response = service
+            .create_key(&tenant_id, &principal, request)
+            .await
+            .map_err(AppError::from)?;
+        tracing::info!(
+            "Issued API key {} for tenant {} as {}",
+            response.api_key.id,
+            tenant_id,
+            admin.subject
+        );
 
         Ok((StatusCode::CREATED, Json(response)))
     }
+
+    /// GET /v1/tenants/{tenant_id}/api-keys - A tenant's keys, without secrets
+    async fn list_keys(
+        State(service): State<Arc<ApiKeyService>>,
+        Path(tenant_id): Path<String>,
+        Extension(principal): Extension<Principal>,
+    ) -> Result<Json<ApiKeysResponse>> {
+        let response = service
+            .list_keys(&tenant_id, &principal)
+            .await
+            .map_err(AppError::from)?;
+
+        Ok(Json(response))
+    }
+
+    /// POST /v1/tenants/{tenant_id}/api-keys/{key_id}/revoke - Revoke a key
+    async fn revoke_key(
+        State(service): State<Arc<ApiKeyService>>,
+        Path((tenant_id, key_id)): Path<(String, String)>,
+        Extension(admin): Extension<AdminClaims>,
+        Extension(principal): Extension<Principal>,
+    ) -> Result<Json<ApiKeyDto>> {
+        let response = service
+            .revoke_key(&tenant_id, &key_id, &principal)
+            .await
+            .map_err(AppError::from)?;
+        tracing::info!(
+            "Revoked API key {} of tenant {} as {}",
+            key_id,
+            tenant_id,
+            admin.subject
+        );
+
+        Ok(Json(response))
+    }
 }
 
 /// Health check handler
@@ -262,15 +494,23 @@ pub async fn health_check() -> &'static str {
 /// Create the main application router
 pub fn create_app_router(
     rfq_service: Arc<RfqService>,
-    image_service: Arc<dyn ImageService + Send + Sync>,
-    manufacturer_repo: Arc<dyn ManufacturerRepository + Send + Sync>,
+    upload_service: Arc<UploadService>,
+    manufacturer_service: Arc<ManufacturerService>,
+    api_key_service: Arc<ApiKeyService>,
+    authenticator: Arc<Authenticator>,
+    admin_credentials: Arc<AdminCredentials>,
 ) -> Router {
     Router::new().route("/health", get(health_check)).nest(
         "/v1",
         Router::new()
             .merge(RfqHandlers::router(rfq_service))
-            .merge(UploadHandlers::router(image_service))
-            .merge(ManufacturerHandlers::router(manufacturer_repo)),
+            .merge(UploadHandlers::router(upload_service))
+            .merge(ManufacturerHandlers::router(
+                manufacturer_service,
+                admin_credentials.clone(),
+            ))
+            .merge(ApiKeyHandlers::router(api_key_service, admin_credentials))
+            .layer(Extension(authenticator)),
     )
 }
 
@@ -280,3 +520,371 @@ pub struct ErrorResponse {
     pub message: String,
     pub details: Option<serde_json::Value>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use application::access::AccessTokens;
+    use axum::body::{to_bytes, Body};
+    use axum::http::{header, Method, Request};
+    use infrastructure::memory::*;
+    use tower::ServiceExt;
+
+    /// The full router, and the token issuer for handing out participant tokens
+    fn app() -> (Router, Arc<AccessTokens>) {
+        let rfqs = Arc::new(InMemoryRfqRepository::new());
+        let manufacturers = Arc::new(InMemoryManufacturerRepository::with_manufacturers(vec![
+            ManufacturerProfile {
+                id: "mfg_acme".to_string(),
+                tenant_id: "t1".to_string(),
+                name: "Acme".to_string(),
+                description: None,
+                location: None,
+                categories: Vec::new(),
+                capabilities: None,
+                contact_email: Some("sales@acme.example".to_string()),
+                media: None,
+                offerings: None,
+                updated_at: chrono::Utc::now(),
+            },
+        ]));
+        let images = Arc::new(InMemoryImageService::new());
+        let access_tokens = Arc::new(AccessTokens::with_random_secret(chrono::Duration::days(1)));
+        let admin_credentials = Arc::new(AdminCredentials::new(&[], None).unwrap());
+        let api_keys = Arc::new(ApiKeyService::new(
+            Arc::new(InMemoryApiKeyRepository::new()),
+        ));
+
+        let router = create_app_router(
+            Arc::new(RfqService::new(
+                rfqs.clone(),
+                manufacturers.clone(),
+                Arc::new(InMemoryEmailService::new()),
+                Arc::new(InMemoryIdempotencyService::new()),
+                images.clone(),
+                access_tokens.clone(),
+            )),
+            Arc::new(UploadService::new(images, rfqs, manufacturers.clone())),
+            Arc::new(ManufacturerService::new(manufacturers)),
+            api_keys.clone(),
+            Arc::new(
+                Authenticator::new()
+                    .with_access_tokens(access_tokens.clone())
+                    .with_api_keys(api_keys)
+                    .with_admin_credentials(admin_credentials.clone()),
+            ),
+            admin_credentials,
+        );
+        (router, access_tokens)
+    }
+
+    async fn send(
+        app: &Router,
+        method: Method,
+        uri: &str,
+        token: Option<&str>,
+        body: Option<serde_json::Value>,
+    ) -> (StatusCode, serde_json::Value) {
+        send_keyed(app, method, uri, token, None, body).await
+    }
+
+    /// `send` with an `Idempotency-Key` header
+    async fn send_keyed(
+        app: &Router,
+        method: Method,
+        uri: &str,
+        token: Option<&str>,
+        idempotency_key: Option<&str>,
+        body: Option<serde_json::Value>,
+    ) -> (StatusCode, serde_json::Value) {
+        let mut request = Request::builder().method(method).uri(uri);
+        if let Some(token) = token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        if let Some(key) = idempotency_key {
+            request = request.header("idempotency-key", key);
+        }
+        let request = match body {
+            Some(body) => request
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string())),
+            None => request.body(Body::empty()),
+        }
+        .unwrap();
+
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let json = serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null);
+        (status, json)
+    }
+
+    #[tokio::test]
+    async fn buyer_creates_and_reads_an_rfq() {
+        let (app, _) = app();
+        let (status, created) = send(
+            &app,
+            Method::POST,
+            "/v1/rfqs",
+            None,
+            Some(serde_json::json!({
+                "tenant_id": "t1",
+                "manufacturer_id": "mfg_acme",
+                "buyer": { "email": "buyer@example.com", "name": null },
+                "subject": "Brackets",
+                "body": "Can you quote 500 brackets?",
+                "attachments": null
+            })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{}", created);
+        let id = created["id"].as_str().unwrap();
+        let token = created["access_token"].as_str().unwrap();
+
+        let events_uri = format!("/v1/rfqs/{}/events", id);
+        let (status, page) = send(&app, Method::GET, &events_uri, Some(token), None).await;
+        assert_eq!(status, StatusCode::OK, "{}", page);
+        let messages: Vec<_> = page["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|event| event["type"] == "message")
+            .map(|event| &event["body"])
+            .collect();
+        assert_eq!(messages, ["Can you quote 500 brackets?"]);
+
+        let (status, error) = send(&app, Method::GET, &events_uri, None, None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(error["code"], "unauthorized");
+
+        let (status, _) = send(
+            &app,
+            Method::GET,
+            &format!("{}?token={}", events_uri, token),
+            None,
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, _) = send(&app, Method::GET, &events_uri, Some("not-a-token"), None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn a_reused_idempotency_key_with_another_body_is_a_409() {
+        let (app, _) = app();
+        let (_, created) = send(
+            &app,
+            Method::POST,
+            "/v1/rfqs",
+            None,
+            Some(serde_json::json!({
+                "tenant_id": "t1",
+                "manufacturer_id": "mfg_acme",
+                "buyer": { "email": "buyer@example.com", "name": null },
+                "subject": "Brackets",
+                "body": "Can you quote 500 brackets?",
+                "attachments": null
+            })),
+        )
+        .await;
+        let uri = format!("/v1/rfqs/{}/messages", created["id"].as_str().unwrap());
+        let token = created["access_token"].as_str();
+        let post = |body: &'static str| {
+            send_keyed(
+                &app,
+                Method::POST,
+                &uri,
+                token,
+                Some("k1"),
+                Some(serde_json::json!({ "body": body, "attachments": null })),
+            )
+        };
+
+        let (status, first) = post("First").await;
+        assert_eq!(status, StatusCode::CREATED, "{}", first);
+        let (status, replayed) = post("First").await;
+        assert_eq!(status, StatusCode::CREATED, "{}", replayed);
+        assert_eq!(replayed, first);
+
+        let (status, error) = post("Second").await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(error["code"], "conflict");
+    }
+
+    /// Open an RFQ with Acme, returning its id, the buyer's token and Acme's token
+    async fn open_rfq(app: &Router, access_tokens: &AccessTokens) -> (String, String, String) {
+        let (status, created) = send(
+            app,
+            Method::POST,
+            "/v1/rfqs",
+            None,
+            Some(serde_json::json!({
+                "tenant_id": "t1",
+                "manufacturer_id": "mfg_acme",
+                "buyer": { "email": "buyer@example.com", "name": null },
+                "subject": "Brackets",
+                "body": "Can you quote 500 brackets?",
+                "attachments": null
+            })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{}", created);
+        let id = created["id"].as_str().unwrap().to_string();
+        let maker = access_tokens.issue(&id, ParticipantRole::Manufacturer, "sales@acme.example");
+        (
+            id,
+            created["access_token"].as_str().unwrap().to_string(),
+            maker,
+        )
+    }
+
+    fn quote() -> serde_json::Value {
+        serde_json::json!({
+            "line_items": [{
+                "part": "BRKT-104",
+                "quantity": { "value": "500", "unit": "pcs" },
+                "unit_price": { "amount": "12.50", "currency": "USD" }
+            }],
+            "valid_until": "2099-12-31"
+        })
+    }
+
+    #[tokio::test]
+    async fn participants_reply_quote_and_decide_over_http() {
+        let (app, access_tokens) = app();
+        let (id, buyer, maker) = open_rfq(&app, &access_tokens).await;
+        let uri = |route: &str| format!("/v1/rfqs/{}/{}", id, route);
+
+        let (status, message) = send(
+            &app,
+            Method::POST,
+            &uri("messages"),
+            Some(&maker),
+            Some(serde_json::json!({ "body": "Yes, 3 weeks.", "attachments": null })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED, "{}", message);
+
+        let (status, quoted) = send(
+            &app,
+            Method::POST,
+            &uri("quotes"),
+            Some(&maker),
+            Some(quote()),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{}", quoted);
+        assert_eq!(quoted["revision"], 1);
+
+        let (status, closed) = send(
+            &app,
+            Method::POST,
+            &uri("status"),
+            Some(&buyer),
+            Some(serde_json::json!({ "status": "closed", "note": null })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{}", closed);
+        assert_eq!(closed["status"], "closed");
+        let (status, reopened) = send(
+            &app,
+            Method::POST,
+            &uri("status"),
+            Some(&buyer),
+            Some(serde_json::json!({ "status": "open", "note": null })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{}", reopened);
+        assert_eq!(reopened["status"], "open");
+
+        let (status, awarded) = send(
+            &app,
+            Method::POST,
+            &uri("award"),
+            Some(&buyer),
+            Some(serde_json::json!({ "reason": "Best lead time" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{}", awarded);
+        assert_eq!(awarded["status"], "awarded");
+
+        let (id, buyer, _) = open_rfq(&app, &access_tokens).await;
+        let (status, declined) = send(
+            &app,
+            Method::POST,
+            &format!("/v1/rfqs/{}/decline", id),
+            Some(&buyer),
+            Some(serde_json::json!({ "reason": null })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{}", declined);
+        // A declined RFQ is closed; the decision itself is in its events
+        assert_eq!(declined["status"], "closed");
+    }
+
+    #[tokio::test]
+    async fn rfq_writes_need_credentials_of_the_right_party() {
+        let (app, access_tokens) = app();
+        let (id, buyer, maker) = open_rfq(&app, &access_tokens).await;
+        let (_, other_buyer, _) = open_rfq(&app, &access_tokens).await;
+        let message = serde_json::json!({ "body": "Hello", "attachments": null });
+        let status_change = serde_json::json!({ "status": "closed", "note": null });
+        let decision = serde_json::json!({ "reason": null });
+        let routes = [
+            ("messages", message.clone()),
+            ("status", status_change.clone()),
+            ("quotes", quote()),
+            ("award", decision.clone()),
+            ("decline", decision.clone()),
+        ];
+
+        for (route, body) in &routes {
+            let uri = format!("/v1/rfqs/{}/{}", id, route);
+            for token in [None, Some("not-a-token")] {
+                let (status, error) =
+                    send(&app, Method::POST, &uri, token, Some(body.clone())).await;
+                assert_eq!(
+                    status,
+                    StatusCode::UNAUTHORIZED,
+                    "{} with {:?}",
+                    route,
+                    token
+                );
+                assert_eq!(error["code"], "unauthorized");
+            }
+
+            // Another RFQ's buyer is not a participant of this one
+            let (status, error) = send(
+                &app,
+                Method::POST,
+                &uri,
+                Some(&other_buyer),
+                Some(body.clone()),
+            )
+            .await;
+            assert_eq!(status, StatusCode::FORBIDDEN, "{} by another buyer", route);
+            assert_eq!(error["code"], "forbidden");
+        }
+
+        for (route, token) in [("quotes", &buyer), ("award", &maker), ("decline", &maker)] {
+            let body = &routes.iter().find(|(r, _)| *r == route).unwrap().1;
+            let (status, error) = send(
+                &app,
+                Method::POST,
+                &format!("/v1/rfqs/{}/{}", id, route),
+                Some(token),
+                Some(body.clone()),
+            )
+            .await;
+            assert_eq!(
+                status,
+                StatusCode::FORBIDDEN,
+                "{} by the wrong party",
+                route
+            );
+            assert_eq!(error["code"], "forbidden");
+        }
+    }
+}
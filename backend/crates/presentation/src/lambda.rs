@@ -0,0 +1,607 @@
+use axum::{
+    body::{to_bytes, Body},
+    http::{header, HeaderMap, HeaderName, HeaderValue, Method, Request, Response},
+    Router,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use lambda_runtime::{Error, LambdaEvent};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use tower::ServiceExt;
+
+/// Which API Gateway payload format an invocation arrived in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PayloadVersion {
+    /// REST API (and HTTP API with payload format 1.0)
+    V1,
+    /// HTTP API payload format 2.0
+    V2,
+}
+
+/// API Gateway request context made available to handlers as a request extension
+#[derive(Debug, Clone)]
+pub struct ApiGatewayContext {
+    pub version: PayloadVersion,
+    pub request_id: Option<String>,
+    pub stage: Option<String>,
+    pub path_parameters: HashMap<String, String>,
+}
+
+/// Run an API Gateway proxy event through the router and build the proxy response
+pub async fn handle_event(router: Router, event: LambdaEvent<Value>) -> Result<Value, Error> {
+    let (payload, _context) = event.into_parts();
+
+    let (request, version) = into_request(payload)?;
+    tracing::info!(
+        "Routing {} {} from API Gateway",
+        request.method(),
+        request.uri().path()
+    );
+
+    let response = router.oneshot(request).await?;
+
+    from_response(response, version).await
+}
+
+/// Convert an API Gateway v1 or v2 proxy payload into an axum request
+pub fn into_request(payload: Value) -> Result<(Request<Body>, PayloadVersion), Error> {
+    let version = if payload.get("version").and_then(Value::as_str) == Some("2.0") {
+        PayloadVersion::V2
+    } else if payload.get("httpMethod").is_some() {
+        PayloadVersion::V1
+    } else {
+        return Err(Error::from(
+            "Unsupported event: not an API Gateway proxy payload",
+        ));
+    };
+
+    let context = &payload["requestContext"];
+    let stage = str_field(context, "stage");
+
+    let (method, path, query) = match version {
+        PayloadVersion::V2 => {
+            let method = str_field(&context["http"], "method").unwrap_or_default();
+            let raw_path = str_field(&payload, "rawPath").unwrap_or_else(|| "/".to_string());
+            let query = str_field(&payload, "rawQueryString").unwrap_or_default();
+            (method, strip_stage(raw_path, stage.as_deref()), query)
+        }
+        PayloadVersion::V1 => {
+            let method = str_field(&payload, "httpMethod").unwrap_or_default();
+            let path = str_field(&payload, "path").unwrap_or_else(|| "/".to_string());
+            (method, path, v1_query_string(&payload))
+        }
+    };
+
+    let method = Method::from_bytes(method.as_bytes())
+        .map_err(|e| Error::from(format!("Invalid HTTP method '{}': {}", method, e)))?;
+
+    let uri = if query.is_empty() {
+        path
+    } else {
+        format!("{}?{}", path, query)
+    };
+
+    let mut builder = Request::builder().method(method).uri(uri);
+
+    if let Some(headers) = builder.headers_mut() {
+        match version {
+            PayloadVersion::V2 => {
+                append_headers(headers, &payload["headers"]);
+                if let Some(cookies) = payload["cookies"].as_array() {
+                    let cookie = cookies
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .collect::<Vec<_>>()
+                        .join("; ");
+                    if !cookie.is_empty() {
+                        if let Ok(value) = HeaderValue::from_str(&cookie) {
+                            headers.insert(header::COOKIE, value);
+                        }
+                    }
+                }
+            }
+            PayloadVersion::V1 => {
+                if payload["multiValueHeaders"].is_object() {
+                    append_headers(headers, &payload["multiValueHeaders"]);
+                } else {
+                    append_headers(headers, &payload["headers"]);
+                }
+            }
+        }
+    }
+
+    let body = match payload.get("body").and_then(Value::as_str) {
+        Some(body) if payload["isBase64Encoded"].as_bool().unwrap_or(false) => BASE64
+            .decode(body)
+            .map_err(|e| Error::from(format!("Invalid base64 request body: {}", e)))?,
+        Some(body) => body.as_bytes().to_vec(),
+        None => Vec::new(),
+    };
+
+    let path_parameters = payload["pathParameters"]
+        .as_object()
+        .map(|params| {
+            params
+                .iter()
+                .filter_map(|(k, v)| v.as_str().map(|v| (k.clone(), v.to_string())))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let request = builder
+        .extension(ApiGatewayContext {
+            version,
+            request_id: str_field(context, "requestId"),
+            stage,
+            path_parameters,
+        })
+        .body(Body::from(body))
+        .map_err(|e| Error::from(format!("Failed to build request: {}", e)))?;
+
+    Ok((request, version))
+}
+
+/// Convert an axum response into the proxy response shape API Gateway expects
+pub async fn from_response(
+    response: Response<Body>,
+    version: PayloadVersion,
+) -> Result<Value, Error> {
+    let (parts, body) = response.into_parts();
+    let bytes = to_bytes(body, usize::MAX)
+        .await
+        .map_err(|e| Error::from(format!("Failed to read response body: {}", e)))?;
+
+    let is_base64_encoded = !is_text_response(&parts.headers);
+    let body = if is_base64_encoded {
+        BASE64.encode(&bytes)
+    } else {
+        String::from_utf8_lossy(&bytes).into_owned()
+    };
+
+    let mut headers = Map::new();
+    let mut multi_value_headers = Map::new();
+    let mut cookies = Vec::new();
+
+    for name in parts.headers.keys() {
+        let values: Vec<String> = parts
+            .headers
+            .get_all(name)
+            .iter()
+            .filter_map(|v| v.to_str().ok().map(str::to_string))
+            .collect();
+
+        if version == PayloadVersion::V2 && name == header::SET_COOKIE {
+            cookies.extend(values.into_iter().map(Value::String));
+            continue;
+        }
+
+        headers.insert(name.to_string(), Value::String(values.join(",")));
+        multi_value_headers.insert(
+            name.to_string(),
+            Value::Array(values.into_iter().map(Value::String).collect()),
+        );
+    }
+
+    let mut result = json!({
+        "statusCode": parts.status.as_u16(),
+        "headers": headers,
+        "body": body,
+        "isBase64Encoded": is_base64_encoded,
+    });
+
+    match version {
+        PayloadVersion::V1 => result["multiValueHeaders"] = Value::Object(multi_value_headers),
+        PayloadVersion::V2 => result["cookies"] = Value::Array(cookies),
+    }
+
+    Ok(result)
+}
+
+fn str_field(value: &Value, field: &str) -> Option<String> {
+    value.get(field).and_then(Value::as_str).map(str::to_string)
+}
+
+/// HTTP APIs with a named stage include it as the first path segment
+fn strip_stage(path: String, stage: Option<&str>) -> String {
+    match stage {
+        Some(stage) if stage != "$default" => {
+            let prefix = format!("/{}", stage);
+            match path.strip_prefix(&prefix) {
+                Some("") => "/".to_string(),
+                Some(rest) if rest.starts_with('/') => rest.to_string(),
+                _ => path,
+            }
+        }
+        _ => path,
+    }
+}
+
+/// REST API payloads carry decoded query parameters, so they need re-encoding
+fn v1_query_string(payload: &Value) -> String {
+    let mut serializer = form_urlencoded::Serializer::new(String::new());
+
+    if let Some(params) = payload["multiValueQueryStringParameters"].as_object() {
+        for (name, values) in params {
+            for value in values.as_array().into_iter().flatten() {
+                if let Some(value) = value.as_str() {
+                    serializer.append_pair(name, value);
+                }
+            }
+        }
+    } else if let Some(params) = payload["queryStringParameters"].as_object() {
+        for (name, value) in params {
+            if let Some(value) = value.as_str() {
+                serializer.append_pair(name, value);
+            }
+        }
+    }
+
+    serializer.finish()
+}
+
+fn append_headers(headers: &mut HeaderMap, source: &Value) {
+    let Some(source) = source.as_object() else {
+        return;
+    };
+
+    for (name, values) in source {
+        let Ok(name) = HeaderName::from_bytes(name.as_bytes()) else {
+            tracing::warn!("Skipping invalid header name {}", name);
+            continue;
+        };
+
+        let values = match values {
+            Value::Array(values) => values.iter().filter_map(Value::as_str).collect(),
+            Value::String(value) => vec![value.as_str()],
+            _ => Vec::new(),
+        };
+
+        for value in values {
+            if let Ok(value) = HeaderValue::from_str(value) {
+                headers.append(name.clone(), value);
+            }
+        }
+    }
+}
+
+fn is_text_response(headers: &HeaderMap) -> bool {
+    if headers.contains_key(header::CONTENT_ENCODING) {
+        return false;
+    }
+
+    match headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+    {
+        Some(content_type) => {
+            let content_type = content_type.to_ascii_lowercase();
+            content_type.starts_with("text/")
+                || content_type.starts_with("application/json")
+                || content_type.starts_with("application/xml")
+                || content_type.starts_with("application/javascript")
+                || content_type.contains("+json")
+                || content_type.contains("+xml")
+        }
+        // Bodies without a content type are plain strings such as the health check
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::StatusCode, routing::get};
+
+    fn fixture(name: &str) -> Value {
+        let json = match name {
+            "v1" => include_str!("../tests/fixtures/apigw_v1_rest.json"),
+            "v2" => include_str!("../tests/fixtures/apigw_v2_http.json"),
+            _ => unreachable!(),
+        };
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn header_values<'a>(request: &'a Request<Body>, name: &str) -> Vec<&'a str> {
+        request
+            .headers()
+            .get_all(name)
+            .iter()
+            .map(|value| value.to_str().unwrap())
+            .collect()
+    }
+
+    fn query_pairs(request: &Request<Body>) -> Vec<(String, String)> {
+        let mut pairs: Vec<(String, String)> =
+            form_urlencoded::parse(request.uri().query().unwrap_or_default().as_bytes())
+                .into_owned()
+                .collect();
+        pairs.sort();
+        pairs
+    }
+
+    async fn body(request: Request<Body>) -> Vec<u8> {
+        to_bytes(request.into_body(), usize::MAX)
+            .await
+            .unwrap()
+            .to_vec()
+    }
+
+    #[tokio::test]
+    async fn rest_v1_payloads_become_requests() {
+        let (request, version) = into_request(fixture("v1")).unwrap();
+
+        assert_eq!(version, PayloadVersion::V1);
+        assert_eq!(request.method(), Method::POST);
+        assert_eq!(request.uri().path(), "/v1/rfqs/r_1/messages");
+        // Multi-value parameters win over the single-value ones and are re-encoded
+        let query = request.uri().query().unwrap();
+        assert!(query.contains("tag=a+b"), "{}", query);
+        assert!(query.contains("tag=c%26d"), "{}", query);
+        assert!(query.contains("note=%C3%BCmlaut"), "{}", query);
+        assert_eq!(
+            query_pairs(&request),
+            [
+                ("note".to_string(), "ümlaut".to_string()),
+                ("tag".to_string(), "a b".to_string()),
+                ("tag".to_string(), "c&d".to_string()),
+            ]
+        );
+        assert_eq!(
+            header_values(&request, "accept"),
+            ["application/json", "text/plain"]
+        );
+        assert_eq!(
+            header_values(&request, "x-forwarded-for"),
+            ["203.0.113.1", "198.51.100.7"]
+        );
+
+        let context = request.extensions().get::<ApiGatewayContext>().unwrap();
+        assert_eq!(context.version, PayloadVersion::V1);
+        assert_eq!(context.stage.as_deref(), Some("prod"));
+        assert_eq!(
+            context.request_id.as_deref(),
+            Some("c6af9ac6-7b61-11e6-9a41-93e8deadbeef")
+        );
+        assert_eq!(context.path_parameters["id"], "r_1");
+
+        assert_eq!(body(request).await, br#"{"body":"Hello"}"#);
+    }
+
+    #[tokio::test]
+    async fn rest_v1_payloads_fall_back_to_single_value_fields() {
+        let mut payload = fixture("v1");
+        let object = payload.as_object_mut().unwrap();
+        object.remove("multiValueHeaders");
+        object.remove("multiValueQueryStringParameters");
+        object.insert("body".to_string(), json!("plain text"));
+        object.insert("isBase64Encoded".to_string(), json!(false));
+
+        let (request, _) = into_request(payload).unwrap();
+        assert_eq!(
+            query_pairs(&request),
+            [
+                ("note".to_string(), "ümlaut".to_string()),
+                ("tag".to_string(), "c&d".to_string()),
+            ]
+        );
+        assert_eq!(header_values(&request, "accept"), ["text/plain"]);
+        assert_eq!(body(request).await, b"plain text");
+    }
+
+    #[tokio::test]
+    async fn http_v2_payloads_become_requests() {
+        let (request, version) = into_request(fixture("v2")).unwrap();
+
+        assert_eq!(version, PayloadVersion::V2);
+        assert_eq!(request.method(), Method::GET);
+        // The stage is stripped and the raw query string passed through as sent
+        assert_eq!(
+            request.uri().to_string(),
+            "/v1/rfqs/r_1/events?since=2026-01-01T00%3A00%3A00Z&limit=5&tag=a+b"
+        );
+        assert_eq!(
+            header_values(&request, "accept"),
+            ["application/json,text/plain"]
+        );
+        assert_eq!(header_values(&request, "authorization"), ["Bearer token-1"]);
+        assert_eq!(
+            header_values(&request, "cookie"),
+            ["session=abc; theme=dark"]
+        );
+
+        let context = request.extensions().get::<ApiGatewayContext>().unwrap();
+        assert_eq!(context.version, PayloadVersion::V2);
+        assert_eq!(context.request_id.as_deref(), Some("JKJaXmPLvHcESHA="));
+        assert_eq!(context.path_parameters["id"], "r_1");
+
+        assert!(body(request).await.is_empty());
+    }
+
+    #[test]
+    fn malformed_payloads_are_rejected() {
+        assert!(into_request(json!({ "source": "aws.events" })).is_err());
+
+        let mut bad_body = fixture("v1");
+        bad_body["body"] = json!("not base64!");
+        assert!(into_request(bad_body).is_err());
+
+        let mut bad_method = fixture("v2");
+        bad_method["requestContext"]["http"]["method"] = json!("GE T");
+        assert!(into_request(bad_method).is_err());
+    }
+
+    fn response(content_type: Option<&str>, body: &'static [u8]) -> Response<Body> {
+        let mut builder = Response::builder()
+            .status(StatusCode::CREATED)
+            .header(header::SET_COOKIE, "a=1; HttpOnly")
+            .header(header::SET_COOKIE, "b=2")
+            .header("x-multi", "one")
+            .header("x-multi", "two");
+        if let Some(content_type) = content_type {
+            builder = builder.header(header::CONTENT_TYPE, content_type);
+        }
+        builder.body(Body::from(body)).unwrap()
+    }
+
+    #[tokio::test]
+    async fn v1_responses_carry_single_and_multi_value_headers() {
+        let result = from_response(
+            response(Some("application/json"), br#"{"id":"r_1"}"#),
+            PayloadVersion::V1,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            result,
+            json!({
+                "statusCode": 201,
+                "headers": {
+                    "content-type": "application/json",
+                    "set-cookie": "a=1; HttpOnly,b=2",
+                    "x-multi": "one,two",
+                },
+                "multiValueHeaders": {
+                    "content-type": ["application/json"],
+                    "set-cookie": ["a=1; HttpOnly", "b=2"],
+                    "x-multi": ["one", "two"],
+                },
+                "body": r#"{"id":"r_1"}"#,
+                "isBase64Encoded": false,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn v2_responses_move_set_cookie_into_cookies() {
+        let result = from_response(
+            response(Some("image/png"), b"\x89PNG\r\n"),
+            PayloadVersion::V2,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            result,
+            json!({
+                "statusCode": 201,
+                "headers": {
+                    "content-type": "image/png",
+                    "x-multi": "one,two",
+                },
+                "cookies": ["a=1; HttpOnly", "b=2"],
+                "body": BASE64.encode(b"\x89PNG\r\n"),
+                "isBase64Encoded": true,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn events_round_trip_through_the_router() {
+        let router = Router::new().route(
+            "/v1/rfqs/:id/events",
+            get(|request: Request<Body>| async move {
+                let context = request.extensions().get::<ApiGatewayContext>().cloned();
+                axum::Json(json!({
+                    "query": request.uri().query(),
+                    "stage": context.and_then(|context| context.stage),
+                }))
+            }),
+        );
+
+        let event = LambdaEvent::new(fixture("v2"), lambda_runtime::Context::default());
+        let result = handle_event(router, event).await.unwrap();
+
+        assert_eq!(result["statusCode"], 200);
+        assert_eq!(result["isBase64Encoded"], false);
+        assert_eq!(result["cookies"], json!([]));
+        let body: Value = serde_json::from_str(result["body"].as_str().unwrap()).unwrap();
+        assert_eq!(
+            body,
+            json!({
+                "query": "since=2026-01-01T00%3A00%3A00Z&limit=5&tag=a+b",
+                "stage": "prod",
+            })
+        );
+    }
+
+    #[test]
+    fn stages_are_stripped_only_as_a_whole_first_segment() {
+        let cases = [
+            ("/prod/v1/rfqs", Some("prod"), "/v1/rfqs"),
+            ("/prod", Some("prod"), "/"),
+            ("/prod/", Some("prod"), "/"),
+            ("/production/v1", Some("prod"), "/production/v1"),
+            ("/v1/prod/rfqs", Some("prod"), "/v1/prod/rfqs"),
+            ("/v1/rfqs", Some("$default"), "/v1/rfqs"),
+            ("/v1/rfqs", None, "/v1/rfqs"),
+        ];
+        for (path, stage, expected) in cases {
+            assert_eq!(
+                strip_stage(path.to_string(), stage),
+                expected,
+                "{} {:?}",
+                path,
+                stage
+            );
+        }
+    }
+
+    #[test]
+    fn v1_query_strings_are_re_encoded() {
+        let cases = [
+            (json!({}), ""),
+            (
+                json!({ "queryStringParameters": { "q": "a b&c=d" } }),
+                "q=a+b%26c%3Dd",
+            ),
+            (
+                json!({
+                    "queryStringParameters": { "q": "2" },
+                    "multiValueQueryStringParameters": { "q": ["1", "2"] }
+                }),
+                "q=1&q=2",
+            ),
+            (
+                json!({ "queryStringParameters": { "since": "2026-01-01T00:00:00+01:00" } }),
+                "since=2026-01-01T00%3A00%3A00%2B01%3A00",
+            ),
+            (json!({ "queryStringParameters": null }), ""),
+        ];
+        for (payload, expected) in cases {
+            assert_eq!(v1_query_string(&payload), expected, "{}", payload);
+        }
+    }
+
+    #[test]
+    fn text_responses_are_told_from_binary_ones() {
+        let cases = [
+            (None, None, true),
+            (Some("application/json"), None, true),
+            (Some("Application/JSON; charset=utf-8"), None, true),
+            (Some("text/html"), None, true),
+            (Some("application/problem+json"), None, true),
+            (Some("application/atom+xml"), None, true),
+            (Some("application/javascript"), None, true),
+            (Some("image/png"), None, false),
+            (Some("application/octet-stream"), None, false),
+            (Some("application/json"), Some("gzip"), false),
+        ];
+        for (content_type, encoding, expected) in cases {
+            let mut headers = HeaderMap::new();
+            if let Some(content_type) = content_type {
+                headers.insert(header::CONTENT_TYPE, content_type.parse().unwrap());
+            }
+            if let Some(encoding) = encoding {
+                headers.insert(header::CONTENT_ENCODING, encoding.parse().unwrap());
+            }
+            assert_eq!(
+                is_text_response(&headers),
+                expected,
+                "{:?} {:?}",
+                content_type,
+                encoding
+            );
+        }
+    }
+}
@@ -1,4 +1,6 @@
+pub mod auth;
 pub mod error;
 pub mod handlers;
+pub mod lambda;
 pub mod middleware;
 pub mod validation;
@@ -4,7 +4,12 @@ use axum::{
     middleware::Next,
     response::Response,
 };
-use tower_http::cors::{Any, CorsLayer};
+use tower_http::{
+    classify::{ServerErrorsAsFailures, SharedClassifier},
+    cors::{Any, CorsLayer},
+    trace::TraceLayer,
+};
+use tracing::Span;
 use uuid::Uuid;
 
 /// Add request ID to all requests
@@ -41,3 +46,17 @@ pub fn cors_layer() -> CorsLayer {
         .allow_headers(Any) // Simplified for MVP - use specific headers in production
         .expose_headers(Any) // Simplified for MVP - use specific headers in production
 }
+
+/// HTTP tracing whose spans name the path only. Query strings can carry
+/// access tokens (`?token=`), which must not reach the logs.
+pub fn trace_layer() -> TraceLayer<SharedClassifier<ServerErrorsAsFailures>, fn(&Request) -> Span> {
+    TraceLayer::new_for_http().make_span_with(request_span as fn(&Request) -> Span)
+}
+
+fn request_span(request: &Request) -> Span {
+    tracing::debug_span!(
+        "request",
+        method = %request.method(),
+        path = %request.uri().path(),
+    )
+}
@@ -103,15 +103,35 @@ pub fn create_rfq_schema() -> Value {
     })
 }
 
+/// Same as an RFQ, but with 2-10 distinct manufacturers instead of one
+pub fn create_campaign_schema() -> Value {
+    let mut schema = create_rfq_schema();
+    let properties = schema["properties"]
+        .as_object_mut()
+        .expect("RFQ schema has properties");
+    let manufacturer_id = properties
+        .remove("manufacturer_id")
+        .expect("RFQ schema has manufacturer_id");
+    properties.insert(
+        "manufacturer_ids".to_string(),
+        serde_json::json!({
+            "type": "array",
+            "minItems": 2,
+            "maxItems": 10,
+            "uniqueItems": true,
+            "items": manufacturer_id
+        }),
+    );
+    schema["required"] =
+        serde_json::json!(["tenant_id", "manufacturer_ids", "buyer", "subject", "body"]);
+    schema
+}
+
 pub fn post_message_schema() -> Value {
     serde_json::json!({
         "type": "object",
-        "required": ["by", "body"],
+        "required": ["body"],
         "properties": {
-            "by": {
-                "type": "string",
-                "enum": ["buyer", "manufacturer"]
-            },
             "body": {
                 "type": "string",
                 "minLength": 1,
@@ -150,10 +170,155 @@ pub fn post_message_schema() -> Value {
     })
 }
 
+pub fn change_status_schema() -> Value {
+    serde_json::json!({
+        "type": "object",
+        "required": ["status"],
+        "properties": {
+            "status": {
+                "type": "string",
+                "enum": ["open", "closed", "archived"]
+            },
+            "note": {
+                "type": "string",
+                "maxLength": 8000
+            }
+        },
+        "additionalProperties": false
+    })
+}
+
+pub fn rfq_decision_schema() -> Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "reason": {
+                "type": "string",
+                "maxLength": 8000
+            },
+            "reference": {
+                "type": "object",
+                "required": ["kind", "id"],
+                "properties": {
+                    "kind": {
+                        "type": "string",
+                        "enum": ["message", "attachment", "quote"]
+                    },
+                    "id": {
+                        "type": "string",
+                        "minLength": 1
+                    }
+                },
+                "additionalProperties": false
+            }
+        },
+        "additionalProperties": false
+    })
+}
+
+pub fn submit_quote_schema() -> Value {
+    let money = serde_json::json!({
+        "type": "object",
+        "required": ["amount", "currency"],
+        "properties": {
+            "amount": {
+                "type": "string",
+                "pattern": "^[0-9]+(\\.[0-9]+)?$"
+            },
+            "currency": {
+                "type": "string",
+                "pattern": "^[A-Z]{3}$"
+            }
+        },
+        "additionalProperties": false
+    });
+
+    serde_json::json!({
+        "type": "object",
+        "required": ["line_items", "valid_until"],
+        "properties": {
+            "line_items": {
+                "type": "array",
+                "minItems": 1,
+                "maxItems": 100,
+                "items": {
+                    "type": "object",
+                    "required": ["part", "quantity", "unit_price"],
+                    "properties": {
+                        "part": {
+                            "type": "string",
+                            "minLength": 1,
+                            "maxLength": 200
+                        },
+                        "quantity": {
+                            "type": "object",
+                            "required": ["value", "unit"],
+                            "properties": {
+                                "value": {
+                                    "type": "string",
+                                    "pattern": "^[0-9]+(\\.[0-9]+)?$"
+                                },
+                                "unit": {
+                                    "type": "string",
+                                    "enum": ["pcs", "kg", "lbs", "m", "ft"]
+                                }
+                            },
+                            "additionalProperties": false
+                        },
+                        "unit_price": money.clone()
+                    },
+                    "additionalProperties": false
+                }
+            },
+            "charges": {
+                "type": "array",
+                "maxItems": 20,
+                "items": {
+                    "type": "object",
+                    "required": ["kind", "amount"],
+                    "properties": {
+                        "kind": {
+                            "type": "string",
+                            "enum": ["tooling", "nre"]
+                        },
+                        "description": {
+                            "type": "string",
+                            "maxLength": 500
+                        },
+                        "amount": money
+                    },
+                    "additionalProperties": false
+                }
+            },
+            "lead_time_days": {
+                "type": "object",
+                "properties": {
+                    "min": { "type": "integer", "minimum": 0 },
+                    "max": { "type": "integer", "minimum": 0 }
+                },
+                "additionalProperties": false
+            },
+            "incoterms": {
+                "type": "string",
+                "enum": ["EXW", "FCA", "CPT", "CIP", "DAP", "DPU", "DDP", "FAS", "FOB", "CFR", "CIF"]
+            },
+            "valid_until": {
+                "type": "string",
+                "format": "date"
+            },
+            "note": {
+                "type": "string",
+                "maxLength": 8000
+            }
+        },
+        "additionalProperties": false
+    })
+}
+
 pub fn presign_upload_schema() -> Value {
     serde_json::json!({
         "type": "object",
-        "required": ["tenant_id", "pathType", "content_type", "size_bytes"],
+        "required": ["pathType", "content_type", "size_bytes"],
         "properties": {
             "tenant_id": {
                 "type": "string",
@@ -161,6 +326,11 @@ pub fn presign_upload_schema() -> Value {
                 "minLength": 1,
                 "maxLength": 50
             },
+            "manufacturer_id": {
+                "type": "string",
+                "pattern": "^mfg_[a-zA-Z0-9_-]+$",
+                "maxLength": 50
+            },
             "pathType": {
                 "type": "string",
                 "enum": ["imageRaw"]
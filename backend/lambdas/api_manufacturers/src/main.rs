@@ -4,13 +4,24 @@ use std::sync::Arc;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 // Import the shared crates
+use application::access::AdminCredentials;
+use application::ports::{ApiKeyRepository, ManufacturerRepository};
+use application::services::{ApiKeyService, ManufacturerService};
 use aws_sdk_s3::Client as S3Client;
-use axum::{serve, Router};
-use infrastructure::config::Config;
-use infrastructure::s3::S3ManufacturerRepository;
-use presentation::handlers::ManufacturerHandlers;
+use axum::{serve, Extension, Router};
+use infrastructure::config::{Config, StorageBackend};
+use infrastructure::fs::{FsApiKeyRepository, FsManufacturerRepository, FsStore};
+use infrastructure::memory::{InMemoryApiKeyRepository, InMemoryManufacturerRepository};
+use infrastructure::s3::{S3ApiKeyRepository, S3ManufacturerRepository};
+use presentation::auth::Authenticator;
+use presentation::handlers::{ApiKeyHandlers, ManufacturerHandlers};
 use tokio::net::TcpListener;
 
+type Repositories = (
+    Arc<dyn ManufacturerRepository + Send + Sync>,
+    Arc<dyn ApiKeyRepository + Send + Sync>,
+);
+
 #[tokio::main]
 async fn main() -> Result<(), Error> {
     // Initialize tracing
@@ -21,51 +32,36 @@ async fn main() -> Result<(), Error> {
         .with(tracing_subscriber::fmt::layer().json())
         .init();
 
+    let config = Arc::new(Config::load().await?);
+    let admin_credentials = Arc::new(config.admin_credentials()?);
+
     if std::env::var("AWS_LAMBDA_FUNCTION_NAME").is_ok() {
-        lambda_runtime::run(service_fn(function_handler)).await
+        let app = create_app(create_repositories(config).await, admin_credentials);
+        lambda_runtime::run(service_fn(move |event: LambdaEvent<Value>| {
+            presentation::lambda::handle_event(app.clone(), event)
+        }))
+        .await
     } else {
-        local_server().await
+        local_server(config, admin_credentials).await
     }
 }
 
-async fn function_handler(event: LambdaEvent<Value>) -> Result<Value, Error> {
-    let (event, _context) = event.into_parts();
-
-    tracing::info!("Processing manufacturer API request: {:?}", event);
-
-    // Set up AWS clients
-    let app_config = Arc::new(Config::from_env());
-    let aws_config = app_config.create_aws_config().await;
-    let s3_client = S3Client::new(&aws_config);
-
-    // Create services
-    let _manufacturer_repo = Arc::new(S3ManufacturerRepository::new(s3_client, app_config.clone()));
-
-    // For now, return a success response indicating the service is ready
-    // Full implementation would parse the Lambda event and route to appropriate handler
-    Ok(serde_json::json!({
-        "statusCode": 200,
-        "headers": {
-            "Content-Type": "application/json",
-            "Access-Control-Allow-Origin": "*"
-        },
-        "body": serde_json::json!({
-            "message": "Manufacturer API service initialized successfully"
-        }).to_string()
-    }))
-}
-
-async fn local_server() -> Result<(), Error> {
+async fn local_server(
+    config: Arc<Config>,
+    admin_credentials: Arc<AdminCredentials>,
+) -> Result<(), Error> {
     tracing::info!("Starting manufacturer API server on http://0.0.0.0:3002");
 
-    // Set up AWS clients for local development
-    let app_config = Arc::new(Config::from_env());
-    let aws_config = app_config.create_aws_config().await;
-    let s3_client = S3Client::new(&aws_config);
-    let manufacturer_repo = Arc::new(S3ManufacturerRepository::new(s3_client, app_config.clone()));
-
-    // Create router
-    let app = Router::new().nest("/v1", ManufacturerHandlers::router(manufacturer_repo));
+    let repositories: Repositories = if std::env::args().any(|arg| arg == "--in-memory") {
+        tracing::info!("Using in-memory storage; data is lost on restart");
+        (
+            Arc::new(InMemoryManufacturerRepository::new()),
+            Arc::new(InMemoryApiKeyRepository::new()),
+        )
+    } else {
+        create_repositories(config).await
+    };
+    let app = create_app(repositories, admin_credentials);
 
     // Start server
     let listener = TcpListener::bind("0.0.0.0:3002").await.unwrap();
@@ -73,3 +69,52 @@ async fn local_server() -> Result<(), Error> {
 
     Ok(())
 }
+
+async fn create_repositories(app_config: Arc<Config>) -> Repositories {
+    match app_config.storage_backend {
+        StorageBackend::S3 => {
+            // Set up AWS clients
+            let aws_config = app_config.create_aws_config().await;
+            let s3_client = S3Client::new(&aws_config);
+            (
+                Arc::new(S3ManufacturerRepository::new(
+                    s3_client.clone(),
+                    app_config.clone(),
+                )),
+                Arc::new(S3ApiKeyRepository::new(s3_client, app_config.clone())),
+            )
+        }
+        StorageBackend::Fs => {
+            let store = FsStore::new(app_config.storage_root.clone());
+            (
+                Arc::new(FsManufacturerRepository::new(
+                    store.clone(),
+                    app_config.clone(),
+                )),
+                Arc::new(FsApiKeyRepository::new(store, app_config.clone())),
+            )
+        }
+    }
+}
+
+fn create_app(
+    (manufacturer_repo, api_key_repo): Repositories,
+    admin_credentials: Arc<AdminCredentials>,
+) -> Router {
+    let api_key_service = Arc::new(ApiKeyService::new(api_key_repo));
+    // Profile edits come from tenant API keys or admins
+    let authenticator = Authenticator::new()
+        .with_api_keys(api_key_service.clone())
+        .with_admin_credentials(admin_credentials.clone());
+
+    // Create router
+    Router::new().nest(
+        "/v1",
+        ManufacturerHandlers::router(
+            Arc::new(ManufacturerService::new(manufacturer_repo)),
+            admin_credentials.clone(),
+        )
+        .merge(ApiKeyHandlers::router(api_key_service, admin_credentials))
+        .layer(Extension(Arc::new(authenticator))),
+    )
+}
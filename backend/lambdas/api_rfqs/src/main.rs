@@ -1,17 +1,34 @@
-use application::services::RfqService;
+use application::{
+    access::AdminCredentials,
+    ports::{
+        ApiKeyRepository, IdempotencyService, ImageService, ManufacturerRepository, RfqRepository,
+    },
+    services::{ApiKeyService, ManufacturerService, RfqService, UploadService},
+};
 use aws_sdk_s3::Client as S3Client;
 use aws_sdk_sesv2::Client as SesClient;
+use axum::Router;
 use infrastructure::{
-    config::Config,
-    s3::{S3IdempotencyService, S3ManufacturerRepository, S3RfqRepository},
+    config::{Config, StorageBackend},
+    fs::{
+        FsApiKeyRepository, FsIdempotencyService, FsImageService, FsManufacturerRepository,
+        FsRfqRepository, FsStore,
+    },
+    memory::{
+        InMemoryApiKeyRepository, InMemoryEmailService, InMemoryIdempotencyService,
+        InMemoryImageService, InMemoryManufacturerRepository, InMemoryRfqRepository,
+    },
+    s3::{
+        S3ApiKeyRepository, S3IdempotencyService, S3ImageService, S3ManufacturerRepository,
+        S3RfqRepository,
+    },
     ses::SesEmailService,
 };
 use lambda_runtime::{service_fn, Error, LambdaEvent};
-use presentation::{handlers::create_app_router, middleware};
+use presentation::{auth::Authenticator, handlers::create_app_router, middleware};
 use serde_json::Value;
 use std::sync::Arc;
 use tower::ServiceBuilder;
-use tower_http::trace::TraceLayer;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 #[tokio::main]
@@ -26,90 +43,194 @@ async fn main() -> Result<(), Error> {
 
     if std::env::var("AWS_LAMBDA_FUNCTION_NAME").is_ok() {
         // Running on AWS Lambda
-        lambda_runtime::run(service_fn(function_handler)).await
+        let app = create_app(create_services().await?);
+        lambda_runtime::run(service_fn(move |event: LambdaEvent<Value>| {
+            presentation::lambda::handle_event(app.clone(), event)
+        }))
+        .await
     } else {
         // Running locally for development
         local_server().await
     }
 }
 
-async fn function_handler(event: LambdaEvent<Value>) -> Result<Value, Error> {
-    let (_event, _context) = event.into_parts();
-
-    // Create services
-    let (_rfq_service, _image_service, _manufacturer_repo) = create_services().await?;
-
-    // For now, return a success response
-    // Full Axum integration would require more complex lambda-http integration
-    Ok(serde_json::json!({
-        "statusCode": 200,
-        "headers": {
-            "Content-Type": "application/json",
-            "Access-Control-Allow-Origin": "*"
-        },
-        "body": serde_json::json!({
-            "message": "RFQ API service initialized successfully"
-        }).to_string()
-    }))
-}
-
 async fn local_server() -> Result<(), Error> {
     tracing::info!("Starting RFQ API server on http://0.0.0.0:3001");
 
-    let (rfq_service, image_service, manufacturer_repo) = create_services().await?;
+    let services = if std::env::args().any(|arg| arg == "--in-memory") {
+        tracing::info!("Using in-memory storage; data is lost on restart");
+        create_in_memory_services()?
+    } else {
+        create_services().await?
+    };
+    let app = create_app(services);
+
+    let listener = tokio::net::TcpListener::bind("0.0.0.0:3001").await?;
+    axum::serve(listener, app).await?;
 
-    let app = create_app_router(rfq_service, image_service, manufacturer_repo).layer(
+    Ok(())
+}
+
+type Services = (
+    Arc<RfqService>,
+    Arc<UploadService>,
+    Arc<ManufacturerService>,
+    Arc<ApiKeyService>,
+    Arc<Authenticator>,
+    Arc<AdminCredentials>,
+);
+
+type Storage = (
+    Arc<dyn RfqRepository + Send + Sync>,
+    Arc<dyn ManufacturerRepository + Send + Sync>,
+    Arc<dyn ImageService + Send + Sync>,
+    Arc<dyn IdempotencyService + Send + Sync>,
+    Arc<dyn ApiKeyRepository + Send + Sync>,
+);
+
+fn create_app(
+    (
+        rfq_service,
+        upload_service,
+        manufacturer_service,
+        api_key_service,
+        authenticator,
+        admin_credentials,
+    ): Services,
+) -> Router {
+    create_app_router(
+        rfq_service,
+        upload_service,
+        manufacturer_service,
+        api_key_service,
+        authenticator,
+        admin_credentials,
+    )
+    .layer(
         ServiceBuilder::new()
-            .layer(TraceLayer::new_for_http())
+            .layer(middleware::trace_layer())
             .layer(middleware::cors_layer())
             .layer(axum::middleware::from_fn(middleware::request_id_middleware)),
+    )
+}
+
+fn create_in_memory_services() -> Result<Services, Error> {
+    let rfq_repository = Arc::new(InMemoryRfqRepository::new());
+    let manufacturer_repository = Arc::new(InMemoryManufacturerRepository::new());
+    let image_service = Arc::new(InMemoryImageService::new());
+    let api_key_repository = Arc::new(InMemoryApiKeyRepository::new());
+    let config = Config::from_env();
+    let access_tokens = Arc::new(config.in_memory_access_tokens()?);
+    let admin_credentials = Arc::new(config.admin_credentials()?);
+
+    let rfq_service = RfqService::new(
+        rfq_repository.clone(),
+        manufacturer_repository.clone(),
+        Arc::new(InMemoryEmailService::new()),
+        Arc::new(InMemoryIdempotencyService::new()),
+        image_service.clone(),
+        access_tokens.clone(),
     );
 
-    let listener = tokio::net::TcpListener::bind("0.0.0.0:3001").await?;
-    axum::serve(listener, app).await?;
+    let api_key_service = Arc::new(ApiKeyService::new(api_key_repository));
+    let authenticator = Authenticator::new()
+        .with_access_tokens(access_tokens)
+        .with_api_keys(api_key_service.clone())
+        .with_admin_credentials(admin_credentials.clone());
 
-    Ok(())
+    Ok((
+        Arc::new(rfq_service),
+        Arc::new(UploadService::new(
+            image_service,
+            rfq_repository,
+            manufacturer_repository.clone(),
+        )),
+        Arc::new(ManufacturerService::new(manufacturer_repository)),
+        api_key_service,
+        Arc::new(authenticator),
+        admin_credentials,
+    ))
 }
 
-async fn create_services() -> Result<
-    (
-        Arc<RfqService>,
-        Arc<dyn application::ports::ImageService + Send + Sync>,
-        Arc<dyn application::ports::ManufacturerRepository + Send + Sync>,
-    ),
-    Error,
-> {
-    use application::ports::{ImageService, ManufacturerRepository};
-    use infrastructure::s3::S3ImageService;
+async fn create_services() -> Result<Services, Error> {
     // Create configuration and AWS clients
-    let config = Arc::new(Config::from_env());
+    let config = Arc::new(Config::load().await?);
     let aws_config = config.create_aws_config().await;
-    let s3_client = S3Client::new(&aws_config);
     let ses_client = SesClient::new(&aws_config);
 
     // Create repositories and services
-    let rfq_repository = Arc::new(S3RfqRepository::new(s3_client.clone(), config.clone()));
-    let manufacturer_repository = Arc::new(S3ManufacturerRepository::new(
-        s3_client.clone(),
-        config.clone(),
-    ));
-    let image_service = Arc::new(S3ImageService::new(s3_client.clone(), config.clone()));
-    let idempotency_service = Arc::new(S3IdempotencyService::new(s3_client, config.clone()));
+    let (
+        rfq_repository,
+        manufacturer_repository,
+        image_service,
+        idempotency_service,
+        api_key_repository,
+    ): Storage = match config.storage_backend {
+        StorageBackend::S3 => {
+            let s3_client = S3Client::new(&aws_config);
+            (
+                Arc::new(S3RfqRepository::new(s3_client.clone(), config.clone())),
+                Arc::new(S3ManufacturerRepository::new(
+                    s3_client.clone(),
+                    config.clone(),
+                )),
+                Arc::new(S3ImageService::new(s3_client.clone(), config.clone())),
+                Arc::new(S3IdempotencyService::new(s3_client.clone(), config.clone())),
+                Arc::new(S3ApiKeyRepository::new(s3_client, config.clone())),
+            )
+        }
+        StorageBackend::Fs => {
+            tracing::info!(
+                "Using filesystem storage under {}",
+                config.storage_root.display()
+            );
+            let store = FsStore::new(config.storage_root.clone());
+            (
+                Arc::new(FsRfqRepository::new(store.clone(), config.clone())),
+                Arc::new(FsManufacturerRepository::new(store.clone(), config.clone())),
+                Arc::new(FsImageService::new(store.clone(), config.clone())),
+                Arc::new(FsIdempotencyService::new(store.clone(), config.clone())),
+                Arc::new(FsApiKeyRepository::new(store, config.clone())),
+            )
+        }
+    };
 
     let from_email =
         std::env::var("FROM_EMAIL").unwrap_or_else(|_| "noreply@terra-platform.com".to_string());
-    let email_service = Arc::new(SesEmailService::new(ses_client, config, from_email));
+    let access_tokens = Arc::new(config.access_tokens()?);
+    let admin_credentials = Arc::new(config.admin_credentials()?);
+    let email_service = Arc::new(SesEmailService::new(
+        ses_client,
+        config,
+        from_email,
+        access_tokens.clone(),
+    ));
 
     let rfq_service = RfqService::new(
         rfq_repository.clone(),
         manufacturer_repository.clone(),
         email_service,
         idempotency_service,
+        image_service.clone(),
+        access_tokens.clone(),
     );
 
+    let api_key_service = Arc::new(ApiKeyService::new(api_key_repository));
+    let authenticator = Authenticator::new()
+        .with_access_tokens(access_tokens)
+        .with_api_keys(api_key_service.clone())
+        .with_admin_credentials(admin_credentials.clone());
+
     Ok((
         Arc::new(rfq_service),
-        image_service as Arc<dyn ImageService + Send + Sync>,
-        manufacturer_repository as Arc<dyn ManufacturerRepository + Send + Sync>,
+        Arc::new(UploadService::new(
+            image_service,
+            rfq_repository,
+            manufacturer_repository.clone(),
+        )),
+        Arc::new(ManufacturerService::new(manufacturer_repository)),
+        api_key_service,
+        Arc::new(authenticator),
+        admin_credentials,
     ))
 }
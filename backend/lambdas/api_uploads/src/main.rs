@@ -1,13 +1,30 @@
+use application::ports::{ApiKeyRepository, ImageService, ManufacturerRepository, RfqRepository};
+use application::services::{ApiKeyService, UploadService};
 use aws_sdk_s3::Client as S3Client;
-use infrastructure::{config::Config, s3::S3ImageService};
+use axum::{Extension, Router};
+use infrastructure::{
+    config::{Config, StorageBackend},
+    fs::{FsApiKeyRepository, FsImageService, FsManufacturerRepository, FsRfqRepository, FsStore},
+    memory::{
+        InMemoryApiKeyRepository, InMemoryImageService, InMemoryManufacturerRepository,
+        InMemoryRfqRepository,
+    },
+    s3::{S3ApiKeyRepository, S3ImageService, S3ManufacturerRepository, S3RfqRepository},
+};
 use lambda_runtime::{service_fn, Error, LambdaEvent};
-use presentation::{handlers::UploadHandlers, middleware};
+use presentation::{auth::Authenticator, handlers::UploadHandlers, middleware};
 use serde_json::Value;
 use std::sync::Arc;
 use tower::ServiceBuilder;
-use tower_http::trace::TraceLayer;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+type Storage = (
+    Arc<dyn ImageService + Send + Sync>,
+    Arc<dyn RfqRepository + Send + Sync>,
+    Arc<dyn ManufacturerRepository + Send + Sync>,
+    Arc<dyn ApiKeyRepository + Send + Sync>,
+);
+
 #[tokio::main]
 async fn main() -> Result<(), Error> {
     // Initialize tracing
@@ -19,59 +36,93 @@ async fn main() -> Result<(), Error> {
         .init();
 
     if std::env::var("AWS_LAMBDA_FUNCTION_NAME").is_ok() {
-        // Running on AWS Lambda
-        lambda_runtime::run(service_fn(function_handler)).await
+        // Running on AWS Lambda - API Gateway routes arrive under /v1
+        let config = Arc::new(Config::load().await?);
+        let app = Router::new().nest(
+            "/v1",
+            create_app(create_storage(config.clone()).await, &config)?,
+        );
+        lambda_runtime::run(service_fn(move |event: LambdaEvent<Value>| {
+            presentation::lambda::handle_event(app.clone(), event)
+        }))
+        .await
     } else {
         // Running locally for development
         local_server().await
     }
 }
 
-async fn function_handler(event: LambdaEvent<Value>) -> Result<Value, Error> {
-    let (_event, _context) = event.into_parts();
+async fn local_server() -> Result<(), Error> {
+    tracing::info!("Starting uploads API server on http://0.0.0.0:3000");
+
+    let config = Arc::new(Config::load().await?);
 
-    // Create AWS clients
-    let config = Arc::new(Config::from_env());
-    let aws_config = config.create_aws_config().await;
-    let s3_client = S3Client::new(&aws_config);
+    let storage: Storage = if std::env::args().any(|arg| arg == "--in-memory") {
+        tracing::info!("Using in-memory storage; data is lost on restart");
+        (
+            Arc::new(InMemoryImageService::new()),
+            Arc::new(InMemoryRfqRepository::new()),
+            Arc::new(InMemoryManufacturerRepository::new()),
+            Arc::new(InMemoryApiKeyRepository::new()),
+        )
+    } else {
+        create_storage(config.clone()).await
+    };
+    let app = create_app(storage, &config)?;
 
-    // Create image service
-    let _image_service = Arc::new(S3ImageService::new(s3_client, config));
+    let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await?;
+    axum::serve(listener, app).await?;
 
-    // For now, return a success response
-    // Full Axum integration would require more complex lambda-http integration
-    Ok(serde_json::json!({
-        "statusCode": 200,
-        "headers": {
-            "Content-Type": "application/json",
-            "Access-Control-Allow-Origin": "*"
-        },
-        "body": serde_json::json!({
-            "message": "Upload API service initialized successfully"
-        }).to_string()
-    }))
+    Ok(())
 }
 
-async fn local_server() -> Result<(), Error> {
-    tracing::info!("Starting uploads API server on http://0.0.0.0:3000");
+async fn create_storage(config: Arc<Config>) -> Storage {
+    match config.storage_backend {
+        StorageBackend::S3 => {
+            // Create AWS clients
+            let aws_config = config.create_aws_config().await;
+            let s3_client = S3Client::new(&aws_config);
+            (
+                Arc::new(S3ImageService::new(s3_client.clone(), config.clone())),
+                Arc::new(S3RfqRepository::new(s3_client.clone(), config.clone())),
+                Arc::new(S3ManufacturerRepository::new(
+                    s3_client.clone(),
+                    config.clone(),
+                )),
+                Arc::new(S3ApiKeyRepository::new(s3_client, config)),
+            )
+        }
+        StorageBackend::Fs => {
+            let store = FsStore::new(config.storage_root.clone());
+            (
+                Arc::new(FsImageService::new(store.clone(), config.clone())),
+                Arc::new(FsRfqRepository::new(store.clone(), config.clone())),
+                Arc::new(FsManufacturerRepository::new(store.clone(), config.clone())),
+                Arc::new(FsApiKeyRepository::new(store, config)),
+            )
+        }
+    }
+}
 
-    // Create AWS clients for local development
-    let config = Arc::new(Config::from_env());
-    let aws_config = config.create_aws_config().await;
-    let s3_client = S3Client::new(&aws_config);
-    let image_service = Arc::new(S3ImageService::new(s3_client, config));
+fn create_app(
+    (image_service, rfq_repository, manufacturer_repository, api_key_repository): Storage,
+    config: &Config,
+) -> Result<Router, Error> {
+    // Uploads go to the tenant the caller's credentials are bound to; callers
+    // without any name the manufacturer they are writing to instead
+    let upload_service = UploadService::new(image_service, rfq_repository, manufacturer_repository);
+    let authenticator = Authenticator::new()
+        .with_access_tokens(Arc::new(config.access_tokens()?))
+        .with_api_keys(Arc::new(ApiKeyService::new(api_key_repository)))
+        .with_admin_credentials(Arc::new(config.admin_credentials()?));
 
-    let app = axum::Router::new()
-        .merge(UploadHandlers::router(image_service))
+    Ok(Router::new()
+        .merge(UploadHandlers::router(Arc::new(upload_service)))
+        .layer(Extension(Arc::new(authenticator)))
         .layer(
             ServiceBuilder::new()
-                .layer(TraceLayer::new_for_http())
+                .layer(middleware::trace_layer())
                 .layer(middleware::cors_layer())
                 .layer(axum::middleware::from_fn(middleware::request_id_middleware)),
-        );
-
-    let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await?;
-    axum::serve(listener, app).await?;
-
-    Ok(())
+        ))
 }
@@ -0,0 +1,272 @@
+use anyhow::{anyhow, bail, Context};
+use application::ports::{RfqReplay, RfqRepository};
+use aws_sdk_s3::Client as S3Client;
+use chrono::{Duration, Utc};
+use domain::value_objects::RfqId;
+use infrastructure::{
+    config::{Config, StorageBackend},
+    fs::{FsRfqRepository, FsStore},
+    s3::{CompactionPolicy, S3RfqRepository},
+};
+use serde_json::Value;
+use std::sync::Arc;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+const USAGE: &str = "\
+Usage: maintenance <command> [options]
+
+Commands:
+  compact-events    Fold older RFQ event objects into JSONL segments
+      --rfq <id>              Only this RFQ (repeatable; default: all RFQs)
+      --older-than-hours <n>  Only compact events older than this (default: 24)
+      --min-events <n>        Skip RFQs with fewer eligible events (default: 50)
+      --max-events <n>        Upper bound on events per segment (default: 1000)
+  replay-rfqs       Rebuild RFQ meta, index and inbox entry from events and report drift
+      --rfq <id>              Only this RFQ (repeatable; default: all RFQs)
+      --apply                 Write the rebuilt documents where they differ
+  index-inbox       Write the listing markers of inbox entries saved before inboxes were paged
+";
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::EnvFilter::new(
+            std::env::var("RUST_LOG").unwrap_or_else(|_| "info".into()),
+        ))
+        .with(tracing_subscriber::fmt::layer())
+        .init();
+
+    let mut args = std::env::args().skip(1);
+    match args.next().as_deref() {
+        Some("compact-events") => compact_events(args.collect()).await,
+        Some("replay-rfqs") => replay_rfqs(args.collect()).await,
+        Some("index-inbox") => index_inbox(args.collect()).await,
+        Some("help" | "--help" | "-h") => {
+            print!("{}", USAGE);
+            Ok(())
+        }
+        Some(other) => bail!("Unknown command {:?}\n\n{}", other, USAGE),
+        None => bail!("Missing command\n\n{}", USAGE),
+    }
+}
+
+async fn compact_events(args: Vec<String>) -> anyhow::Result<()> {
+    let mut rfq_ids = Vec::new();
+    let mut older_than_hours = 24;
+    let mut policy = CompactionPolicy {
+        older_than: Utc::now(),
+        min_events: 50,
+        max_events: 1000,
+    };
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| anyhow!("Missing value for {}", arg))
+        };
+        match arg.as_str() {
+            "--rfq" => rfq_ids.push(parse_rfq_id(value()?)?),
+            "--older-than-hours" => older_than_hours = parse(&arg, value()?)?,
+            "--min-events" => policy.min_events = parse(&arg, value()?)?,
+            "--max-events" => policy.max_events = parse(&arg, value()?)?,
+            other => bail!("Unknown option {:?}\n\n{}", other, USAGE),
+        }
+    }
+    if policy.max_events == 0 {
+        bail!("--max-events must be at least 1");
+    }
+    policy.older_than = Utc::now() - Duration::hours(older_than_hours);
+
+    let repository = s3_rfq_repository().await?;
+    if rfq_ids.is_empty() {
+        rfq_ids = repository.list_rfq_ids().await?;
+    }
+
+    let mut failures = 0;
+    for rfq_id in &rfq_ids {
+        // One RFQ failing leaves it as it was; carry on with the rest
+        match repository.compact_events(rfq_id, &policy).await {
+            Ok(report) => tracing::info!(
+                rfq_id = rfq_id.as_str(),
+                segment = report.segment.as_ref().map(|s| s.key.as_str()),
+                events = report.segment.as_ref().map(|s| s.count),
+                deleted_events = report.deleted_events,
+                "Compacted RFQ events"
+            ),
+            Err(e) => {
+                failures += 1;
+                tracing::error!(rfq_id = rfq_id.as_str(), "Compaction failed: {}", e);
+            }
+        }
+    }
+
+    if failures > 0 {
+        bail!(
+            "Compaction failed for {} of {} RFQs",
+            failures,
+            rfq_ids.len()
+        );
+    }
+    Ok(())
+}
+
+async fn index_inbox(args: Vec<String>) -> anyhow::Result<()> {
+    if let Some(other) = args.first() {
+        bail!("Unknown option {:?}\n\n{}", other, USAGE);
+    }
+
+    let written = s3_rfq_repository().await?.index_inbox().await?;
+    println!("Indexed {} inbox entries", written);
+    Ok(())
+}
+
+async fn replay_rfqs(args: Vec<String>) -> anyhow::Result<()> {
+    let mut rfq_ids = Vec::new();
+    let mut apply = false;
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--rfq" => {
+                let id = args
+                    .next()
+                    .ok_or_else(|| anyhow!("Missing value for {}", arg))?;
+                rfq_ids.push(parse_rfq_id(id)?);
+            }
+            "--apply" => apply = true,
+            other => bail!("Unknown option {:?}\n\n{}", other, USAGE),
+        }
+    }
+
+    let config = Arc::new(Config::from_env());
+    let repository: Arc<dyn RfqRepository + Send + Sync> = match config.storage_backend {
+        StorageBackend::S3 => {
+            let repository = s3_rfq_repository().await?;
+            if rfq_ids.is_empty() {
+                rfq_ids = repository.list_rfq_ids().await?;
+            }
+            Arc::new(repository)
+        }
+        StorageBackend::Fs => {
+            let store = FsStore::new(config.storage_root.clone());
+            let repository = FsRfqRepository::new(store, config.clone());
+            if rfq_ids.is_empty() {
+                rfq_ids = repository.list_rfq_ids().await?;
+            }
+            Arc::new(repository)
+        }
+    };
+
+    let (mut drifted, mut failures) = (0, 0);
+    for rfq_id in &rfq_ids {
+        let replay = match repository.replay_rfq(rfq_id).await {
+            Ok(Some(replay)) => replay,
+            Ok(None) => {
+                tracing::warn!(rfq_id = rfq_id.as_str(), "No meta.json; skipping");
+                continue;
+            }
+            Err(e) => {
+                failures += 1;
+                tracing::error!(rfq_id = rfq_id.as_str(), "Replay failed: {}", e);
+                continue;
+            }
+        };
+        if !replay.has_drift() {
+            continue;
+        }
+
+        drifted += 1;
+        print_drift(rfq_id, &replay);
+        if apply {
+            if let Err(e) = replay.repair(repository.as_ref()).await {
+                failures += 1;
+                tracing::error!(rfq_id = rfq_id.as_str(), "Repair failed: {}", e);
+            }
+        }
+    }
+
+    println!(
+        "{} of {} RFQs differ from their events{}",
+        drifted,
+        rfq_ids.len(),
+        if apply { " (repaired)" } else { "" }
+    );
+    if failures > 0 {
+        bail!("Replay failed for {} RFQs", failures);
+    }
+    Ok(())
+}
+
+/// Print each top-level field where the stored value and the replay disagree
+fn print_drift(rfq_id: &RfqId, replay: &RfqReplay) {
+    let stored_index = replay.stored_index.as_ref().map(|index| &index.value);
+    let stored_inbox = replay.stored_inbox.as_ref().map(|inbox| &inbox.value);
+    let documents = [
+        (
+            "meta",
+            serde_json::to_value(&replay.stored_meta.value),
+            serde_json::to_value(&replay.meta),
+        ),
+        (
+            "index",
+            serde_json::to_value(stored_index),
+            serde_json::to_value(&replay.index),
+        ),
+        (
+            "inbox",
+            serde_json::to_value(stored_inbox),
+            serde_json::to_value(&replay.inbox),
+        ),
+    ];
+
+    for (name, stored, replayed) in documents {
+        let (Ok(stored), Ok(replayed)) = (stored, replayed) else {
+            continue;
+        };
+        let empty = serde_json::Map::new();
+        let stored_fields = stored.as_object().unwrap_or(&empty);
+        let replayed_fields = replayed.as_object().unwrap_or(&empty);
+
+        let mut fields: Vec<&String> = stored_fields.keys().chain(replayed_fields.keys()).collect();
+        fields.sort();
+        fields.dedup();
+        for field in fields {
+            let stored = stored_fields.get(field).unwrap_or(&Value::Null);
+            let replayed = replayed_fields.get(field).unwrap_or(&Value::Null);
+            if stored != replayed {
+                println!(
+                    "{} {}.{}: stored {} != replayed {}",
+                    rfq_id.as_str(),
+                    name,
+                    field,
+                    stored,
+                    replayed
+                );
+            }
+        }
+    }
+}
+
+async fn s3_rfq_repository() -> anyhow::Result<S3RfqRepository> {
+    let config = Arc::new(Config::from_env());
+    if config.storage_backend != StorageBackend::S3 {
+        bail!("Event segments are only used by the S3 storage backend");
+    }
+
+    let aws_config = config.create_aws_config().await;
+    Ok(S3RfqRepository::new(S3Client::new(&aws_config), config))
+}
+
+fn parse_rfq_id(id: String) -> anyhow::Result<RfqId> {
+    RfqId::new(id.clone()).map_err(|e| anyhow!("{}: {}", id, e))
+}
+
+fn parse<T: std::str::FromStr>(option: &str, value: String) -> anyhow::Result<T>
+where
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    value
+        .parse()
+        .with_context(|| format!("Invalid value {:?} for {}", value, option))
+}
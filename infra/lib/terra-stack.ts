@@ -5,6 +5,7 @@ import * as apigateway from 'aws-cdk-lib/aws-apigateway';
 import * as lambda from 'aws-cdk-lib/aws-lambda';
 import * as iam from 'aws-cdk-lib/aws-iam';
 import * as ses from 'aws-cdk-lib/aws-ses';
+import * as secretsmanager from 'aws-cdk-lib/aws-secretsmanager';
 import * as cloudfront from 'aws-cdk-lib/aws-cloudfront';
 import * as origins from 'aws-cdk-lib/aws-cloudfront-origins';
 import { Construct } from 'constructs';
@@ -114,6 +115,17 @@ export class TerraStack extends cdk.Stack {
       },
     });
 
+    // Key for signing RFQ access links; every api_rfqs instance must share it
+    const accessTokenSecret = new secretsmanager.Secret(this, 'AccessTokenSecret', {
+      generateSecretString: { passwordLength: 64, excludePunctuation: true },
+    });
+
+    // Static bearer secrets for admin routes, comma separated so a new key can
+    // be added before the old one is removed
+    const adminApiKeys = new secretsmanager.Secret(this, 'AdminApiKeys', {
+      generateSecretString: { passwordLength: 64, excludePunctuation: true },
+    });
+
     // Lambda functions (simplified for MVP - would build from Rust binaries in production)
     const apiRfqsLambda = new lambda.Function(this, 'ApiRfqsLambda', {
       runtime: lambda.Runtime.PROVIDED_AL2,
@@ -128,6 +140,8 @@ export class TerraStack extends cdk.Stack {
         PUBLIC_BUCKET: publicBucket.bucketName,
         PRIVATE_BUCKET: privateBucket.bucketName,
         FROM_EMAIL: 'noreply@terra-platform.com',
+        ACCESS_TOKEN_SECRET_ARN: accessTokenSecret.secretArn,
+        ADMIN_API_KEYS_SECRET_ARN: adminApiKeys.secretArn,
         RUST_LOG: 'info',
       },
     });
@@ -144,6 +158,8 @@ export class TerraStack extends cdk.Stack {
         ENVIRONMENT: environment,
         PUBLIC_BUCKET: publicBucket.bucketName,
         PRIVATE_BUCKET: privateBucket.bucketName,
+        ACCESS_TOKEN_SECRET_ARN: accessTokenSecret.secretArn,
+        ADMIN_API_KEYS_SECRET_ARN: adminApiKeys.secretArn,
         RUST_LOG: 'info',
       },
     });
@@ -160,10 +176,20 @@ export class TerraStack extends cdk.Stack {
         ENVIRONMENT: environment,
         PUBLIC_BUCKET: publicBucket.bucketName,
         PRIVATE_BUCKET: privateBucket.bucketName,
+        ADMIN_API_KEYS_SECRET_ARN: adminApiKeys.secretArn,
         RUST_LOG: 'info',
       },
     });
 
+    // The Lambdas read these secrets at cold start, so the values stay out
+    // of the template and the function configuration
+    for (const fn of [apiRfqsLambda, apiUploadsLambda]) {
+      accessTokenSecret.grantRead(fn);
+    }
+    for (const fn of [apiRfqsLambda, apiUploadsLambda, apiManufacturersLambda]) {
+      adminApiKeys.grantRead(fn);
+    }
+
     // Publisher Lambda for generating static HTML and JSON
     const publisherLambda = new lambda.Function(this, 'PublisherLambda', {
       runtime: lambda.Runtime.PROVIDED_AL2,
@@ -235,15 +261,57 @@ export class TerraStack extends cdk.Stack {
     const rfqMessages = rfqById.addResource('messages');
     rfqMessages.addMethod('POST', new apigateway.LambdaIntegration(apiRfqsLambda));
 
+    const rfqStatus = rfqById.addResource('status');
+    rfqStatus.addMethod('POST', new apigateway.LambdaIntegration(apiRfqsLambda));
+
+    const rfqAttachment = rfqById.addResource('attachments').addResource('{attachment_id}');
+    rfqAttachment.addMethod('GET', new apigateway.LambdaIntegration(apiRfqsLambda));
+
+    const rfqQuotes = rfqById.addResource('quotes');
+    rfqQuotes.addMethod('POST', new apigateway.LambdaIntegration(apiRfqsLambda));
+
+    const rfqAward = rfqById.addResource('award');
+    rfqAward.addMethod('POST', new apigateway.LambdaIntegration(apiRfqsLambda));
+
+    const rfqDecline = rfqById.addResource('decline');
+    rfqDecline.addMethod('POST', new apigateway.LambdaIntegration(apiRfqsLambda));
+
+    // Campaign endpoints
+    const campaigns = v1.addResource('campaigns');
+    campaigns.addMethod('POST', new apigateway.LambdaIntegration(apiRfqsLambda));
+
+    const campaignById = campaigns.addResource('{id}');
+    campaignById.addMethod('GET', new apigateway.LambdaIntegration(apiRfqsLambda));
+
     // Upload endpoints
     const uploads = v1.addResource('uploads');
     const presign = uploads.addResource('presign');
     presign.addMethod('POST', new apigateway.LambdaIntegration(apiUploadsLambda));
 
-    // Manufacturer endpoints (admin)
+    // Manufacturer endpoints (admin, or the tenant's API key for its own profile)
     const manufacturers = v1.addResource('manufacturers');
     manufacturers.addMethod('POST', new apigateway.LambdaIntegration(apiManufacturersLambda));
 
+    const manufacturerById = manufacturers.addResource('{id}');
+    manufacturerById.addMethod('PUT', new apigateway.LambdaIntegration(apiManufacturersLambda));
+
+    // Manufacturer RFQ inbox, served by the RFQ API
+    const inbox = manufacturerById.addResource('rfqs');
+    inbox.addMethod('GET', new apigateway.LambdaIntegration(apiRfqsLambda));
+    inbox
+      .addResource('{rfq_id}')
+      .addResource('read')
+      .addMethod('POST', new apigateway.LambdaIntegration(apiRfqsLambda));
+
+    // Tenant API key management (admin)
+    const tenantApiKeys = v1.addResource('tenants').addResource('{tenant_id}').addResource('api-keys');
+    tenantApiKeys.addMethod('GET', new apigateway.LambdaIntegration(apiManufacturersLambda));
+    tenantApiKeys.addMethod('POST', new apigateway.LambdaIntegration(apiManufacturersLambda));
+    tenantApiKeys
+      .addResource('{key_id}')
+      .addResource('revoke')
+      .addMethod('POST', new apigateway.LambdaIntegration(apiManufacturersLambda));
+
     // Add a manual trigger for the publisher Lambda (for demo purposes)
     const publisherTrigger = new lambda.Function(this, 'PublisherTriggerLambda', {
       runtime: lambda.Runtime.NODEJS_18_X,